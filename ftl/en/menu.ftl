@@ -1,20 +1,89 @@
 about = About
 calculation = Calculation
+cancel = Cancel
+comparison = Comparison
+comparison-comparative_major_number = Comparative major number (the samples where the value survives the filter)
+    .abbreviation = CMN
+comparison-difference = Difference
+    .hover = Value - reference value
+comparison-fold_change = Fold change
+    .hover = Value / reference value
+comparison-group = Group by the comparative major number
+comparison-keys = Keys
+comparison-major = Major in
+comparison-reference = Reference
+    .hover = Reference sample of the differences and the fold changes
+comparison-value = Value
+    .hover = Value of the most detailed composition (zero, if absent)
 composition = Composition
 configuration = Configuration
+copy = Copy
+correction_factor = Factor
+correction_factors = Correction factors
 create = Create
+delete = Delete
+diagnostics = Mass balance diagnostics
+diagnostics-balance = Balance
+    .hover = 3·TAG + MAG2 - 4·DAG1223 (zero for consistent data)
+diagnostics-clipped = Clipped
+    .hover = Theoretical values that were negative and replaced with zeros
+diagnostics-goodness_of_fit = Goodness of fit
+    .hover = 1 - ∑|3·TAG + MAG2 - 4·DAG1223| / ∑(3·TAG + MAG2 + 4·DAG1223)
+diagnostics-residual = Residual
+    .hover = Experimental - theoretical
+diagnostics-root_mean_square = Root mean square
+    .hover = Root mean square of the balance
+diagnostics-tolerance = Tolerance
+    .hover = Highlight the values with the absolute balance greater than the tolerance
 edit = Edit
+exclude_replicate = Exclude the replicate of the fatty acid from the means
+export = Export
+export_formatted = Formatted
+    .hover = Apply the percent and precision settings to the exported values
+export_workbook = Export open panes to a workbook
 grid = Grid
+github-cached = Cached configs
+github-clear_cache = Clear cache
+github-refresh = Refresh
+github-search = Search
+github-source = Source
+    .hover = Git tree JSON endpoint (`.../git/trees/{"{"}tree_sha{"}"}?recursive=true`) or local directory
 horizontal = Horizontal
+import = Import
+inverse = Inverse fit
+    .hover = sn-1,3 and sn-2 distributions fitted to the measured species by constrained least squares (1,3-random-2-random)
+inverse-configuration = Send as configuration
+    .hover = Add the synthetic configuration of the fitted distributions to the data
+inverse-fitted = Fitted
+inverse-measured = Measured
+inverse-residual = Residual
+inverse-species = Species
+inverse-unmatched = Keys without the labels of the frame
 language = Language
 left_panel = Left panel
 list = List
 load = Load
+models = Distribution models
+models-chi_square = Chi-square of the model to the measured species
+models-measured = Measured species
+    .hover = Measured species profile (key and value rows), the keys as in the first composition
+models-root_mean_square = Root mean square error
+    .abbreviation = RMSE
+models-similarity = Similarity index (1 - ∑|measured - model| / 2)
+    .abbreviation = SI
+models-unexpected = Measured species absent in the model (the chi-square is infinite)
+name = Name
+open = Open (data, project or saved result)
+outliers = Replicate outliers (robust z-scores)
+read_only = Read-only saved result
+replicate = Replicate
 reset_application = Reset application
 reset_gui = Reset GUI
 reset_table = Reset table state
 resize_table = Resize table columns
 save = Save
+save_project = Save project
 settings = Settings
 tabs = Tabs
-vertical = Vertical
\ No newline at end of file
+tukey = Tukey HSD
+vertical = Vertical
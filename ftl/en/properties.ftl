@@ -16,5 +16,6 @@ configure = configure
 
 key = key
 value = value
+propagated = propagated uncertainty
 
 -fatty_acid_term = fatty acid
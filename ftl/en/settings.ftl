@@ -1,9 +1,13 @@
 ## Settings
 
+settings-copy_headers = Copy headers
+    .hover = Include the header rows when copying the selected cells (Ctrl+C)
 settings-percent = Percent
     .hover = Display of values in percent
 settings-precision = Precision
     .hover = Number of decimal pieces
+settings-separate_standard_deviations = Separate ±
+    .hover = Copy standard deviations to separate columns
 settings-sticky_columns = Sticky
     .hover = Number of sticky columns
 settings-truncate_headers = Truncate
@@ -19,6 +23,10 @@ settings-properties = Properties
 ## Calculation
 
 settings-as_is = As is
+settings-ethyl_ester = Ethyl esters
+settings-fatty_acid = Fatty acids
+settings-form = Form
+    .hover = Form of the fatty acid mass (M)
 settings-fraction = Fraction
     .hover = Method of calculation of component fraction
 settings-from = Calculate
@@ -27,6 +35,8 @@ settings-from_dag = from 1,2/2,3-DAGs
     .hover = Calculate 1,3-DAGs from 1,2/2,3-DAGs
 settings-from_mag = from 2-{ monoacylglycerol.abbreviation }s
     .hover = Calculate 1,3-DAGs from 2-MAGs
+settings-methyl_ester = Methyl esters
+settings-pchelkin = Pchelkin
 settings-sign = Sign
 settings-signed = Signed
     .hover = theoretically calculated negative values are as is
@@ -37,8 +47,23 @@ settings-unsigned = Unsigned
 settings-normalize = Normalize
     .hover_experimental = Experimental
     .hover_theoretical = Theoretical
-settings-christie = Christie
-    .hover = Christy coefficients
+settings-correction_factors = Correction factors
+    .hover = Multiply the experimental values by the fatty acid correction (response) factors
+    .modified = The set was changed in the library, select it again to apply the changes
+settings-none = None
+settings-internal_standard = Internal standard
+    .hover = Configuration row of the internal standard added to the sample (for example 17:0 or 19:0)
+    .disabled = The internal standard requires the molar values (to mole fraction or Pchelkin fraction)
+settings-standard_amount = Standard amount
+    .hover = Amount of the internal standard fatty acid added to the sample
+settings-sample_mass = Sample mass
+    .hover = Mass of the analyzed sample
+settings-unit = Unit
+    .hover = Unit of the values
+    .disabled = Absolute units require the internal standard, its amount, the sample mass and the molar values
+settings-relative = Relative
+settings-micromole_per_gram = µmol/g
+settings-milligram_per_gram = mg/g
 settings-show = Show
 settings-ddof = DDOF
     .hover = Means Delta Degrees of Freedom. The divisor used in calculations is
@@ -46,6 +71,23 @@ settings-ddof = DDOF
     zero.
     .info = Different values of the argument ddof are useful in
     different contexts. 
+settings-dispersion = Dispersion
+    .hover = Measure of the dispersion shown after the mean
+settings-standard_deviation = Standard deviation
+    .hover = Sample standard deviation (SD)
+settings-standard_error = Standard error
+    .hover = Standard error of the mean (SEM = SD / √N)
+settings-confidence_interval = Confidence interval
+    .hover = Half width of the confidence interval of the mean (Student's t)
+settings-range = Range
+    .hover = Minimum and maximum of the replicates
+settings-confidence_level = Confidence level
+settings-outliers = Outliers
+    .hover = Screen the experimental values of the replicates by the robust z-score on the median and the median absolute deviation
+settings-outlier_threshold = Threshold
+    .hover = Robust z-score above which the replicate value is flagged
+settings-excluded = Excluded
+    .hover = Restore all excluded replicates
 
 ## Composition
 
@@ -55,6 +97,17 @@ settings-gunstone = Gunstone
     .hover = Calculate by Gunstone's theory
 settings-vander_wal = Vander Wal
     .hover = Calculate by Vander Wal's theory
+settings-random = 1,2,3-random
+    .hover = Calculate by the 1,2,3-random distribution of the fatty acids
+settings-stereospecific = 1-random-2-random-3-random
+    .hover = Calculate by the independent random distributions in sn-1, sn-2 and sn-3 (sn-3 = 2·sn-1,3 - sn-1)
+settings-restricted_random = Restricted random
+    .hover = Calculate by Kartha's restricted random theory (1,2,3-random with the limited trisaturated species)
+settings-stereospecific_number1 = sn-1
+    .hover = Measured sn-1 profile (label and value rows), sn-1,3 is used for sn-1 if empty
+settings-stereospecific_number1-unmatched = Labels of the profile without the labels of the frame
+settings-restriction = Trisaturated limit
+    .hover = Maximum fraction of the trisaturated species, the excess exchanges the fatty acids with the triunsaturated species
 settings-group = Group
 settings-sort = Sort
 settings-by_key = Key
@@ -72,7 +125,14 @@ settings-round_mass = Round mass
     .hover = 
 settings-view = View
 settings-show_filtered = Show filtered
-    .hover = Show filtered values
+    .hover = Show filtered values (dimmed)
+settings-statistic = Statistic
+settings-groups = Groups
+    .hover = Assign the frames to named groups to test the differences between the groups (Welch t-test for two groups, one-way ANOVA and Tukey HSD for more)
+settings-propagate = Propagate uncertainty
+    .hover = Report the uncertainty propagated from the sn-1(3) and sn-2 fractions (first order, the species of a group and the positions of a fatty acid are correlated)
+settings-measurement_error = Measurement error
+    .hover = Relative standard uncertainty of a single measurement of the sn fractions
 settings-filter = { $case ->
     *[title] Filter
     [lower] filter
@@ -1,20 +1,89 @@
 about = О программе
 calculation = Вычисления
+cancel = Отмена
+comparison = Сравнение
+comparison-comparative_major_number = Сравнительное мажорное число (образцы, в которых значение не отфильтровано)
+    .abbreviation = СМЧ
+comparison-difference = Разность
+    .hover = Значение - значение образца сравнения
+comparison-fold_change = Кратность
+    .hover = Значение / значение образца сравнения
+comparison-group = Группировать по сравнительному мажорному числу
+comparison-keys = Ключи
+comparison-major = Мажорное в
+comparison-reference = Образец сравнения
+    .hover = Образец сравнения для разностей и кратностей
+comparison-value = Значение
+    .hover = Значение самой подробной композиции (ноль, если отсутствует)
 composition = Композиция
 configuration = Конфигурация
+copy = Копировать
+correction_factor = Коэффициент
+correction_factors = Поправочные коэффициенты
 create = Создать
+delete = Удалить
+diagnostics = Диагностика баланса масс
+diagnostics-balance = Баланс
+    .hover = 3·ТАГ + МАГ2 - 4·ДАГ1223 (ноль для согласованных данных)
+diagnostics-clipped = Обнулено
+    .hover = Теоретические значения, которые были отрицательными и заменены нулем
+diagnostics-goodness_of_fit = Согласованность
+    .hover = 1 - ∑|3·ТАГ + МАГ2 - 4·ДАГ1223| / ∑(3·ТАГ + МАГ2 + 4·ДАГ1223)
+diagnostics-residual = Невязка
+    .hover = Экспериментальное - теоретическое
+diagnostics-root_mean_square = Среднеквадратичное
+    .hover = Среднеквадратичное значение баланса
+diagnostics-tolerance = Допуск
+    .hover = Выделять значения с абсолютным балансом больше допуска
 edit = Редактировать
+exclude_replicate = Исключить повторность жирной кислоты из средних
+export = Экспорт
+export_formatted = Форматировать
+    .hover = Применить настройки процентов и точности к экспортируемым значениям
+export_workbook = Экспортировать открытые панели в книгу
 grid = Сетка
+github-cached = Кэшированные конфигурации
+github-clear_cache = Очистить кэш
+github-refresh = Обновить
+github-search = Поиск
+github-source = Источник
+    .hover = JSON-ресурс дерева Git (`.../git/trees/{"{"}tree_sha{"}"}?recursive=true`) или локальный каталог
 horizontal = Горизонтально
+import = Импортировать
+inverse = Обратная задача
+    .hover = Распределения sn-1,3 и sn-2, подобранные к измеренным видам методом наименьших квадратов с ограничениями (1,3-случайное-2-случайное)
+inverse-configuration = Отправить как конфигурацию
+    .hover = Добавить синтетическую конфигурацию подобранных распределений к данным
+inverse-fitted = Подобрано
+inverse-measured = Измерено
+inverse-residual = Остаток
+inverse-species = Вид
+inverse-unmatched = Ключи без меток кадра
 language = Язык
 left_panel = Левая панель
 list = Список
 load = Загрузить
+models = Модели распределения
+models-chi_square = Хи-квадрат модели относительно измеренных видов
+models-measured = Измеренные виды
+    .hover = Измеренный профиль видов (строки ключа и значения), ключи как в первой композиции
+models-root_mean_square = Среднеквадратичная ошибка
+    .abbreviation = RMSE
+models-similarity = Индекс сходства (1 - ∑|измерено - модель| / 2)
+    .abbreviation = SI
+models-unexpected = Измеренные виды, отсутствующие в модели (хи-квадрат бесконечен)
+name = Название
+open = Открыть (данные, проект или сохранённый результат)
+outliers = Выбросы повторностей (робастные z-оценки)
+read_only = Сохранённый результат (только чтение)
+replicate = Повторность
 reset_application = Сбросить данные приложения
 reset_gui = Сбросить настройки интерфейса
 reset_table = Сбросить настройки таблицы
 resize_table = Изменить размер столбцов таблицы
 save = Сохранить
+save_project = Сохранить проект
 settings = Настройки
 tabs = Вкладки
-vertical = Вертикально
\ No newline at end of file
+tukey = Критерий Тьюки
+vertical = Вертикально
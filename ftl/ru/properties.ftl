@@ -32,6 +32,7 @@ descending = по убыванию
 
 key = ключ
 value = значение
+propagated = распространенная неопределенность
 
 -fatty_acid_term = { $genus ->
    *[nominative] жирная кислота
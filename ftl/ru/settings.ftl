@@ -1,9 +1,13 @@
 ## Settings
 
+settings-copy_headers = Копировать заголовки
+    .hover = Включать строки заголовков при копировании выделенных ячеек (Ctrl+C)
 settings-percent = В процентах
     .description = Вывод значений в процентах 
 settings-precision = Точность
     .description = Количество знаков после запятой
+settings-separate_standard_deviations = Отдельно ±
+    .hover = Копировать стандартные отклонения в отдельные столбцы
 settings-sticky_columns = Закрепить
     .description = Количество закрепленных столбцов
 settings-truncate_headers = Усечение
@@ -19,6 +23,10 @@ settings-properties = Свойства
 ## Calculation
 
 settings-as_is = Как есть
+settings-ethyl_ester = Этиловые эфиры
+settings-fatty_acid = Жирные кислоты
+settings-form = Форма
+    .hover = Форма массы жирной кислоты (M)
 settings-fraction = Доля
     .description = Метод расчета доли компонента
 settings-from = Вычислить из
@@ -27,6 +35,8 @@ settings-from_dag = из 1,2/2,3-ДАГ
     .description = Вычисление значения 1,3-ДАГ из 1,2/2,3-ДАГ
 settings-from_mag = из 2-{ monoacylglycerol.abbreviation }
     .description = Вычисление значения 1,3-ДАГ из 2-МАГ
+settings-methyl_ester = Метиловые эфиры
+settings-pchelkin = Пчелкин
 settings-sign = Знак
 settings-signed = Со знаком
     .description = Теоретически рассчитанные отрицательные значения отстаются без изменения
@@ -34,3 +44,63 @@ settings-to_mass_fraction = В массовую долю
 settings-to_mole_fraction = В мольную долю
 settings-unsigned = Без знака
     .description = Теоретически рассчитанные отрицательные значения замещаются нулем
+settings-correction_factors = Поправочные коэффициенты
+    .hover = Умножить экспериментальные значения на поправочные коэффициенты жирных кислот (коэффициенты отклика)
+    .modified = Набор изменен в библиотеке, выберите его снова, чтобы применить изменения
+settings-none = Нет
+settings-internal_standard = Внутренний стандарт
+    .hover = Строка конфигурации внутреннего стандарта, добавленного к образцу (например, 17:0 или 19:0)
+    .disabled = Внутренний стандарт требует мольных значений (в мольную долю или доля по Пчелкину)
+settings-standard_amount = Количество стандарта
+    .hover = Количество жирной кислоты внутреннего стандарта, добавленного к образцу
+settings-sample_mass = Масса образца
+    .hover = Масса анализируемого образца
+settings-unit = Единица
+    .hover = Единица измерения значений
+    .disabled = Абсолютные единицы требуют внутреннего стандарта, его количества, массы образца и мольных значений
+settings-relative = Относительная
+settings-micromole_per_gram = мкмоль/г
+settings-milligram_per_gram = мг/г
+settings-statistic = Статистика
+settings-dispersion = Разброс
+    .hover = Мера разброса, показываемая после среднего
+settings-standard_deviation = Стандартное отклонение
+    .hover = Выборочное стандартное отклонение (SD)
+settings-standard_error = Стандартная ошибка
+    .hover = Стандартная ошибка среднего (SEM = SD / √N)
+settings-confidence_interval = Доверительный интервал
+    .hover = Полуширина доверительного интервала среднего (t-распределение Стьюдента)
+settings-range = Размах
+    .hover = Минимум и максимум повторностей
+settings-confidence_level = Доверительная вероятность
+settings-outliers = Выбросы
+    .hover = Проверять экспериментальные значения повторностей по робастной z-оценке на медиане и медианном абсолютном отклонении
+settings-outlier_threshold = Порог
+    .hover = Робастная z-оценка, выше которой значение повторности отмечается
+settings-excluded = Исключено
+    .hover = Восстановить все исключённые повторности
+settings-groups = Группы
+    .hover = Распределите данные по именованным группам для проверки различий между группами (t-критерий Уэлча для двух групп, однофакторный дисперсионный анализ и критерий Тьюки для большего числа групп)
+settings-group = Группа
+settings-propagate = Распространение неопределенности
+    .hover = Показать неопределенность, распространенную от долей в sn-1(3) и sn-2 (первый порядок, с учетом корреляции видов группы и позиций жирной кислоты)
+settings-measurement_error = Погрешность измерения
+    .hover = Относительная стандартная неопределенность единичного измерения долей sn
+
+## Composition
+
+settings-gunstone = Ганстоун
+    .hover = Вычисление по теории Ганстоуна
+settings-vander_wal = Вандер Валь
+    .hover = Вычисление по теории Вандер Валя
+settings-random = 1,2,3-случайное
+    .hover = Вычисление по 1,2,3-случайному распределению жирных кислот
+settings-stereospecific = 1-случайное-2-случайное-3-случайное
+    .hover = Вычисление по независимым случайным распределениям в sn-1, sn-2 и sn-3 (sn-3 = 2·sn-1,3 - sn-1)
+settings-restricted_random = Ограниченно случайное
+    .hover = Вычисление по теории ограниченно случайного распределения Карта (1,2,3-случайное с ограничением тринасыщенных видов)
+settings-stereospecific_number1 = sn-1
+    .hover = Измеренный профиль sn-1 (строки метки и значения), при пустом профиле используется sn-1,3
+settings-stereospecific_number1-unmatched = Метки профиля, отсутствующие в кадре
+settings-restriction = Предел тринасыщенных
+    .hover = Максимальная доля тринасыщенных видов, избыток обменивается жирными кислотами с триненасыщенными видами
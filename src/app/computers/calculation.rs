@@ -1,6 +1,7 @@
-use crate::app::{
-    panes::calculation::settings::{Fraction, From, Settings},
-    presets::CHRISTIE,
+use super::{Groups, dispersion};
+use crate::{
+    app::panes::calculation::settings::{Fraction, From, Settings, Unit},
+    utils::statistics::robust_z_scores,
 };
 use egui::util::cache::{ComputerMut, FrameCache};
 use lipid::prelude::*;
@@ -17,7 +18,7 @@ pub(crate) type Computed = FrameCache<Value, Computer>;
 pub(crate) struct Computer;
 
 impl Computer {
-    fn try_compute(&mut self, key: Key) -> PolarsResult<DataFrame> {
+    pub(crate) fn try_compute(&mut self, key: Key) -> PolarsResult<DataFrame> {
         match key.settings.index {
             Some(index) => {
                 let frame = &key.frames[index];
@@ -26,24 +27,29 @@ impl Computer {
                 lazy_frame.collect()
             }
             None => {
-                let compute = |frame: &MetaDataFrame| -> PolarsResult<LazyFrame> {
+                let compute = |index: usize| -> PolarsResult<LazyFrame> {
+                    let frame = &key.frames[index];
                     Ok(compute(frame.data.clone().lazy(), key.settings)?.select([
                         as_struct(vec![col("Label"), col("FattyAcid")]).hash(),
                         col("Label"),
                         col("FattyAcid"),
-                        as_struct(vec![
-                            col("Experimental"),
-                            col("Theoretical"),
-                            col("Calculated"),
-                            col("Factors"),
-                        ])
+                        exclude(
+                            as_struct(vec![
+                                col("Experimental"),
+                                col("Theoretical"),
+                                col("Calculated"),
+                                col("Factors"),
+                            ]),
+                            index,
+                            key.settings,
+                        )
                         .alias(frame.meta.title()),
                     ]))
                 };
-                let mut lazy_frame = compute(&key.frames[0])?;
-                for frame in &key.frames[1..] {
+                let mut lazy_frame = compute(0)?;
+                for index in 1..key.frames.len() {
                     lazy_frame = lazy_frame.join(
-                        compute(frame)?,
+                        compute(index)?,
                         [col("Hash"), col("Label"), col("FattyAcid")],
                         [col("Hash"), col("Label"), col("FattyAcid")],
                         JoinArgs::new(JoinType::Full).with_coalesce(JoinCoalesce::CoalesceColumns),
@@ -61,7 +67,7 @@ impl Computer {
 
 impl ComputerMut<Key<'_>, Value> for Computer {
     fn compute(&mut self, key: Key) -> Value {
-        self.try_compute(key).unwrap()
+        self.try_compute(key).map_err(|error| error.to_string())
     }
 }
 
@@ -82,23 +88,38 @@ impl Hash for Key<'_> {
         // self.settings.sticky_columns.hash(state);
         // self.settings.truncate.hash(state);
         self.settings.fraction.hash(state);
+        self.settings.form.hash(state);
         self.settings.from.hash(state);
         self.settings.normalize.hash(state);
         self.settings.unsigned.hash(state);
-        self.settings.christie.hash(state);
+        self.settings.correction_factors.hash(state);
+        self.settings.standard.hash(state);
+        self.settings.unit.hash(state);
         self.settings.factors.hash(state);
         self.settings.ddof.hash(state);
+        self.settings.dispersion.hash(state);
+        self.settings.outliers.hash(state);
+        self.settings.groups.hash(state);
     }
 }
 
-/// Calculation value
-type Value = DataFrame;
+/// Calculation value (the error is shown in the pane)
+type Value = Result<DataFrame, String>;
 
 fn compute(mut lazy_frame: LazyFrame, settings: &Settings) -> PolarsResult<LazyFrame> {
-    // Christie
-    if settings.christie {
-        lazy_frame = christie(lazy_frame);
+    // Correction factors
+    if let Some(correction_factors) = &settings.correction_factors {
+        lazy_frame = correct(lazy_frame, &correction_factors.data);
     }
+    // Internal standard (excluded before the normalization, the mass fractions
+    // are not quantified)
+    let amount = if settings.is_quantified() {
+        let amount = amount(lazy_frame.clone(), settings)?;
+        lazy_frame = lazy_frame.filter(col("Label").neq(lit(settings.standard.label.clone())));
+        Some(amount)
+    } else {
+        None
+    };
     // Experimental
     lazy_frame = lazy_frame.with_column(
         Experimental(as_struct(vec![
@@ -128,7 +149,7 @@ fn compute(mut lazy_frame: LazyFrame, settings: &Settings) -> PolarsResult<LazyF
     );
     // Factors
     lazy_frame = lazy_frame.with_column(Factors(col("Calculated")).compute(col("FattyAcid")));
-    Ok(lazy_frame.select(vec![
+    let mut exprs = vec![
         col("Index"),
         col("Label"),
         col("FattyAcid"),
@@ -136,73 +157,237 @@ fn compute(mut lazy_frame: LazyFrame, settings: &Settings) -> PolarsResult<LazyF
         col("Theoretical"),
         col("Calculated"),
         col("Factors"),
-    ]))
+    ];
+    // Internal standard
+    if let Some(amount) = amount {
+        lazy_frame = quantify(lazy_frame, settings, amount);
+        exprs.push(col("Amount"));
+    }
+    Ok(lazy_frame.select(exprs))
+}
+
+/// Total amount of the fatty acids (µmol/g, without the standard)
+///
+/// The experimental values (molar) are relative to the sum with the standard
+/// row, so the amount is `n(IS) / m * ∑x(FA) / x(IS)`.
+fn amount(lazy_frame: LazyFrame, settings: &Settings) -> PolarsResult<f64> {
+    let standard = &settings.standard;
+    let tag = || {
+        col("Experimental")
+            .struct_()
+            .field_by_name("Triacylglycerol")
+    };
+    let is_standard = || col("Label").eq(lit(standard.label.clone()));
+    let data_frame = lazy_frame
+        .with_column(
+            Experimental(as_struct(vec![
+                col("Triacylglycerol"),
+                col("Diacylglycerol1223"),
+                col("Monoacylglycerol2"),
+            ]))
+            .compute(col("FattyAcid"), settings),
+        )
+        .select([
+            tag().filter(is_standard()).first().alias("Standard"),
+            tag().filter(is_standard().not()).sum().alias("FattyAcids"),
+        ])
+        .collect()?;
+    let Some(value) = data_frame["Standard"].f64()?.get(0) else {
+        polars_bail!(ComputeError: "internal standard not found: {:?}", standard.label);
+    };
+    polars_ensure!(
+        value > 0.0,
+        ComputeError: "internal standard {:?} has no triacylglycerol value",
+        standard.label,
+    );
+    let fatty_acids = data_frame["FattyAcids"].f64()?.get(0).unwrap_or_default();
+    Ok(standard.amount / standard.mass * fatty_acids / value)
+}
+
+/// Quantifies by the internal standard
+///
+/// Adds the total amount of the fatty acids (µmol/g) and converts the
+/// experimental and theoretical values (relative to the fatty acids without
+/// the standard) to the selected unit. The calculated values stay relative.
+fn quantify(lazy_frame: LazyFrame, settings: &Settings, amount: f64) -> LazyFrame {
+    let tag = || {
+        col("Experimental")
+            .struct_()
+            .field_by_name("Triacylglycerol")
+    };
+    // µmol/g per unit of the relative value
+    let scale = || lit(amount) / tag().sum();
+    let lazy_frame = lazy_frame.with_column(lit(amount).alias("Amount"));
+    if settings.unit == Unit::Fraction {
+        return lazy_frame;
+    }
+    let factor = || match settings.unit {
+        Unit::Fraction => unreachable!(),
+        Unit::MicromolePerGram => scale(),
+        // µmol * g/mol = µg
+        Unit::MilligramPerGram => {
+            scale() * (col("FattyAcid").fa().mass(None) + lit(settings.form.offset())) / lit(1000)
+        }
+    };
+    let field =
+        |expr: Expr, name: &str| (expr.struct_().field_by_name(name) * factor()).alias(name);
+    lazy_frame.with_columns([
+        as_struct(vec![
+            field(col("Experimental"), "Triacylglycerol"),
+            field(col("Experimental"), "Diacylglycerol1223"),
+            field(col("Experimental"), "Monoacylglycerol2"),
+        ])
+        .alias("Experimental"),
+        as_struct(vec![
+            field(col("Theoretical"), "Triacylglycerol"),
+            field(col("Theoretical"), "Diacylglycerol1223"),
+            field(col("Theoretical"), "Monoacylglycerol2"),
+            as_struct(vec![
+                field(
+                    col("Theoretical")
+                        .struct_()
+                        .field_by_name("Diacylglycerol13"),
+                    "Diacylglycerol1223",
+                ),
+                field(
+                    col("Theoretical")
+                        .struct_()
+                        .field_by_name("Diacylglycerol13"),
+                    "Monoacylglycerol2",
+                ),
+            ])
+            .alias("Diacylglycerol13"),
+        ])
+        .alias("Theoretical"),
+    ])
 }
 
-fn christie(lazy_frame: LazyFrame) -> LazyFrame {
+/// Multiplies the experimental values by the correction factors (missing
+/// factors are 1)
+fn correct(lazy_frame: LazyFrame, correction_factors: &DataFrame) -> LazyFrame {
+    let factor = || col("Factor").fill_null(lit(1));
     lazy_frame
         .with_column(col("FattyAcid").hash())
         .join(
-            CHRISTIE.data.clone().lazy().select([
+            correction_factors.clone().lazy().select([
                 col("FattyAcid").hash(),
                 col("FattyAcid"),
-                col("Christie"),
+                col("Factor"),
             ]),
             [col("Hash"), col("FattyAcid")],
             [col("Hash"), col("FattyAcid")],
             JoinArgs::new(JoinType::Left),
         )
-        // col("Christie").fill_null(lit(1)),
-        .drop(["Hash"])
+        .with_columns([
+            col("Triacylglycerol") * factor(),
+            col("Diacylglycerol1223") * factor(),
+            col("Monoacylglycerol2") * factor(),
+        ])
+        .drop(["Hash", "Factor"])
+}
+
+/// Replaces the excluded replicates of the frame with nulls
+fn exclude(expr: Expr, index: usize, settings: &Settings) -> Expr {
+    let mut predicate = None;
+    for (_, label) in settings
+        .outliers
+        .excluded
+        .iter()
+        .filter(|excluded| excluded.0 == index)
+    {
+        let expr = col("Label").eq(lit(label.clone()));
+        predicate = Some(match predicate {
+            Some(predicate) => expr.or(predicate),
+            None => expr,
+        });
+    }
+    match predicate {
+        Some(predicate) => when(predicate).then(lit(NULL)).otherwise(expr),
+        None => expr,
+    }
 }
 
 fn means(lazy_frame: LazyFrame, settings: &Settings) -> PolarsResult<LazyFrame> {
+    let groups = &Groups::new(&settings.groups);
+    let mean = |names: &[&str]| mean(names, settings, groups);
     Ok(lazy_frame.select([
         col("Label"),
         col("FattyAcid"),
         as_struct(vec![
-            mean(&["Experimental", "Triacylglycerol"], settings.ddof)?,
-            mean(&["Experimental", "Diacylglycerol1223"], settings.ddof)?,
-            mean(&["Experimental", "Monoacylglycerol2"], settings.ddof)?,
+            mean(&["Experimental", "Triacylglycerol"])?,
+            mean(&["Experimental", "Diacylglycerol1223"])?,
+            mean(&["Experimental", "Monoacylglycerol2"])?,
         ])
         .alias("Experimental"),
         as_struct(vec![
-            mean(&["Theoretical", "Triacylglycerol"], settings.ddof)?,
-            mean(&["Theoretical", "Diacylglycerol1223"], settings.ddof)?,
-            mean(&["Theoretical", "Monoacylglycerol2"], settings.ddof)?,
+            mean(&["Theoretical", "Triacylglycerol"])?,
+            mean(&["Theoretical", "Diacylglycerol1223"])?,
+            mean(&["Theoretical", "Monoacylglycerol2"])?,
             as_struct(vec![
-                mean(
-                    &["Theoretical", "Diacylglycerol13", "Diacylglycerol1223"],
-                    settings.ddof,
-                )?,
-                mean(
-                    &["Theoretical", "Diacylglycerol13", "Monoacylglycerol2"],
-                    settings.ddof,
-                )?,
+                mean(&["Theoretical", "Diacylglycerol13", "Diacylglycerol1223"])?,
+                mean(&["Theoretical", "Diacylglycerol13", "Monoacylglycerol2"])?,
             ])
             .alias("Diacylglycerol13"),
         ])
         .alias("Theoretical"),
         as_struct(vec![
-            mean(&["Factors", "Enrichment"], settings.ddof)?,
-            mean(&["Factors", "Selectivity"], settings.ddof)?,
+            mean(&["Factors", "Enrichment"])?,
+            mean(&["Factors", "Selectivity"])?,
         ])
         .alias("Factors"),
     ]))
 }
 
-fn mean(names: &[&str], ddof: u8) -> PolarsResult<Expr> {
-    Ok(as_struct(vec![
-        concat_list([all().exclude(["Label", "FattyAcid"]).destruct(names)])?
-            .list()
-            .mean()
-            .alias("Mean"),
-        concat_list([all().exclude(["Label", "FattyAcid"]).destruct(names)])?
-            .list()
-            .std(ddof)
-            .alias("StandardDeviations"),
-    ])
-    .alias(names[names.len() - 1]))
+/// Mean and dispersion of the frames (and the between-group tests)
+fn mean(names: &[&str], settings: &Settings, groups: &Groups) -> PolarsResult<Expr> {
+    let values = || concat_list([all().exclude(["Label", "FattyAcid"]).destruct(names)]);
+    let mut fields = vec![values()?.list().mean().alias("Mean")];
+    fields.extend(dispersion(
+        values,
+        settings.ddof,
+        settings.dispersion,
+        "StandardDeviations",
+    )?);
+    if groups.is_testable() {
+        fields.push(groups.means(values()?));
+        fields.push(groups.standard_deviations(values()?, settings.ddof));
+        fields.push(groups.p_value(values()?));
+        if groups.names.len() > 2 {
+            fields.push(groups.tukey(values()?));
+        }
+    }
+    if settings.outliers.screen && names[0] == "Experimental" {
+        fields.push(z_scores(values()?));
+    }
+    Ok(as_struct(fields).alias(names[names.len() - 1]))
+}
+
+/// Robust z-scores of the frame values (in the order of the frames)
+fn z_scores(values: Expr) -> Expr {
+    values
+        .map(
+            |column| {
+                let mut z_scores = Vec::with_capacity(column.len());
+                for series in column.list()? {
+                    z_scores.push(match series {
+                        Some(series) => {
+                            let values = series.f64()?.into_iter().collect::<Vec<_>>();
+                            Some(Series::new(PlSmallStr::EMPTY, robust_z_scores(&values)))
+                        }
+                        None => None,
+                    });
+                }
+                Ok(Some(
+                    z_scores
+                        .into_iter()
+                        .collect::<ListChunked>()
+                        .into_series()
+                        .into_column(),
+                ))
+            },
+            GetOutput::from_type(DataType::List(Box::new(DataType::Float64))),
+        )
+        .alias("ZScores")
 }
 
 trait Tag {
@@ -226,10 +411,16 @@ struct Experimental(Expr);
 impl Experimental {
     fn compute(self, fatty_acid: Expr, settings: &Settings) -> Expr {
         // // col(name) / (col(name) * col("FA").fa().mass() / lit(10)).sum()
-        let experimental = |mut expr: Expr| {
-            // S / ∑(S * M)
-            if let Fraction::Fraction = settings.fraction {
-                expr = expr.clone() / (expr * fatty_acid.clone().fa().mass(None)).sum()
+        let mass = || fatty_acid.clone().fa().mass(None) + lit(settings.form.offset());
+        let experimental = |expr: Expr| {
+            let expr = match settings.fraction {
+                Fraction::AsIs => expr,
+                // n = m / M
+                Fraction::ToMole => expr / mass(),
+                // m = n * M
+                Fraction::ToMass => expr * mass(),
+                // S / ∑(S * M)
+                Fraction::Fraction => expr.clone() / (expr * mass()).sum(),
             };
             expr.normalize_if(settings.normalize.experimental)
         };
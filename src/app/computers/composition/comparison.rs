@@ -0,0 +1,207 @@
+use super::triacylglycerol::{compute, filter};
+use crate::{
+    app::panes::{
+        comparison::settings::Settings,
+        composition::settings::{Filter, Selection},
+    },
+    special::composition::SSC,
+};
+use egui::util::cache::{ComputerMut, FrameCache};
+use metadata::MetaDataFrame;
+use polars::prelude::*;
+use polars_ext::ExprExt as _;
+use std::hash::{Hash, Hasher};
+
+/// Comparison computed
+pub(crate) type Computed = FrameCache<Value, Computer>;
+
+/// Comparison computer
+///
+/// Compositions of the samples aligned by the keys (absent species are
+/// zeros), with the differences and the fold changes to the reference sample
+/// and the comparative major number.
+#[derive(Default)]
+pub(crate) struct Computer;
+
+impl Computer {
+    fn try_compute(&mut self, key: Key) -> PolarsResult<DataFrame> {
+        let mut settings = key.settings.composition.clone();
+        if settings.confirmed.selections.is_empty() {
+            settings.confirmed.selections.push_back(Selection {
+                composition: SSC,
+                filter: Filter::new(),
+            });
+        }
+        // Each sample is one frame, the filtered rows are kept and marked
+        let show_filtered = settings.confirmed.show_filtered;
+        settings.index = Some(0);
+        settings.confirmed.propagate = false;
+        settings.confirmed.show_filtered = true;
+        let settings = &settings;
+        // Value of the last (the most detailed) selection
+        let last = settings.confirmed.selections.len().saturating_sub(1);
+        let sample = |index: usize| -> PolarsResult<LazyFrame> {
+            let lazy_frame = compute(key.frames[index].data.clone().lazy(), settings, false)?;
+            Ok(filter(lazy_frame, settings).select([
+                col("Keys").hash(),
+                col("Keys"),
+                col("Values")
+                    .arr()
+                    .get(lit(last as u32), false)
+                    .alias(format!("Value{index}")),
+                col("Filtered").not().alias(format!("Major{index}")),
+            ]))
+        };
+        let mut lazy_frame = sample(0)?;
+        for index in 1..key.frames.len() {
+            lazy_frame = lazy_frame.join(
+                sample(index)?,
+                [col("Hash"), col("Keys")],
+                [col("Hash"), col("Keys")],
+                JoinArgs::new(JoinType::Full).with_coalesce(JoinCoalesce::CoalesceColumns),
+            );
+        }
+        let value = |index: usize| col(format!("Value{index}"));
+        let reference = value(key.settings.reference);
+        let mut columns = vec![col("Keys")];
+        for index in 0..key.frames.len() {
+            columns.push(value(index));
+            columns.push((value(index) - reference.clone()).alias(format!("Difference{index}")));
+            columns.push(
+                when(reference.clone().neq(lit(0)))
+                    .then(value(index) / reference.clone())
+                    .otherwise(lit(NULL).cast(DataType::Float64))
+                    .alias(format!("FoldChange{index}")),
+            );
+        }
+        columns.push(comparative_major_number(key.frames.len()));
+        lazy_frame = lazy_frame
+            .drop([col("Hash")])
+            .with_columns(
+                (0..key.frames.len())
+                    .map(|index| value(index).fill_null(lit(0.0)))
+                    .collect::<Vec<_>>(),
+            )
+            .select(columns);
+        if !show_filtered {
+            lazy_frame = lazy_frame.filter(col("ComparativeMajorNumber").neq(lit(0u32)));
+        }
+        // Sort
+        let mut by = vec![reference];
+        let mut descending = vec![true];
+        if key.settings.comparative_major_number {
+            by.insert(0, col("ComparativeMajorNumber"));
+            descending.insert(0, true);
+        }
+        lazy_frame = lazy_frame.sort_by_exprs(
+            by,
+            SortMultipleOptions::default().with_order_descending_multi(descending),
+        );
+        // Index
+        lazy_frame = lazy_frame.with_row_index("Index", None);
+        lazy_frame.collect()
+    }
+}
+
+impl ComputerMut<Key<'_>, Value> for Computer {
+    fn compute(&mut self, key: Key) -> Value {
+        self.try_compute(key).map_err(|error| error.to_string())
+    }
+}
+
+/// Comparative major number (one bit per sample, set if the row survives the
+/// filter of the sample)
+fn comparative_major_number(count: usize) -> Expr {
+    (0..count)
+        .fold(lit(0u32), |number, index| {
+            number
+                + when(col(format!("Major{index}")).fill_null(lit(false)))
+                    .then(lit(1u32.checked_shl(index as _).unwrap_or_default()))
+                    .otherwise(lit(0u32))
+        })
+        .alias("ComparativeMajorNumber")
+}
+
+/// Comparison key
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Key<'a> {
+    pub(crate) frames: &'a [MetaDataFrame],
+    pub(crate) settings: &'a Settings,
+}
+
+impl Hash for Key<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.frames.hash(state);
+        self.settings.composition.confirmed.hash(state);
+        self.settings.reference.hash(state);
+        self.settings.comparative_major_number.hash(state);
+    }
+}
+
+/// Comparison value (the error is shown in the pane)
+type Value = Result<DataFrame, String>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        app::panes::composition::settings::Settings as CompositionSettings,
+        special::composition::MNC,
+    };
+
+    /// Major rows of the sample with the mass keys and one selection
+    fn major(index: usize, values: &[f64], settings: &CompositionSettings) -> PolarsResult<Column> {
+        let lazy_frame = df! {
+            "Key0" => [1.0, 2.0, 3.0],
+            "Value" => values,
+        }?
+        .lazy()
+        .select([
+            as_struct(vec![col("Key0")]).alias("Keys"),
+            concat_arr(vec![col("Value")])?.alias("Values"),
+        ]);
+        let data_frame = filter(lazy_frame, settings)
+            .select([col("Filtered").not().alias(format!("Major{index}"))])
+            .collect()?;
+        Ok(data_frame[0].clone())
+    }
+
+    #[test]
+    fn test() -> PolarsResult<()> {
+        let mut settings = CompositionSettings::new(Some(0));
+        let mut filter = Filter::new();
+        filter.keys[0].insert(AnyValue::Float64(2.0));
+        filter.value = 0.2;
+        settings.confirmed.selections.push_back(Selection {
+            composition: MNC,
+            filter,
+        });
+        settings.confirmed.show_filtered = true;
+        // The excluded key and the values below the threshold are not major,
+        // even if the filtered rows are shown
+        let data_frame = DataFrame::new(vec![
+            major(0, &[0.5, 0.4, 0.1], &settings)?,
+            major(1, &[0.1, 0.6, 0.3], &settings)?,
+        ])?
+        .lazy()
+        .select([comparative_major_number(2)])
+        .collect()?;
+        assert_eq!(
+            data_frame["ComparativeMajorNumber"],
+            Series::new("ComparativeMajorNumber".into(), [1u32, 0, 2]).into_column(),
+        );
+        // Absent (null) rows are not major
+        let data_frame = df! {
+            "Major0" => [Some(true), None],
+            "Major1" => [None, Some(true)],
+        }?
+        .lazy()
+        .select([comparative_major_number(2)])
+        .collect()?;
+        assert_eq!(
+            data_frame["ComparativeMajorNumber"],
+            Series::new("ComparativeMajorNumber".into(), [1u32, 2]).into_column(),
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{
+    parsers::clipboard::profile,
+    special::inverse::{Kind, solve},
+};
+use anyhow::Result;
+use egui::util::cache::{ComputerMut, FrameCache};
+use metadata::MetaDataFrame;
+use polars::prelude::*;
+use std::hash::{Hash, Hasher};
+
+/// Inverse computed
+pub(crate) type Computed = FrameCache<Value, Computer>;
+
+/// Inverse computer
+///
+/// sn-1,3 and sn-2 distributions fitted to the measured species.
+#[derive(Default)]
+pub(crate) struct Computer;
+
+impl Computer {
+    fn try_compute(&mut self, key: Key) -> Result<Inverse> {
+        // Labels of the frame or of all frames (the mean)
+        let frames = match key.index {
+            Some(index) => key.frames.get(index..=index).unwrap_or_default(),
+            None => key.frames,
+        };
+        let mut labels = Vec::<String>::new();
+        for frame in frames {
+            for label in frame.data["Label"].str()? {
+                let label = label.unwrap_or_default();
+                if !labels.iter().any(|other| other == label) {
+                    labels.push(label.to_owned());
+                }
+            }
+        }
+        let mut measured = Vec::new();
+        let mut unmatched = Vec::new();
+        for (text, value) in profile(key.measured)? {
+            let indices = text
+                .trim_start_matches('{')
+                .trim_end_matches('}')
+                .split(',')
+                .map(|label| labels.iter().position(|other| other == label.trim()))
+                .collect::<Option<Vec<_>>>();
+            match indices.as_deref() {
+                Some(&[a, b, c]) => measured.push(([a, b, c], value)),
+                _ => unmatched.push(text),
+            }
+        }
+        let solution = solve(labels.len(), &measured, key.kind);
+        let key_text =
+            |[a, b, c]: [usize; 3]| format!("{{{},{},{}}}", labels[a], labels[b], labels[c]);
+        let distribution = df! {
+            "Label" => &labels,
+            "StereospecificNumber13" => &solution.stereospecific_numbers13,
+            "StereospecificNumber2" => &solution.stereospecific_numbers2,
+        }?;
+        let species = df! {
+            "Key" => solution.species.iter().map(|&(key, ..)| key_text(key)).collect::<Vec<_>>(),
+            "Measured" => solution.species.iter().map(|&(_, measured, _)| measured).collect::<Vec<_>>(),
+            "Fitted" => solution.species.iter().map(|&(.., fitted)| fitted).collect::<Vec<_>>(),
+        }?
+        .lazy()
+        .with_column((col("Fitted") - col("Measured")).alias("Residual"))
+        .sort(["Residual"], SortMultipleOptions::default().with_order_descending(true))
+        .collect()?;
+        Ok(Inverse {
+            distribution,
+            species,
+            root_mean_square: solution.root_mean_square,
+            unmatched,
+        })
+    }
+}
+
+impl ComputerMut<Key<'_>, Value> for Computer {
+    fn compute(&mut self, key: Key) -> Value {
+        self.try_compute(key).map_err(|error| error.to_string())
+    }
+}
+
+/// Inverse key
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Key<'a> {
+    pub(crate) frames: &'a [MetaDataFrame],
+    pub(crate) index: Option<usize>,
+    pub(crate) kind: Kind,
+    pub(crate) measured: &'a str,
+}
+
+impl Hash for Key<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.frames.hash(state);
+        self.index.hash(state);
+        self.kind.hash(state);
+        self.measured.hash(state);
+    }
+}
+
+/// Inverse value (the error is shown in the pane)
+type Value = Result<Inverse, String>;
+
+/// Inverse solution
+#[derive(Clone, Debug)]
+pub(crate) struct Inverse {
+    /// Label, sn-1,3 and sn-2 fractions
+    pub(crate) distribution: DataFrame,
+    /// Key, measured, fitted and residual fractions
+    pub(crate) species: DataFrame,
+    pub(crate) root_mean_square: f64,
+    /// Measured keys without the labels of the frame
+    pub(crate) unmatched: Vec<String>,
+}
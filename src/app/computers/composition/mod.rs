@@ -1,7 +1,13 @@
 pub(crate) use self::{
+    comparison::{Computed as ComparisonComputed, Key as ComparisonKey},
     fatty_acid::{Computed as FattyAcidComputed, Key as FattyAcidKey},
+    inverse::{Computed as InverseComputed, Key as InverseKey},
+    models::{Computed as ModelsComputed, Key as ModelsKey},
     triacylglycerol::{Computed as TriacylglycerolComputed, Key as TriacylglycerolKey},
 };
 
+pub(crate) mod comparison;
 pub(crate) mod fatty_acid;
+pub(crate) mod inverse;
+pub(crate) mod models;
 pub(crate) mod triacylglycerol;
@@ -0,0 +1,78 @@
+use super::triacylglycerol::{average, compute};
+use crate::{
+    app::panes::{
+        calculation::settings::Unit,
+        composition::settings::{Filter, Method, Selection, Settings},
+    },
+    special::composition::SSC,
+};
+use egui::util::cache::{ComputerMut, FrameCache};
+use metadata::MetaDataFrame;
+use polars::prelude::*;
+use std::hash::{Hash, Hasher};
+
+/// Models computed
+pub(crate) type Computed = FrameCache<Value, Computer>;
+
+/// Models computer
+///
+/// Species of the first composition by each method (relative, not filtered).
+#[derive(Default)]
+pub(crate) struct Computer;
+
+impl Computer {
+    fn try_compute(&mut self, key: Key) -> PolarsResult<Vec<(Method, DataFrame)>> {
+        let mut settings = key.settings.clone();
+        let composition = settings
+            .confirmed
+            .selections
+            .front()
+            .map_or(SSC, |selection| selection.composition);
+        settings.confirmed.selections = [Selection {
+            composition,
+            filter: Filter::new(),
+        }]
+        .into();
+        settings.confirmed.unit = Unit::Fraction;
+        let lazy_frame = match settings.index {
+            Some(index) => key.frames[index].data.clone().lazy(),
+            None => average(key.frames, &settings)?,
+        };
+        let mut value = Vec::with_capacity(Method::ALL.len());
+        for method in Method::ALL {
+            settings.confirmed.method = method;
+            let data_frame = compute(lazy_frame.clone(), &settings, false)?
+                .select([
+                    col("Keys").struct_().field_by_name("Key0").alias("Key"),
+                    col("Values").arr().get(lit(0), false).alias("Value"),
+                ])
+                .collect()?;
+            value.push((method, data_frame));
+        }
+        Ok(value)
+    }
+}
+
+impl ComputerMut<Key<'_>, Value> for Computer {
+    fn compute(&mut self, key: Key) -> Value {
+        self.try_compute(key).map_err(|error| error.to_string())
+    }
+}
+
+/// Models key
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Key<'a> {
+    pub(crate) frames: &'a [MetaDataFrame],
+    pub(crate) settings: &'a Settings,
+}
+
+impl Hash for Key<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.frames.hash(state);
+        self.settings.index.hash(state);
+        self.settings.confirmed.hash(state);
+    }
+}
+
+/// Models value (the error is shown in the window)
+type Value = Result<Vec<(Method, DataFrame)>, String>;
@@ -1,18 +1,31 @@
 use crate::{
-    app::panes::composition::settings::{Filter, Method, Order, Selection, Settings, Sort},
+    app::{
+        computers::{Groups, dispersion},
+        panes::{
+            calculation::settings::Unit,
+            composition::settings::{Filter, Method, Order, Selection, Settings, Sort},
+        },
+    },
+    parsers::clipboard::profile,
     special::composition::{MNC, MSC, NNC, NSC, SNC, SPC, SSC, TNC, TPC, TSC, UNC, USC},
 };
+use anyhow::{Result, ensure};
 use egui::util::cache::{ComputerMut, FrameCache};
 use lipid::prelude::*;
 use metadata::MetaDataFrame;
 use polars::prelude::*;
 use polars_ext::{ExprExt as _, column, functions::round};
 use std::{
+    collections::HashSet,
     convert::identity,
     hash::{Hash, Hasher},
-    process::exit,
 };
-use tracing::warn;
+
+const STEREOSPECIFIC_NUMBERS: [&str; 3] = [
+    "StereospecificNumber1",
+    "StereospecificNumber2",
+    "StereospecificNumber3",
+];
 
 /// Composition computed
 pub(crate) type Computed = FrameCache<Value, Computer>;
@@ -111,8 +124,7 @@ pub(crate) struct Computer;
 // }
 
 impl Computer {
-    fn try_compute(&mut self, key: Key) -> PolarsResult<Value> {
-        warn!("index: {:?}", key.settings.index);
+    pub(crate) fn try_compute(&mut self, key: Key) -> PolarsResult<DataFrame> {
         let mut settings = key.settings.clone();
         if settings.confirmed.selections.is_empty() {
             settings.confirmed.selections.push_back(Selection {
@@ -125,16 +137,25 @@ impl Computer {
             Some(index) => {
                 let frame = &key.frames[index];
                 let mut lazy_frame = frame.data.clone().lazy();
-                lazy_frame = compute(lazy_frame, settings)?;
+                if settings.confirmed.propagate {
+                    lazy_frame = lazy_frame.with_column(uncertainty(
+                        col("Calculated"),
+                        None,
+                        settings.confirmed.measurement_error,
+                    ));
+                }
+                lazy_frame = compute(lazy_frame, settings, settings.confirmed.propagate)?;
                 lazy_frame
             }
             None => {
                 let compute = |frame: &MetaDataFrame| -> PolarsResult<LazyFrame> {
-                    Ok(compute(frame.data.clone().lazy(), settings)?.select([
-                        col("Keys").hash(),
-                        col("Keys"),
-                        col("Values").alias(frame.meta.title()),
-                    ]))
+                    Ok(
+                        compute(frame.data.clone().lazy(), settings, false)?.select([
+                            col("Keys").hash(),
+                            col("Keys"),
+                            col("Values").alias(frame.meta.title()),
+                        ]),
+                    )
                 };
                 let mut lazy_frame = compute(&key.frames[0])?;
                 for frame in &key.frames[1..] {
@@ -145,6 +166,28 @@ impl Computer {
                         JoinArgs::new(JoinType::Full).with_coalesce(JoinCoalesce::CoalesceColumns),
                     );
                 }
+                // Propagated
+                if settings.confirmed.propagate {
+                    let mut propagated = vec![col("Hash"), col("Keys")];
+                    for index in 0..settings.confirmed.selections.len() {
+                        propagated.push(
+                            col("Values")
+                                .arr()
+                                .get(lit(index as u32), false)
+                                .struct_()
+                                .field_by_name("Propagated")
+                                .alias(format!("Propagated{index}")),
+                        );
+                    }
+                    lazy_frame = lazy_frame.join(
+                        self::compute(average(key.frames, settings)?, settings, true)?
+                            .with_column(col("Keys").hash())
+                            .select(propagated),
+                        [col("Hash"), col("Keys")],
+                        [col("Hash"), col("Keys")],
+                        JoinArgs::new(JoinType::Left),
+                    );
+                }
                 lazy_frame = lazy_frame.drop([col("Hash")]);
                 lazy_frame = meta(lazy_frame, settings)?;
                 lazy_frame
@@ -162,7 +205,7 @@ impl Computer {
 
 impl ComputerMut<Key<'_>, Value> for Computer {
     fn compute(&mut self, key: Key) -> Value {
-        self.try_compute(key).unwrap()
+        self.try_compute(key).map_err(|error| error.to_string())
     }
 }
 
@@ -177,88 +220,255 @@ impl Hash for Key<'_> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.frames.hash(state);
         self.settings.index.hash(state);
+        self.settings.groups.hash(state);
         self.settings.confirmed.hash(state);
     }
 }
 
-/// Composition value
-type Value = DataFrame;
+/// Composition value (the error is shown in the pane)
+type Value = Result<DataFrame, String>;
+
+pub(super) fn compute(
+    lazy_frame: LazyFrame,
+    settings: &Settings,
+    propagate: bool,
+) -> PolarsResult<LazyFrame> {
+    let amount = amount(&lazy_frame, settings)?;
+    let mut lazy_frame = match settings.confirmed.method {
+        Method::Gunstone => restricted(
+            lazy_frame,
+            ["Diacylglycerol13", "Monoacylglycerol2", "Diacylglycerol13"],
+            Types::gunstone,
+            propagate,
+        )?,
+        Method::VanderWal => vander_wal(lazy_frame, propagate)?,
+        Method::Random => random(lazy_frame, propagate)?,
+        Method::Stereospecific => {
+            let labels = lazy_frame.clone().select([col("Label")]).collect()?;
+            let labels = labels["Label"]
+                .str()?
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            let profile = Profile::new(&settings.confirmed.stereospecific_number1, &labels)
+                .map_err(|error| polars_err!(ComputeError: "sn-1 profile: {error}"))?;
+            stereospecific(lazy_frame, &profile.values, propagate)?
+        }
+        Method::RestrictedRandom => restricted(
+            lazy_frame,
+            ["Triacylglycerol", "Triacylglycerol", "Triacylglycerol"],
+            |s| Types::kartha(s, settings.confirmed.restriction),
+            propagate,
+        )?,
+    };
+    // Unit
+    lazy_frame = quantify(lazy_frame, settings, amount, propagate);
+    // Compose
+    compose(lazy_frame, settings, propagate)
+}
 
-fn compute(lazy_frame: LazyFrame, settings: &Settings) -> PolarsResult<LazyFrame> {
-    match settings.confirmed.method {
-        Method::Gunstone => vander_wal(lazy_frame, settings),
-        Method::VanderWal => vander_wal(lazy_frame, settings),
+/// Total amount of the fatty acids (µmol/g) for the absolute units
+///
+/// The calculation must be quantified by the internal standard (the standard
+/// row is excluded there).
+fn amount(lazy_frame: &LazyFrame, settings: &Settings) -> PolarsResult<Option<f64>> {
+    if settings.confirmed.unit == Unit::Fraction {
+        return Ok(None);
     }
+    polars_ensure!(
+        lazy_frame.clone().collect_schema()?.contains("Amount"),
+        ComputeError: "absolute units require the internal standard in the calculation",
+    );
+    let data_frame = lazy_frame
+        .clone()
+        .select([col("Amount").mean()])
+        .collect()?;
+    let Some(amount) = data_frame["Amount"].f64()?.get(0) else {
+        polars_bail!(ComputeError: "internal standard amount is not calculated");
+    };
+    Ok(Some(amount))
 }
 
-// let u = 1.0 - s;
-// if s <= 2.0 / 3.0 {
-//     Self {
-//         s,
-//         u,
-//         s3: 0.0,
-//         s2u: (3.0 * s / 2.0).powi(2),
-//         su2: 3.0 * s * (3.0 * u - 1.0) / 2.0,
-//         u3: ((3.0 * u - 1.0) / 2.0).powi(2),
-//     }
-// } else {
-//     Self {
-//         s,
-//         u,
-//         s3: 3.0 * s - 2.0,
-//         s2u: 3.0 * u,
-//         su2: 0.0,
-//         u3: 0.0,
-//     }
-// }
-fn gunstone(mut lazy_frame: LazyFrame, settings: &Settings) -> PolarsResult<LazyFrame> {
-    println!("lazy_frame g0: {}", lazy_frame.clone().collect().unwrap());
-    lazy_frame = lazy_frame.select([
-        col("Index"),
-        col("Label"),
-        col("FattyAcid"),
-        col("Calculated")
-            .struct_()
-            .field_by_names(["Triacylglycerol"]),
-    ]);
-    println!("lazy_frame g1: {}", lazy_frame.clone().collect().unwrap());
-    let lazy_frame = lazy_frame.with_columns([
-        col("Triacylglycerol")
+/// Converts the species fractions to the absolute amounts
+///
+/// `n(TAG) = x(TAG) * n(FA) / 3`, `m(TAG) = n(TAG) * M(TAG)`. Without the
+/// amount the values stay fractions.
+fn quantify(
+    mut lazy_frame: LazyFrame,
+    settings: &Settings,
+    amount: Option<f64>,
+    propagate: bool,
+) -> LazyFrame {
+    let Some(amount) = amount else {
+        return lazy_frame;
+    };
+    let amount = || lit(amount / 3.0);
+    let factor = match settings.confirmed.unit {
+        Unit::Fraction => return lazy_frame,
+        Unit::MicromolePerGram => amount(),
+        // µmol * g/mol = µg
+        Unit::MilligramPerGram => amount() * col("FattyAcid").tag().mass(None) / lit(1000),
+    };
+    if propagate {
+        lazy_frame = lazy_frame.with_column((col("Scale") * factor.clone()).alias("Scale"));
+    }
+    lazy_frame.with_column((col("Value") * factor).alias("Value"))
+}
+
+/// Mean of the replicates with the uncertainty of the sn fractions
+///
+/// The uncertainty combines the replicate standard deviation and the relative
+/// measurement error.
+pub(super) fn average(frames: &[MetaDataFrame], settings: &Settings) -> PolarsResult<LazyFrame> {
+    const NAMES: [&str; 3] = ["Triacylglycerol", "Diacylglycerol13", "Monoacylglycerol2"];
+    let lazy_frames = frames
+        .iter()
+        .map(|frame| {
+            frame.data.clone().lazy().select([
+                as_struct(vec![col("Label"), col("FattyAcid")]).hash(),
+                col("Label"),
+                col("FattyAcid"),
+                col("Calculated").struct_().field_by_names(NAMES),
+                col("^Amount$"),
+            ])
+        })
+        .collect::<Vec<_>>();
+    let ddof = settings.confirmed.ddof;
+    let lazy_frame = concat(lazy_frames, UnionArgs::default())?
+        .group_by_stable([col("Hash")])
+        .agg([
+            col("Label").first(),
+            col("FattyAcid").first(),
+            col("^Amount$").mean(),
+            as_struct(NAMES.map(|name| col(name).mean()).to_vec()).alias("Calculated"),
+            as_struct(
+                NAMES
+                    .map(|name| col(name).std(ddof).fill_null(lit(0.0)))
+                    .to_vec(),
+            )
+            .alias("StandardDeviation"),
+        ])
+        .with_row_index("Index", None)
+        .with_column(uncertainty(
+            col("Calculated"),
+            Some(col("StandardDeviation")),
+            settings.confirmed.measurement_error,
+        ));
+    Ok(lazy_frame)
+}
+
+/// Standard uncertainty of the sn fractions
+///
+/// `u = √(σ² + (ε * x)²)`, where `σ` - standard deviation, `ε` - relative
+/// measurement error.
+fn uncertainty(values: Expr, standard_deviations: Option<Expr>, error: f64) -> Expr {
+    as_struct(
+        ["Triacylglycerol", "Diacylglycerol13", "Monoacylglycerol2"]
+            .map(|name| {
+                let mut variance =
+                    (values.clone().struct_().field_by_name(name) * lit(error)).pow(2);
+                if let Some(standard_deviations) = &standard_deviations {
+                    variance = variance
+                        + standard_deviations
+                            .clone()
+                            .struct_()
+                            .field_by_name(name)
+                            .pow(2);
+                }
+                variance.sqrt().alias(name)
+            })
+            .to_vec(),
+    )
+    .alias("Uncertainty")
+}
+
+// Random with the type (S/U) restriction
+// [abc] = factor(type) * [a1] * [b2] * [c3], where the factor is the target
+// fraction of the positional type (from the saturated part of the calculated
+// TAG) over its random fraction
+fn restricted(
+    mut lazy_frame: LazyFrame,
+    names: [&str; 3],
+    types: impl Fn(f64) -> Types,
+    propagate: bool,
+) -> PolarsResult<LazyFrame> {
+    let mut unique = Vec::with_capacity(names.len());
+    for name in names {
+        if !unique.contains(&name) {
+            unique.push(name);
+        }
+    }
+    // Saturated part of the calculated triacylglycerols
+    let triacylglycerol = || col("Calculated").struct_().field_by_name("Triacylglycerol");
+    let data_frame = lazy_frame
+        .clone()
+        .select([(triacylglycerol()
             .filter(col("FattyAcid").fa().is_saturated())
             .sum()
-            .alias("S"),
-        col("Triacylglycerol")
-            .filter(col("FattyAcid").fa().is_unsaturated())
-            .sum()
-            .alias("U"),
-    ]);
-    println!("lazy_frame g0: {}", lazy_frame.clone().collect().unwrap());
-    let s = lazy_frame.clone().collect()?["_Sum"]
-        .f64()?
-        .first()
-        .unwrap();
-    println!("lazy_frame g1: {}", lazy_frame.clone().collect().unwrap());
-
-    exit(0);
-    // // Cartesian product (TAG from FA)
-    // let mut tags = fatty_acids.cartesian_product()?;
-    // // Filter
-    // tags = tags.filter(settings);
-
-    // // let gunstone = Gunstone::new(s);
-    // let lazy_frame = key.fatty_acids.0.clone().lazy();
-    // // lazy_frame = lazy_frame.select([
-    // //     col("Label"),
-    // //     col("Formula"),
-    // //     col("TAG.Experimental"),
-    // //     col("DAG1223.Experimental"),
-    // //     col("MAG2.Experimental"),
-    // //     col("DAG13.DAG1223.Calculated"),
-    // //     col("DAG13.MAG2.Calculated"),
-    // // ]);
-    // // lazy_frame = lazy_frame.with_columns([s().alias("S"), u().alias("U")]);
-    // println!("key.data_frame: {}", lazy_frame.clone().collect().unwrap());
-    // lazy_frame.collect().unwrap()
+            / triacylglycerol().sum())
+        .alias("Triacylglycerol")])
+        .collect()?;
+    let types = types(
+        data_frame["Triacylglycerol"]
+            .f64()?
+            .first()
+            .unwrap_or_default(),
+    );
+    lazy_frame = lazy_frame.select(select(&unique, propagate));
+    // Saturated part of each position
+    let data_frame = lazy_frame
+        .clone()
+        .select(
+            unique
+                .iter()
+                .map(|&name| {
+                    (col(name).filter(col("FattyAcid").fa().is_saturated()).sum() / col(name).sum())
+                        .alias(name)
+                })
+                .collect::<Vec<_>>(),
+        )
+        .collect()?;
+    let mut saturated = [0.0; 3];
+    for (saturated, name) in saturated.iter_mut().zip(names) {
+        *saturated = data_frame[name].f64()?.first().unwrap_or_default();
+    }
+    // Cartesian product (TAG from FA)
+    lazy_frame = cartesian_product(lazy_frame, names, propagate)?;
+    // Type factor
+    let is_saturated = |name| {
+        col("FattyAcid")
+            .struct_()
+            .field_by_name(name)
+            .fa()
+            .is_saturated()
+    };
+    let mut factor = lit(0.0);
+    for r#type in 0..8 {
+        let r#type = [r#type & 4 != 0, r#type & 2 != 0, r#type & 1 != 0];
+        let random = r#type
+            .iter()
+            .zip(saturated)
+            .map(|(&is_saturated, s)| if is_saturated { s } else { 1.0 - s })
+            .product::<f64>();
+        let value = types.fraction(r#type) / random;
+        factor = when(
+            is_saturated("StereospecificNumber1")
+                .eq(lit(r#type[0]))
+                .and(is_saturated("StereospecificNumber2").eq(lit(r#type[1])))
+                .and(is_saturated("StereospecificNumber3").eq(lit(r#type[2]))),
+        )
+        .then(lit(if value.is_finite() { value } else { 0.0 }))
+        .otherwise(factor);
+    }
+    // The types without the random species have no values, the rest are
+    // renormalized
+    let total = (col("Value") * factor.clone()).sum();
+    if propagate {
+        lazy_frame =
+            lazy_frame.with_column((col("Scale") * factor.clone() / total.clone()).alias("Scale"));
+    }
+    lazy_frame = lazy_frame.with_column((col("Value") * factor / total).alias("Value"));
+    Ok(lazy_frame)
 }
 
 // 1,3-sn 2-sn 1,2,3-sn
@@ -268,49 +478,215 @@ fn gunstone(mut lazy_frame: LazyFrame, settings: &Settings) -> PolarsResult<Lazy
 // [aba] = [a13]^2*[b2]
 // `2*[a_{13}]` - потому что зеркальные ([abc]=[cba], [aab]=[baa]).
 // SSC: [abc] = [a_{13}]*[b_2]*[c_{13}]
-fn vander_wal(mut lazy_frame: LazyFrame, settings: &Settings) -> PolarsResult<LazyFrame> {
-    lazy_frame = lazy_frame.select([
+fn vander_wal(mut lazy_frame: LazyFrame, propagate: bool) -> PolarsResult<LazyFrame> {
+    lazy_frame = lazy_frame.select(select(
+        &["Diacylglycerol13", "Monoacylglycerol2"],
+        propagate,
+    ));
+    // Cartesian product (TAG from FA)
+    lazy_frame = cartesian_product(
+        lazy_frame,
+        ["Diacylglycerol13", "Monoacylglycerol2", "Diacylglycerol13"],
+        propagate,
+    )?;
+    Ok(lazy_frame)
+}
+
+// 1,2,3-random
+// [abc] = [a123] * [b123] * [c123]
+fn random(mut lazy_frame: LazyFrame, propagate: bool) -> PolarsResult<LazyFrame> {
+    lazy_frame = lazy_frame.select(select(&["Triacylglycerol"], propagate));
+    cartesian_product(
+        lazy_frame,
+        ["Triacylglycerol", "Triacylglycerol", "Triacylglycerol"],
+        propagate,
+    )
+}
+
+// 1-random-2-random-3-random
+// [abc] = [a1] * [b2] * [c3], where [a3] = 2 * [a13] - [a1] (the negative
+// values are clipped and the rest renormalized)
+// The sn-1 profile (label and value) is measured, without it [a1] = [a3] =
+// [a13] (1,3-random-2-random).
+fn stereospecific(
+    mut lazy_frame: LazyFrame,
+    profile: &[(String, f64)],
+    propagate: bool,
+) -> PolarsResult<LazyFrame> {
+    if profile.is_empty() {
+        return vander_wal(lazy_frame, propagate);
+    }
+    lazy_frame = lazy_frame.select(select(
+        &["Diacylglycerol13", "Monoacylglycerol2"],
+        propagate,
+    ));
+    let labels = profile.iter().map(|(label, _)| label.as_str());
+    let values = profile.iter().map(|(_, value)| *value);
+    lazy_frame = lazy_frame.join(
+        df! {
+            "Label" => labels.collect::<Vec<_>>(),
+            "Profile" => values.collect::<Vec<_>>(),
+        }?
+        .lazy(),
+        [col("Label")],
+        [col("Label")],
+        JoinArgs::new(JoinType::Left),
+    );
+    let sn3 = lit(2) * col("Diacylglycerol13") - col("StereospecificNumber1");
+    lazy_frame = lazy_frame
+        .with_column(
+            col("Profile")
+                .fill_null(lit(0.0))
+                .alias("StereospecificNumber1"),
+        )
+        .with_column(
+            when(sn3.clone().lt(lit(0)))
+                .then(lit(0.0))
+                .otherwise(sn3)
+                .alias("StereospecificNumber3"),
+        );
+    let total = || col("StereospecificNumber3").sum();
+    // `u1 = 0` (measured), `u3 = 2 * u13 / ∑[a3]`
+    if propagate {
+        lazy_frame = lazy_frame.with_columns([
+            lit(0.0).alias("StereospecificNumber1Uncertainty"),
+            (lit(2) * col("Diacylglycerol13Uncertainty") / total())
+                .alias("StereospecificNumber3Uncertainty"),
+        ]);
+    }
+    lazy_frame = lazy_frame
+        .with_column((col("StereospecificNumber3") / total()).alias("StereospecificNumber3"));
+    cartesian_product(
+        lazy_frame,
+        [
+            "StereospecificNumber1",
+            "Monoacylglycerol2",
+            "StereospecificNumber3",
+        ],
+        propagate,
+    )
+}
+
+/// Measured sn-1 profile
+///
+/// Values are normalized by the total of the labels of the frame. The labels
+/// missing in the frame are unmatched (shown in the settings).
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Profile {
+    pub(crate) values: Vec<(String, f64)>,
+    pub(crate) unmatched: Vec<String>,
+}
+
+impl Profile {
+    pub(crate) fn new(text: &str, labels: &[&str]) -> Result<Self> {
+        let mut values = Vec::new();
+        let mut unmatched = Vec::new();
+        for (label, value) in profile(text)? {
+            ensure!(value >= 0.0, "negative value of {label}: {value}");
+            if labels.contains(&&*label) {
+                values.push((label, value));
+            } else {
+                unmatched.push(label);
+            }
+        }
+        let total = values.iter().map(|(_, value)| value).sum::<f64>();
+        if !values.is_empty() || !unmatched.is_empty() {
+            ensure!(total > 0.0, "no values for the labels of the frame");
+        }
+        for (_, value) in &mut values {
+            *value /= total;
+        }
+        Ok(Self { values, unmatched })
+    }
+}
+
+/// Selects the sn fractions (and their uncertainties)
+fn select(names: &[&str], propagate: bool) -> Vec<Expr> {
+    let mut exprs = vec![
         col("Index"),
         col("Label"),
         col("FattyAcid"),
-        col("Calculated")
-            .struct_()
-            .field_by_names(["Diacylglycerol13", "Monoacylglycerol2"]),
-    ]);
-    // Cartesian product (TAG from FA)
-    lazy_frame = cartesian_product(lazy_frame)?;
-    // Compose
-    lazy_frame = compose(lazy_frame, settings)?;
-    Ok(lazy_frame)
+        col("Calculated").struct_().field_by_names(names),
+    ];
+    if propagate {
+        for name in names {
+            exprs.push(
+                col("Uncertainty")
+                    .struct_()
+                    .field_by_name(name)
+                    .alias(format!("{name}Uncertainty")),
+            );
+        }
+    }
+    exprs
 }
 
-fn cartesian_product(mut lazy_frame: LazyFrame) -> PolarsResult<LazyFrame> {
+fn cartesian_product(
+    mut lazy_frame: LazyFrame,
+    values: [&str; 3],
+    propagate: bool,
+) -> PolarsResult<LazyFrame> {
+    let [sn1, sn2, sn3] = values;
+    // Total variance of each input (for the normalization)
+    if propagate {
+        for value in values {
+            lazy_frame = lazy_frame.with_column(
+                col(format!("{value}Uncertainty"))
+                    .pow(2)
+                    .sum()
+                    .alias(format!("{value}Variance")),
+            );
+        }
+    }
+    let stereospecific_number = |value: &str| {
+        let mut exprs = vec![col("Label"), col("FattyAcid"), col(value).alias("Value")];
+        if propagate {
+            exprs.push(col(format!("{value}Uncertainty")).alias("Uncertainty"));
+            exprs.push(col(format!("{value}Variance")).alias("Variance"));
+        }
+        as_struct(exprs)
+    };
     lazy_frame = lazy_frame
         .clone()
-        .select([as_struct(vec![
-            col("Label"),
-            col("FattyAcid"),
-            col("Diacylglycerol13").alias("Value"),
-        ])
-        .alias("StereospecificNumber1")])
+        .select([stereospecific_number(sn1).alias("StereospecificNumber1")])
         .cross_join(
-            lazy_frame.clone().select([as_struct(vec![
-                col("Label"),
-                col("FattyAcid"),
-                col("Monoacylglycerol2").alias("Value"),
-            ])
-            .alias("StereospecificNumber2")]),
+            lazy_frame
+                .clone()
+                .select([stereospecific_number(sn2).alias("StereospecificNumber2")]),
             None,
         )
         .cross_join(
-            lazy_frame.clone().select([as_struct(vec![
-                col("Label"),
-                col("FattyAcid"),
-                col("Diacylglycerol13").alias("Value"),
-            ])
-            .alias("StereospecificNumber3")]),
+            lazy_frame
+                .clone()
+                .select([stereospecific_number(sn3).alias("StereospecificNumber3")]),
             None,
         );
+    // Gradient over the inputs (the positions with the same input are merged
+    // in the composition)
+    // `∂x/∂x1 = x2 * x3`, `∂x/∂x2 = x1 * x3`, `∂x/∂x3 = x1 * x2`
+    if propagate {
+        let field = |name: &str, field| col(name).struct_().field_by_name(field);
+        let [x1, x2, x3] = STEREOSPECIFIC_NUMBERS.map(|name| field(name, "Value"));
+        let derivatives = [x2.clone() * x3.clone(), x1.clone() * x3, x1 * x2];
+        for (index, (name, derivative)) in STEREOSPECIFIC_NUMBERS
+            .into_iter()
+            .zip(derivatives)
+            .enumerate()
+        {
+            lazy_frame = lazy_frame.with_column(
+                as_struct(vec![
+                    lit(values[index]).alias("Input"),
+                    field(name, "Label"),
+                    derivative.alias("Derivative"),
+                    field(name, "Value"),
+                    field(name, "Uncertainty"),
+                    field(name, "Variance"),
+                ])
+                .alias(format!("Gradient{}", index + 1)),
+            );
+        }
+        lazy_frame = lazy_frame.with_column(lit(1.0).alias("Scale"));
+    }
     // Restruct
     lazy_frame = lazy_frame.select([
         as_struct(vec![
@@ -343,7 +719,7 @@ fn cartesian_product(mut lazy_frame: LazyFrame) -> PolarsResult<LazyFrame> {
                 .alias("StereospecificNumber3"),
         ])
         .alias("FattyAcid"),
-        col("StereospecificNumber1")
+        (col("StereospecificNumber1")
             .struct_()
             .field_by_name("Value")
             * col("StereospecificNumber2")
@@ -351,7 +727,10 @@ fn cartesian_product(mut lazy_frame: LazyFrame) -> PolarsResult<LazyFrame> {
                 .field_by_name("Value")
             * col("StereospecificNumber3")
                 .struct_()
-                .field_by_name("Value"),
+                .field_by_name("Value"))
+        .alias("Value"),
+        col(r#"^Gradient\d$"#),
+        col("^Scale$"),
     ]);
     Ok(lazy_frame)
 }
@@ -452,7 +831,11 @@ fn cartesian_product(mut lazy_frame: LazyFrame) -> PolarsResult<LazyFrame> {
 //     ]);
 //     Ok(lazy_frame)
 // }
-fn compose(mut lazy_frame: LazyFrame, settings: &Settings) -> PolarsResult<LazyFrame> {
+fn compose(
+    mut lazy_frame: LazyFrame,
+    settings: &Settings,
+    propagate: bool,
+) -> PolarsResult<LazyFrame> {
     // Composition
     for (index, selection) in settings.confirmed.selections.iter().enumerate() {
         lazy_frame = lazy_frame.with_column(
@@ -515,38 +898,141 @@ fn compose(mut lazy_frame: LazyFrame, settings: &Settings) -> PolarsResult<LazyF
                 .over([as_struct(vec![col(format!("^Key[0-{index}]$"))])])
                 .alias(format!("Value{index}")),
         );
+        // Variance
+        if propagate {
+            let group = format!("Group{index}");
+            lazy_frame = lazy_frame
+                .with_column(as_struct(vec![col(format!("^Key[0-{index}]$"))]).alias(&*group));
+            lazy_frame = lazy_frame.clone().join(
+                variance(lazy_frame, index)?,
+                [col(&*group)],
+                [col(&*group)],
+                JoinArgs::new(JoinType::Left),
+            );
+        }
     }
     // Group
-    lazy_frame = lazy_frame
-        .group_by([col(r#"^Key\d$"#), col(r#"^Value\d$"#)])
-        .agg([as_struct(vec![col("Label"), col("FattyAcid"), col("Value")]).alias("Species")]);
+    let mut by = vec![col(r#"^Key\d$"#), col(r#"^Value\d$"#)];
+    if propagate {
+        by.push(col(r#"^Variance\d$"#));
+    }
+    lazy_frame = lazy_frame.group_by(by).agg([as_struct(vec![
+        col("Label"),
+        col("FattyAcid"),
+        col("Value"),
+    ])
+    .alias("Species")]);
+    let values = if propagate {
+        (0..settings.confirmed.selections.len())
+            .map(|index| {
+                as_struct(vec![
+                    col(format!("Value{index}")).alias("Mean"),
+                    lit(NULL).cast(DataType::Float64).alias("StandardDeviation"),
+                    col(format!("Variance{index}")).sqrt().alias("Propagated"),
+                ])
+                .alias(format!("Value{index}"))
+            })
+            .collect()
+    } else {
+        vec![col(r#"^Value\d$"#)]
+    };
     lazy_frame = lazy_frame.select([
         as_struct(vec![col(r#"^Key\d$"#)]).alias("Keys"),
-        concat_arr(vec![col(r#"^Value\d$"#)])?.alias("Values"),
+        concat_arr(values)?.alias("Values"),
         col("Species"),
     ]);
     Ok(lazy_frame)
 }
 
+/// Variance of the group values
+///
+/// The gradients of the species over the inputs (the sn fractions of the
+/// labels) are summed over the group. The sn fractions sum to one, so the
+/// gradient is taken over the amounts before the normalization
+/// (`∂x_i/∂m_j = δ_ij - x_i`):
+/// `g'_j = g_j - ∑ x_i * g_i`, `u² = ∑ (g'_j * u_j)²`.
+fn variance(lazy_frame: LazyFrame, index: usize) -> PolarsResult<LazyFrame> {
+    let group = format!("Group{index}");
+    let group = group.as_str();
+    let gradients = (1..=3)
+        .map(|position| {
+            let field = |name| {
+                col(format!("Gradient{position}"))
+                    .struct_()
+                    .field_by_name(name)
+            };
+            lazy_frame.clone().select([
+                col(group),
+                field("Input"),
+                field("Label"),
+                (col("Scale") * field("Derivative")).alias("Derivative"),
+                field("Value"),
+                field("Uncertainty"),
+                field("Variance"),
+            ])
+        })
+        .collect::<Vec<_>>();
+    // `∑ (g_j - ḡ)² * u_j² = ∑ g_j² * u_j² - 2 * ḡ * ∑ g_j * u_j² + ḡ² * ∑ u_j²`,
+    // where `ḡ = ∑ x_i * g_i` and `∑ u_j²` is over all labels of the input
+    let variance = col("Squares") - lit(2) * col("Mean") * col("Products")
+        + col("Mean").pow(2) * col("Variance");
+    Ok(concat(gradients, UnionArgs::default())?
+        .group_by([col(group), col("Input"), col("Label")])
+        .agg([
+            col("Derivative").sum(),
+            col("Value").first(),
+            col("Uncertainty").first(),
+            col("Variance").first(),
+        ])
+        .group_by([col(group), col("Input")])
+        .agg([
+            (col("Derivative") * col("Uncertainty"))
+                .pow(2)
+                .sum()
+                .alias("Squares"),
+            (col("Derivative") * col("Uncertainty").pow(2))
+                .sum()
+                .alias("Products"),
+            (col("Derivative") * col("Value")).sum().alias("Mean"),
+            col("Variance").first(),
+        ])
+        .group_by([col(group)])
+        .agg([when(variance.clone().gt(lit(0)))
+            .then(variance)
+            .otherwise(lit(0.0))
+            .sum()
+            .alias(format!("Variance{index}"))]))
+}
+
 fn meta(mut lazy_frame: LazyFrame, settings: &Settings) -> PolarsResult<LazyFrame> {
     // TODO [array_get?](https://docs.rs/polars/latest/polars/prelude/array/trait.ArrayNameSpace.html)
     let values = |index| {
         concat_list([all()
-            .exclude(["Keys", r#"^Value\d$"#])
+            .exclude(["Keys", r#"^Value\d$"#, r#"^Propagated\d$"#])
             .arr()
             .get(lit(index as u32), false)])
     };
+    let groups = Groups::new(&settings.groups);
     for index in 0..settings.confirmed.selections.len() {
-        lazy_frame = lazy_frame.with_column(
-            as_struct(vec![
-                values(index)?.list().mean().alias("Mean"),
-                values(index)?
-                    .list()
-                    .std(settings.confirmed.ddof)
-                    .alias("StandardDeviation"),
-            ])
-            .alias(format!("Value{index}")),
-        );
+        let mut fields = vec![values(index)?.list().mean().alias("Mean")];
+        fields.extend(dispersion(
+            || values(index),
+            settings.confirmed.ddof,
+            settings.confirmed.dispersion,
+            "StandardDeviation",
+        )?);
+        if settings.confirmed.propagate {
+            fields.push(col(format!("Propagated{index}")).alias("Propagated"));
+        }
+        if groups.is_testable() {
+            fields.push(groups.means(values(index)?));
+            fields.push(groups.standard_deviations(values(index)?, settings.confirmed.ddof));
+            fields.push(groups.p_value(values(index)?));
+            if groups.names.len() > 2 {
+                fields.push(groups.tukey(values(index)?));
+            }
+        }
+        lazy_frame = lazy_frame.with_column(as_struct(fields).alias(format!("Value{index}")));
     }
     // Group
     lazy_frame = lazy_frame.select([
@@ -556,81 +1042,74 @@ fn meta(mut lazy_frame: LazyFrame, settings: &Settings) -> PolarsResult<LazyFram
     Ok(lazy_frame)
 }
 
-fn filter(mut lazy_frame: LazyFrame, settings: &Settings) -> LazyFrame {
-    println!("lazy_frame: {}", lazy_frame.clone().collect().unwrap());
-    if !settings.confirmed.show_filtered {
-        let mut predicate = lit(true);
-        for (index, selection) in settings.confirmed.selections.iter().enumerate() {
-            // Key
-            // for (key, value) in &group.filter.key {
-            //     // match group.composition {
-            //     //     SC => {
-            //     //         let expr = col("Keys").struct_().field_by_index(index as _).tag();
-            //     //         if value[0] {
-            //     //             predicate = predicate
-            //     //                 .and(expr.clone().sn1().neq(lit(LiteralValue::from(key.clone()))));
-            //     //         }
-            //     //         if value[1] {
-            //     //             predicate = predicate
-            //     //                 .and(expr.clone().sn2().neq(lit(LiteralValue::from(key.clone()))));
-            //     //         }
-            //     //         if value[2] {
-            //     //             predicate = predicate
-            //     //                 .and(expr.clone().sn3().neq(lit(LiteralValue::from(key.clone()))));
-            //     //         }
-            //     //     }
-            //     //     PSC => {
-            //     //         let expr = col("Keys").struct_().field_by_index(index as _).tag();
-            //     //         predicate = predicate
-            //     //             .and(expr.clone().sn1().neq(lit(LiteralValue::from(key.clone()))))
-            //     //             .and(expr.clone().sn2().neq(lit(LiteralValue::from(key.clone()))));
-            //     //     }
-            //     //     // MC | ECNC | UC => {
-            //     //     //     let expr = col("Keys").struct_().field_by_index(index as _);
-            //     //     //     predicate = predicate.and(expr.neq(lit(LiteralValue::from(key.clone()))));
-            //     //     // }
-            //     //     _ => {
-            //     //         // let expr = col("Keys")
-            //     //         //     .struct_()
-            //     //         //     .field_by_index(index as _)
-            //     //         //     .triacylglycerol();
-            //     //         // predicate = predicate.and(expr.sn1().neq(lit(key.to_string())));
-            //     //     }
-            //     // }
-            //     let expr = col("Keys").struct_().field_by_index(index as _);
-            //     match group.composition {
-            //         MC => {
-            //             predicate = predicate.and(expr.neq(lit(LiteralValue::from(key.clone()))));
-            //         }
-            //         _ => {
-            //             let expr = expr.tag();
-            //             if value[0] {
-            //                 predicate = predicate
-            //                     .and(expr.clone().sn1().neq(lit(LiteralValue::from(key.clone()))));
-            //             }
-            //             if value[1] {
-            //                 predicate = predicate
-            //                     .and(expr.clone().sn2().neq(lit(LiteralValue::from(key.clone()))));
-            //             }
-            //             if value[2] {
-            //                 predicate = predicate
-            //                     .and(expr.clone().sn3().neq(lit(LiteralValue::from(key.clone()))));
-            //             }
-            //         }
-            //     }
-            // }
-            // Value
-            let mut expr = col("Values").arr().get(lit(index as u32), false);
-            if settings.index.is_none() {
-                expr = expr.struct_().field_by_name("Mean");
+/// Values are structs with the mean (replicates or propagated uncertainty)
+fn is_struct(settings: &Settings) -> bool {
+    settings.index.is_none() || settings.confirmed.propagate
+}
+
+/// Filters the excluded keys and the values below the threshold
+///
+/// With `show_filtered` the rows are kept and marked in the `Filtered` column.
+pub(super) fn filter(mut lazy_frame: LazyFrame, settings: &Settings) -> LazyFrame {
+    let mut predicate = lit(true);
+    for (index, selection) in settings.confirmed.selections.iter().enumerate() {
+        // Key
+        let key = col("Keys").struct_().field_by_index(index as _);
+        match selection.composition {
+            MNC | NNC | UNC => {
+                predicate = predicate.and(is_excluded(key, &selection.filter.keys[0]).not());
+            }
+            _ => {
+                for (name, keys) in STEREOSPECIFIC_NUMBERS
+                    .into_iter()
+                    .zip(&selection.filter.keys)
+                {
+                    let key = key.clone().struct_().field_by_name(name);
+                    predicate = predicate.and(is_excluded(key, keys).not());
+                }
             }
-            // predicate = predicate.and(expr.gt(lit(selection.filter.value)));
         }
-        lazy_frame = lazy_frame.filter(predicate);
+        // Value
+        if selection.filter.value > 0.0 {
+            let mut value = col("Values").arr().get(lit(index as u32), false);
+            if is_struct(settings) {
+                value = value.struct_().field_by_name("Mean");
+            }
+            predicate = predicate.and(
+                value
+                    .gt_eq(lit(selection.filter.value))
+                    .fill_null(lit(false)),
+            );
+        }
+    }
+    lazy_frame = lazy_frame.with_column(predicate.not().alias("Filtered"));
+    if !settings.confirmed.show_filtered {
+        lazy_frame = lazy_frame.filter(col("Filtered").not());
     }
     lazy_frame
 }
 
+/// Key is in the excluded keys
+fn is_excluded(key: Expr, keys: &HashSet<AnyValue<'static>>) -> Expr {
+    if keys.is_empty() {
+        return lit(false);
+    }
+    let keys = keys.clone();
+    key.map(
+        move |column| {
+            Ok(Some(
+                column
+                    .as_materialized_series()
+                    .iter()
+                    .map(|key| keys.contains(&key.into_static()))
+                    .collect::<BooleanChunked>()
+                    .into_column(),
+            ))
+        },
+        GetOutput::from_type(DataType::Boolean),
+    )
+}
+
 fn sort(mut lazy_frame: LazyFrame, settings: &Settings) -> LazyFrame {
     let mut sort_options = SortMultipleOptions::default();
     if let Order::Descending = settings.confirmed.order {
@@ -642,7 +1121,7 @@ fn sort(mut lazy_frame: LazyFrame, settings: &Settings) -> LazyFrame {
         Sort::Key => lazy_frame.sort_by_exprs([col("Keys")], sort_options),
         Sort::Value => {
             let mut expr = col("Values");
-            if settings.index.is_none() {
+            if is_struct(settings) {
                 expr = expr
                     .arr()
                     .to_list()
@@ -890,48 +1369,74 @@ fn sort(mut lazy_frame: LazyFrame, settings: &Settings) -> LazyFrame {
 //     }
 // }
 
-/// Gunstone
-struct Gunstone {
-    s: f64,
-    u: f64,
+/// Triacylglycerol type composition
+struct Types {
     s3: f64,
     s2u: f64,
     su2: f64,
     u3: f64,
+    /// Unsaturated acids occupy sn-2 first
+    sn2_unsaturated: bool,
 }
 
-impl Gunstone {
-    fn new(s: f64) -> Self {
+impl Types {
+    /// Gunstone's theory
+    fn gunstone(s: f64) -> Self {
         let u = 1.0 - s;
         if s <= 2.0 / 3.0 {
             Self {
-                s,
-                u,
                 s3: 0.0,
                 s2u: (3.0 * s / 2.0).powi(2),
                 su2: 3.0 * s * (3.0 * u - 1.0) / 2.0,
                 u3: ((3.0 * u - 1.0) / 2.0).powi(2),
+                sn2_unsaturated: true,
             }
         } else {
             Self {
-                s,
-                u,
                 s3: 3.0 * s - 2.0,
                 s2u: 3.0 * u,
                 su2: 0.0,
                 u3: 0.0,
+                sn2_unsaturated: true,
             }
         }
     }
 
-    // fn factor(&self, r#type: Tag<Saturation>) -> f64 {
-    //     match r#type.into() {
-    //         S3 => self.s3 / self.s.powi(3),                    // [SSS]
-    //         S2U => self.s2u / (self.s.powi(2) * self.u) / 3.0, // [SSU], [USS], [SUS]
-    //         SU2 => self.su2 / (self.s * self.u.powi(2)) / 3.0, // [SUU], [USU], [UUS]
-    //         U3 => self.u3 / self.u.powi(3),                    // [UUU]
-    //     }
-    // }
+    /// Kartha's restricted random theory
+    ///
+    /// 1,2,3-random types with the trisaturated species limited, the excess
+    /// exchanges the acids with the triunsaturated species (`S3 + U3 = S2U +
+    /// SU2`).
+    fn kartha(s: f64, limit: f64) -> Self {
+        let u = 1.0 - s;
+        let excess = (s.powi(3) - limit).clamp(0.0, u.powi(3));
+        Self {
+            s3: s.powi(3) - excess,
+            s2u: 3.0 * s.powi(2) * u + excess,
+            su2: 3.0 * s * u.powi(2) + excess,
+            u3: u.powi(3) - excess,
+            sn2_unsaturated: false,
+        }
+    }
+
+    /// Fraction of the positional type (saturated at sn-1, sn-2 and sn-3)
+    fn fraction(&self, saturated: [bool; 3]) -> f64 {
+        let count = saturated.iter().filter(|&&saturated| saturated).count();
+        match count {
+            3 => self.s3,
+            0 => self.u3,
+            // [SUS]
+            2 if self.sn2_unsaturated && saturated[1] => 0.0,
+            2 if self.sn2_unsaturated => self.s2u,
+            // [SUU], [UUS]
+            1 if self.sn2_unsaturated && saturated[1] => 0.0,
+            1 if self.sn2_unsaturated => self.su2 / 2.0,
+            // [SSU], [USS], [SUS]
+            2 => self.s2u / 3.0,
+            // [SUU], [USU], [UUS]
+            _ => self.su2 / 3.0,
+        }
+    }
 }
 
 // fn discriminated(context: &Context, sn: Sn) -> Vec<f64> {
@@ -956,3 +1461,265 @@ impl Gunstone {
 //         })
 //         .normalized()
 // }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{parsers::clipboard::fatty_acid, utils::polars::fatty_acids};
+
+    /// Saturated (`16:0`) and unsaturated (`18:1`) fatty acids, the calculated
+    /// TAG is not consistent with the positional values
+    fn saturated_unsaturated() -> PolarsResult<LazyFrame> {
+        let fatty_acids = fatty_acids(
+            "FattyAcid".into(),
+            &[fatty_acid("16:0").ok(), fatty_acid("18:1").ok()],
+        )?;
+        let mut data_frame = df! {
+            "Index" => [0u32, 1],
+            "Label" => ["S", "U"],
+            "Triacylglycerol" => [0.3, 0.7],
+            "Diacylglycerol13" => [0.5, 0.5],
+            "Monoacylglycerol2" => [0.2, 0.8],
+        }?;
+        data_frame.with_column(fatty_acids)?;
+        Ok(data_frame.lazy().select([
+            col("Index"),
+            col("Label"),
+            col("FattyAcid"),
+            as_struct(vec![
+                col("Triacylglycerol"),
+                col("Diacylglycerol13"),
+                col("Monoacylglycerol2"),
+            ])
+            .alias("Calculated"),
+        ]))
+    }
+
+    /// Values of the U3, SU2, S2U and S3 types
+    fn types(lazy_frame: LazyFrame) -> PolarsResult<Vec<f64>> {
+        let is_saturated = |name| {
+            col("FattyAcid")
+                .struct_()
+                .field_by_name(name)
+                .fa()
+                .is_saturated()
+                .cast(DataType::UInt32)
+        };
+        let data_frame = lazy_frame
+            .select([
+                STEREOSPECIFIC_NUMBERS
+                    .map(is_saturated)
+                    .into_iter()
+                    .reduce(|sum, saturated| sum + saturated)
+                    .unwrap()
+                    .alias("Saturated"),
+                col("Value"),
+            ])
+            .group_by([col("Saturated")])
+            .agg([col("Value").sum()])
+            .sort(["Saturated"], Default::default())
+            .collect()?;
+        let mut types = vec![0.0; 4];
+        for (saturated, value) in data_frame["Saturated"]
+            .u32()?
+            .into_no_null_iter()
+            .zip(data_frame["Value"].f64()?.into_no_null_iter())
+        {
+            types[saturated as usize] = value;
+        }
+        Ok(types)
+    }
+
+    #[test]
+    fn gunstone() -> PolarsResult<()> {
+        let lazy_frame = restricted(
+            saturated_unsaturated()?,
+            ["Diacylglycerol13", "Monoacylglycerol2", "Diacylglycerol13"],
+            Types::gunstone,
+            false,
+        )?;
+        // `S = 0.3` of the TAG: `U3 = ((3U - 1) / 2)²`, `SU2 = 3S(3U - 1) / 2`,
+        // `S2U = (3S / 2)²`, `S3 = 0`
+        let types = types(lazy_frame)?;
+        for (value, expected) in types.iter().zip([0.3025, 0.495, 0.2025, 0.0]) {
+            assert!((value - expected).abs() < 1e-9, "{value} != {expected}");
+        }
+        assert!((types.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        Ok(())
+    }
+
+    #[test]
+    fn kartha() -> PolarsResult<()> {
+        let lazy_frame = restricted(
+            saturated_unsaturated()?,
+            ["Triacylglycerol", "Triacylglycerol", "Triacylglycerol"],
+            |s| Types::kartha(s, 0.01),
+            false,
+        )?;
+        // `S³ = 0.027` is limited to `0.01`, the excess `0.017` is exchanged
+        let types = types(lazy_frame)?;
+        for (value, expected) in types.iter().zip([0.326, 0.458, 0.206, 0.01]) {
+            assert!((value - expected).abs() < 1e-9, "{value} != {expected}");
+        }
+        assert!((types.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        Ok(())
+    }
+
+    /// sn fractions with the uncertainties
+    fn uncertain() -> PolarsResult<LazyFrame> {
+        let fatty_acids = fatty_acids(
+            "FattyAcid".into(),
+            &[fatty_acid("16:0").ok(), fatty_acid("18:1").ok()],
+        )?;
+        let mut data_frame = df! {
+            "Index" => [0u32, 1],
+            "Label" => ["A", "B"],
+            "Triacylglycerol" => [0.5, 0.5],
+            "Diacylglycerol13" => [0.6, 0.4],
+            "Monoacylglycerol2" => [0.3, 0.7],
+            "Diacylglycerol13Uncertainty" => [0.01, 0.02],
+            "Monoacylglycerol2Uncertainty" => [0.03, 0.04],
+        }?;
+        data_frame.with_column(fatty_acids)?;
+        let lazy_frame = data_frame.lazy().select([
+            col("Index"),
+            col("Label"),
+            col("FattyAcid"),
+            as_struct(vec![
+                col("Triacylglycerol"),
+                col("Diacylglycerol13"),
+                col("Monoacylglycerol2"),
+            ])
+            .alias("Calculated"),
+            as_struct(vec![
+                lit(0.0).alias("Triacylglycerol"),
+                col("Diacylglycerol13Uncertainty").alias("Diacylglycerol13"),
+                col("Monoacylglycerol2Uncertainty").alias("Monoacylglycerol2"),
+            ])
+            .alias("Uncertainty"),
+        ]);
+        Ok(lazy_frame)
+    }
+
+    #[test]
+    fn propagated() -> PolarsResult<()> {
+        let mut settings = Settings::new(Some(0));
+        settings.confirmed.selections.push_back(Selection {
+            composition: SSC,
+            filter: Filter::new(),
+        });
+        let label = |name| {
+            col("Keys")
+                .struct_()
+                .field_by_name("Key0")
+                .struct_()
+                .field_by_name(name)
+        };
+        let data_frame = compose(vander_wal(uncertain()?, true)?, &settings, true)?
+            .filter(
+                label("StereospecificNumber1")
+                    .eq(lit("A"))
+                    .and(label("StereospecificNumber2").eq(lit("B")))
+                    .and(label("StereospecificNumber3").eq(lit("A"))),
+            )
+            .select([col("Values")
+                .arr()
+                .get(lit(0u32), false)
+                .struct_()
+                .field_by_name("Propagated")])
+            .collect()?;
+        let propagated = data_frame["Propagated"].f64()?.get(0).unwrap();
+        // [ABA] = x_A² * y_B, sn-1 and sn-3 share the 1,3-DAG:
+        // `g(x) = (2 * x_A * y_B, 0)`, `g(y) = (0, x_A²)`, centered by the
+        // normalization `ḡ(x) = x_A * g_A(x)`, `ḡ(y) = y_B * g_B(y)`
+        let (x, y) = ([0.6, 0.4], [0.3, 0.7]);
+        let (u, v) = ([0.01, 0.02], [0.03, 0.04]);
+        let gx = [2.0 * x[0] * y[1], 0.0];
+        let gy = [0.0, x[0] * x[0]];
+        let mx = x[0] * gx[0];
+        let my = y[1] * gy[1];
+        let expected = ((gx[0] - mx) * u[0]).powi(2)
+            + ((gx[1] - mx) * u[1]).powi(2)
+            + ((gy[0] - my) * v[0]).powi(2)
+            + ((gy[1] - my) * v[1]).powi(2);
+        assert!(
+            (propagated.powi(2) - expected).abs() < 1e-12,
+            "{} != {expected}",
+            propagated.powi(2),
+        );
+        // The total of all species is exactly one
+        let lazy_frame = vander_wal(uncertain()?, true)?.with_column(lit(0).alias("Group0"));
+        let data_frame = variance(lazy_frame, 0)?.collect()?;
+        let variance = data_frame["Variance0"].f64()?.get(0).unwrap();
+        assert!(variance.abs() < 1e-12, "{variance} != 0");
+        Ok(())
+    }
+
+    #[test]
+    fn stereospecific() -> PolarsResult<()> {
+        // `[B3] = 2 * 0.4 - 0.9 < 0` is clipped, `[A3] = 1.1` is renormalized
+        let profile = [("A".to_owned(), 0.1), ("B".to_owned(), 0.9)];
+        let data_frame = super::stereospecific(uncertain()?, &profile, false)?
+            .select([col("Value").sum()])
+            .collect()?;
+        let total = data_frame["Value"].f64()?.get(0).unwrap();
+        assert!((total - 1.0).abs() < 1e-12, "{total} != 1");
+        Ok(())
+    }
+
+    #[test]
+    fn test() -> PolarsResult<()> {
+        let lazy_frame = df! {
+            "StereospecificNumber1" => ["P", "P", "O", "S"],
+            "StereospecificNumber2" => ["O", "L", "O", "O"],
+            "StereospecificNumber3" => ["P", "P", "O", "P"],
+            "Value" => [0.3, 0.4, 0.25, 0.05],
+        }?
+        .lazy()
+        .select([
+            as_struct(vec![
+                as_struct(STEREOSPECIFIC_NUMBERS.map(col).to_vec()).alias("Key0"),
+            ])
+            .alias("Keys"),
+            concat_arr(vec![col("Value")])?.alias("Values"),
+        ]);
+        let mut settings = Settings::new(Some(0));
+        let mut filter = Filter::new();
+        filter.keys[1].insert(AnyValue::StringOwned("L".into()));
+        filter.value = 0.1;
+        settings.confirmed.selections.push_back(Selection {
+            composition: SSC,
+            filter,
+        });
+        let keys = |data_frame: &DataFrame| -> PolarsResult<Vec<String>> {
+            let keys = data_frame["Keys"].struct_()?.field_by_name("Key0")?;
+            let sn2 = keys.struct_()?.field_by_name("StereospecificNumber2")?;
+            Ok(sn2
+                .str()?
+                .into_iter()
+                .flatten()
+                .map(ToOwned::to_owned)
+                .collect())
+        };
+        // Excluded sn-2 key and the value below the threshold are filtered
+        let data_frame = self::filter(lazy_frame.clone(), &settings).collect()?;
+        assert_eq!(keys(&data_frame)?, ["O", "O"]);
+        assert!(!data_frame["Filtered"].bool()?.any());
+        // With show filtered the rows are kept and marked
+        settings.confirmed.show_filtered = true;
+        let data_frame = self::filter(lazy_frame.clone(), &settings).collect()?;
+        assert_eq!(keys(&data_frame)?, ["O", "L", "O", "O"]);
+        assert_eq!(
+            data_frame["Filtered"]
+                .bool()?
+                .into_iter()
+                .collect::<Vec<_>>(),
+            [Some(false), Some(true), Some(false), Some(true)],
+        );
+        // Without the exclusions and the threshold nothing is filtered
+        settings.confirmed.selections[0].filter = Filter::new();
+        let data_frame = self::filter(lazy_frame, &settings).collect()?;
+        assert!(!data_frame["Filtered"].bool()?.any());
+        Ok(())
+    }
+}
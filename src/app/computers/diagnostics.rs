@@ -0,0 +1,119 @@
+use super::calculation::Key as CalculationKey;
+use crate::app::panes::calculation::settings::{From, Settings};
+use egui::util::cache::{ComputerMut, FrameCache};
+use metadata::MetaDataFrame;
+use polars::prelude::*;
+use std::hash::{Hash, Hasher};
+
+/// Diagnostics computed
+pub(crate) type Computed = FrameCache<Value, Computer>;
+
+/// Mass balance diagnostics computer
+///
+/// 3 * TAG = 4 * DAG1223 - MAG2 = 2 * DAG13 + MAG2
+#[derive(Default)]
+pub(crate) struct Computer;
+
+impl Computer {
+    fn try_compute(&mut self, key: Key) -> PolarsResult<Diagnostics> {
+        let settings = key.settings;
+        let value = |name: &str, field: &str| {
+            let expr = col(name).struct_().field_by_name(field);
+            if settings.index.is_none() {
+                expr.struct_().field_by_name("Mean")
+            } else {
+                expr
+            }
+        };
+        let tag = || value("Experimental", "Triacylglycerol");
+        let dag1223 = || value("Experimental", "Diacylglycerol1223");
+        let mag2 = || value("Experimental", "Monoacylglycerol2");
+        let residual =
+            |name| (value("Experimental", name) - value("Theoretical", name)).alias(name);
+        let clipped = |expr: Expr| lit(settings.unsigned).and(expr.lt(lit(0)));
+        let lazy_frame = key.data_frame.clone().lazy();
+        let data = lazy_frame
+            .clone()
+            .select([
+                col("Index"),
+                col("Label"),
+                col("FattyAcid"),
+                as_struct(vec![
+                    residual("Triacylglycerol"),
+                    residual("Diacylglycerol1223"),
+                    residual("Monoacylglycerol2"),
+                ])
+                .alias("Residual"),
+                (lit(3) * tag() + mag2() - lit(4) * dag1223()).alias("Balance"),
+                as_struct(vec![
+                    clipped((lit(4) * dag1223() - mag2()) / lit(3)).alias("Triacylglycerol"),
+                    clipped(lit(4) * dag1223() - lit(3) * tag()).alias("Monoacylglycerol2"),
+                    clipped(match settings.from {
+                        From::Dag1223 => lit(3) * tag() - lit(2) * dag1223(),
+                        From::Mag2 => (lit(3) * tag() - mag2()) / lit(2),
+                    })
+                    .alias("Diacylglycerol13"),
+                ])
+                .alias("Clipped"),
+            ])
+            .collect()?;
+        let score = lazy_frame
+            .select([
+                (lit(3) * tag() + mag2() - lit(4) * dag1223())
+                    .pow(2)
+                    .mean()
+                    .sqrt()
+                    .alias("RootMeanSquare"),
+                (lit(1)
+                    - (lit(3) * tag() + mag2() - lit(4) * dag1223()).abs().sum()
+                        / (lit(3) * tag() + mag2() + lit(4) * dag1223()).sum())
+                .alias("GoodnessOfFit"),
+            ])
+            .collect()?;
+        Ok(Diagnostics {
+            data,
+            root_mean_square: score["RootMeanSquare"].f64()?.get(0),
+            goodness_of_fit: score["GoodnessOfFit"].f64()?.get(0),
+        })
+    }
+}
+
+impl ComputerMut<Key<'_>, Value> for Computer {
+    fn compute(&mut self, key: Key) -> Value {
+        self.try_compute(key).map_err(|error| error.to_string())
+    }
+}
+
+/// Diagnostics key
+///
+/// The data frame is computed from the frames with the settings. Read-only
+/// panes (without the source data) are not cached.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Key<'a> {
+    pub(crate) frames: &'a [MetaDataFrame],
+    pub(crate) data_frame: &'a DataFrame,
+    pub(crate) settings: &'a Settings,
+}
+
+impl Hash for Key<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        CalculationKey {
+            frames: self.frames,
+            settings: self.settings,
+        }
+        .hash(state);
+    }
+}
+
+/// Diagnostics value (the error is shown in the window)
+type Value = Result<Diagnostics, String>;
+
+/// Mass balance diagnostics
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Diagnostics {
+    /// Residuals, balance and clipped values of each fatty acid
+    pub(crate) data: DataFrame,
+    pub(crate) root_mean_square: Option<f64>,
+    /// `1 - ∑|3 * TAG + MAG2 - 4 * DAG1223| / ∑(3 * TAG + MAG2 + 4 * DAG1223)`
+    pub(crate) goodness_of_fit: Option<f64>,
+}
@@ -0,0 +1,116 @@
+use crate::{
+    app::panes::calculation::settings::{Dispersion, DispersionKind},
+    utils::statistics::t_quantile,
+};
+use polars::prelude::*;
+
+/// Dispersion fields of the frame values list
+///
+/// The standard deviation is named `standard_deviation`, the others are
+/// `StandardError`, `ConfidenceInterval` (half width) or `Minimum` and
+/// `Maximum`.
+pub(crate) fn dispersion(
+    values: impl Fn() -> PolarsResult<Expr>,
+    ddof: u8,
+    dispersion: Dispersion,
+    standard_deviation: &str,
+) -> PolarsResult<Vec<Expr>> {
+    let std = || PolarsResult::Ok(values()?.list().std(ddof));
+    let count = || {
+        PolarsResult::Ok(
+            values()?
+                .list()
+                .eval(col("").drop_nulls(), false)
+                .list()
+                .len()
+                .cast(DataType::Float64),
+        )
+    };
+    Ok(match dispersion.kind {
+        DispersionKind::StandardDeviation => vec![std()?.alias(standard_deviation)],
+        DispersionKind::StandardError => {
+            vec![(std()? / count()?.sqrt()).alias("StandardError")]
+        }
+        DispersionKind::ConfidenceInterval => {
+            // Two-sided Student's t quantile with `n - 1` degrees of freedom
+            let probability = (1.0 + dispersion.confidence) / 2.0;
+            let t = count()?.map(
+                move |column| {
+                    Ok(Some(
+                        column
+                            .f64()?
+                            .into_iter()
+                            .map(|count| {
+                                count
+                                    .filter(|&count| count > 1.0)
+                                    .map(|count| t_quantile(probability, count - 1.0))
+                            })
+                            .collect::<Float64Chunked>()
+                            .into_column(),
+                    ))
+                },
+                GetOutput::from_type(DataType::Float64),
+            );
+            vec![(t * std()? / count()?.sqrt()).alias("ConfidenceInterval")]
+        }
+        DispersionKind::Range => vec![
+            values()?.list().min().alias("Minimum"),
+            values()?.list().max().alias("Maximum"),
+        ],
+    })
+}
+
+/// Dispersion of the mean: `±` value or range
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Spread<T> {
+    Interval(T),
+    Range(T, T),
+}
+
+impl Spread<&'static str> {
+    /// Dispersion field names of the mean struct
+    pub(crate) fn new(fields: &[Field]) -> Option<Self> {
+        let contains = |name: &str| fields.iter().any(|field| field.name() == name);
+        if contains("Minimum") && contains("Maximum") {
+            return Some(Self::Range("Minimum", "Maximum"));
+        }
+        [
+            "StandardDeviation",
+            "StandardDeviations",
+            "StandardError",
+            "ConfidenceInterval",
+        ]
+        .into_iter()
+        .find(|name| contains(name))
+        .map(Self::Interval)
+    }
+
+    /// Localization key of the dispersion
+    pub(crate) fn text(self) -> &'static str {
+        match self {
+            Self::Interval("StandardError") => "settings-standard_error",
+            Self::Interval("ConfidenceInterval") => "settings-confidence_interval",
+            Self::Interval(_) => "settings-standard_deviation",
+            Self::Range(..) => "settings-range",
+        }
+    }
+}
+
+impl Spread<Series> {
+    /// Dispersion fields of the mean struct
+    pub(crate) fn fields(r#struct: &StructChunked) -> PolarsResult<Self> {
+        let Some(names) = Spread::new(r#struct.struct_fields()) else {
+            polars_bail!(StructFieldNotFound: "dispersion");
+        };
+        names.try_map(|name| r#struct.field_by_name(name))
+    }
+}
+
+impl<T> Spread<T> {
+    pub(crate) fn try_map<U>(self, f: impl Fn(T) -> PolarsResult<U>) -> PolarsResult<Spread<U>> {
+        Ok(match self {
+            Self::Interval(value) => Spread::Interval(f(value)?),
+            Self::Range(minimum, maximum) => Spread::Range(f(minimum)?, f(maximum)?),
+        })
+    }
+}
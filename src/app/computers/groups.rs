@@ -0,0 +1,176 @@
+use crate::utils::statistics::{anova, tukey, welch};
+use polars::prelude::*;
+use std::sync::Arc;
+
+/// Groups of the frames
+///
+/// Frames with the same (not empty) group name are the replicates of one
+/// group.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Groups {
+    pub(crate) names: Vec<String>,
+    indices: Arc<[Option<usize>]>,
+}
+
+impl Groups {
+    pub(crate) fn new(groups: &[String]) -> Self {
+        let mut names = Vec::<String>::new();
+        let indices = groups
+            .iter()
+            .map(|group| {
+                if group.is_empty() {
+                    return None;
+                }
+                Some(match names.iter().position(|name| name == group) {
+                    Some(index) => index,
+                    None => {
+                        names.push(group.clone());
+                        names.len() - 1
+                    }
+                })
+            })
+            .collect();
+        Self { names, indices }
+    }
+
+    /// Tests need two groups or more
+    pub(crate) fn is_testable(&self) -> bool {
+        self.names.len() > 1
+    }
+
+    /// Group name pairs in the order of the Tukey p-values
+    pub(crate) fn pairs(&self) -> Vec<(&str, &str)> {
+        let mut pairs = Vec::new();
+        for i in 0..self.names.len() {
+            for j in i + 1..self.names.len() {
+                pairs.push((&*self.names[i], &*self.names[j]));
+            }
+        }
+        pairs
+    }
+
+    /// Between-group p-value of the frame values list (Welch t-test for two
+    /// groups, one-way ANOVA for more)
+    pub(crate) fn p_value(&self, values: Expr) -> Expr {
+        let groups = self.clone();
+        values
+            .map(
+                move |column| {
+                    let mut p_values = Vec::with_capacity(column.len());
+                    for series in column.list()? {
+                        p_values.push(match series {
+                            Some(series) => {
+                                let samples = groups.samples(&series)?;
+                                let samples = samples.iter().map(Vec::as_slice).collect::<Vec<_>>();
+                                match &*samples {
+                                    [a, b] => welch(a, b),
+                                    _ => anova(&samples).map(|anova| anova.p_value),
+                                }
+                            }
+                            None => None,
+                        });
+                    }
+                    Ok(Some(Series::new(PlSmallStr::EMPTY, p_values).into_column()))
+                },
+                GetOutput::from_type(DataType::Float64),
+            )
+            .alias("PValue")
+    }
+
+    /// Tukey HSD p-values of the group pairs
+    pub(crate) fn tukey(&self, values: Expr) -> Expr {
+        let groups = self.clone();
+        values
+            .map(
+                move |column| {
+                    let mut p_values = Vec::with_capacity(column.len());
+                    for series in column.list()? {
+                        p_values.push(match series {
+                            Some(series) => {
+                                let samples = groups.samples(&series)?;
+                                let samples = samples.iter().map(Vec::as_slice).collect::<Vec<_>>();
+                                tukey(&samples)
+                                    .map(|p_values| Series::new(PlSmallStr::EMPTY, p_values))
+                            }
+                            None => None,
+                        });
+                    }
+                    Ok(Some(
+                        p_values
+                            .into_iter()
+                            .collect::<ListChunked>()
+                            .into_series()
+                            .into_column(),
+                    ))
+                },
+                GetOutput::from_type(DataType::List(Box::new(DataType::Float64))),
+            )
+            .alias("Tukey")
+    }
+
+    /// Means of the groups compared by the tests
+    pub(crate) fn means(&self, values: Expr) -> Expr {
+        self.map(values, |sample| {
+            (!sample.is_empty()).then(|| sample.iter().sum::<f64>() / sample.len() as f64)
+        })
+        .alias("GroupMeans")
+    }
+
+    /// Standard deviations of the groups compared by the tests
+    pub(crate) fn standard_deviations(&self, values: Expr, ddof: u8) -> Expr {
+        self.map(values, move |sample| {
+            let count = sample.len();
+            (count > ddof as usize).then(|| {
+                let mean = sample.iter().sum::<f64>() / count as f64;
+                let squares = sample
+                    .iter()
+                    .map(|value| (value - mean).powi(2))
+                    .sum::<f64>();
+                (squares / (count - ddof as usize) as f64).sqrt()
+            })
+        })
+        .alias("GroupStandardDeviations")
+    }
+
+    /// One value per group of the frame values list
+    fn map(&self, values: Expr, f: impl Fn(&[f64]) -> Option<f64> + Send + Sync + 'static) -> Expr {
+        let groups = self.clone();
+        values.map(
+            move |column| {
+                let mut lists = Vec::with_capacity(column.len());
+                for series in column.list()? {
+                    lists.push(match series {
+                        Some(series) => {
+                            let samples = groups.samples(&series)?;
+                            let values = samples.iter().map(|sample| f(sample));
+                            Some(Series::new(PlSmallStr::EMPTY, values.collect::<Vec<_>>()))
+                        }
+                        None => None,
+                    });
+                }
+                Ok(Some(
+                    lists
+                        .into_iter()
+                        .collect::<ListChunked>()
+                        .into_series()
+                        .into_column(),
+                ))
+            },
+            GetOutput::from_type(DataType::List(Box::new(DataType::Float64))),
+        )
+    }
+
+    /// Frame values split by the groups (ungrouped and null values are
+    /// skipped)
+    fn samples(&self, series: &Series) -> PolarsResult<Vec<Vec<f64>>> {
+        let mut samples = vec![Vec::new(); self.names.len()];
+        for (value, index) in series.f64()?.into_iter().zip(self.indices.iter()) {
+            if let (Some(value), Some(index)) = (value, index) {
+                if value.is_finite() {
+                    samples[*index].push(value);
+                }
+            }
+        }
+        Ok(samples)
+    }
+}
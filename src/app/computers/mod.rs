@@ -1,11 +1,21 @@
 pub(super) use self::{
     calculation::{Computed as CalculationComputed, Key as CalculationKey},
     composition::{
-        FattyAcidComputed as FattyAcidCompositionComputed, FattyAcidKey as FattyAcidCompositionKey,
-        TriacylglycerolComputed as TriacylglycerolCompositionComputed,
+        ComparisonComputed, ComparisonKey, FattyAcidComputed as FattyAcidCompositionComputed,
+        FattyAcidKey as FattyAcidCompositionKey, InverseComputed, InverseKey, ModelsComputed,
+        ModelsKey, TriacylglycerolComputed as TriacylglycerolCompositionComputed,
         TriacylglycerolKey as TriacylglycerolCompositionKey,
     },
+    diagnostics::{
+        Computed as DiagnosticsComputed, Computer as DiagnosticsComputer, Diagnostics,
+        Key as DiagnosticsKey,
+    },
+    dispersion::{Spread, dispersion},
+    groups::Groups,
 };
 
-pub(super) mod calculation;
-pub(super) mod composition;
+pub(crate) mod calculation;
+pub(crate) mod composition;
+pub(crate) mod diagnostics;
+pub(crate) mod dispersion;
+pub(crate) mod groups;
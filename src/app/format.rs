@@ -0,0 +1,116 @@
+//! Input format detection
+
+use super::project::Project;
+use crate::parsers::{agilent::Report, clipboard, legacy};
+use std::{path::Path, str};
+
+/// Arrow IPC file magic
+const ARROW: &[u8] = b"ARROW1";
+/// Arrow IPC stream continuation marker
+const CONTINUATION: &[u8] = &[0xFF; 4];
+
+/// Input format
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Format {
+    /// UTCA IPC (data frame or saved result)
+    Ipc,
+    /// Project file
+    Project,
+    /// Agilent text report
+    Agilent,
+    /// Legacy `toml` config
+    Toml,
+    /// Legacy `ron` config
+    Ron,
+    /// CSV/TSV table
+    Table,
+}
+
+/// Detects the format by the content (and the file extension for tables)
+///
+/// Text without a known marker is a table if every line is a valid row.
+pub(crate) fn sniff(name: &str, bytes: &[u8]) -> Option<Format> {
+    if bytes.starts_with(ARROW) || bytes.starts_with(CONTINUATION) {
+        return Some(Format::Ipc);
+    }
+    let text = str::from_utf8(bytes).ok()?;
+    if Project::is(text) {
+        Some(Format::Project)
+    } else if Report::is(text) {
+        Some(Format::Agilent)
+    } else if legacy::is_toml(text) {
+        Some(Format::Toml)
+    } else if legacy::is_ron(text) {
+        Some(Format::Ron)
+    } else if is_table(name, text) {
+        Some(Format::Table)
+    } else {
+        None
+    }
+}
+
+fn is_table(name: &str, text: &str) -> bool {
+    let extension = Path::new(name)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+    if matches!(extension.as_deref(), Some("csv" | "tsv")) {
+        return true;
+    }
+    let rows = clipboard::rows(text);
+    !rows.is_empty() && rows.iter().all(|(_, row)| row.is_ok())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        // Content markers
+        assert_eq!(sniff("frame", b"ARROW1\0\0"), Some(Format::Ipc));
+        assert_eq!(
+            sniff("frame", &[0xFF, 0xFF, 0xFF, 0xFF, 0]),
+            Some(Format::Ipc)
+        );
+        assert_eq!(
+            sniff("project", b"Project(version: 1, data: (), tree: ())"),
+            Some(Format::Project),
+        );
+        assert_eq!(
+            sniff(
+                "PIN341.TXT",
+                include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/doc/test/PIN341.TXT")),
+            ),
+            Some(Format::Agilent),
+        );
+        assert_eq!(
+            sniff(
+                "PinaceaeCedrus.utca.toml",
+                include_bytes!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/doc/test/PinaceaeCedrus.utca.toml"
+                )),
+            ),
+            Some(Format::Toml),
+        );
+        assert_eq!(
+            sniff(
+                "PinaceaeCedrus.utca.ron",
+                include_bytes!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/doc/test/PinaceaeCedrus.utca.ron"
+                )),
+            ),
+            Some(Format::Ron),
+        );
+        // Tables by the rows or by the extension
+        assert_eq!(
+            sniff("table", b"Label\tFatty acid\tTAG\nP\t16:0\t10,5\n"),
+            Some(Format::Table),
+        );
+        assert_eq!(sniff("table.csv", b"anything"), Some(Format::Table));
+        // Unknown
+        assert_eq!(sniff("table", b"not a table"), None);
+        assert_eq!(sniff("binary", &[0, 159, 146, 150]), None);
+    }
+}
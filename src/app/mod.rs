@@ -1,17 +1,27 @@
 use self::{
     data::Data,
+    format::{Format, sniff},
     identifiers::{DATA, ERROR, GITHUB_TOKEN},
-    panes::{Pane, behavior::Behavior, configuration::Pane as ConfigurationPane},
-    windows::{About, GithubWindow},
+    panes::{
+        Pane, behavior::Behavior, composition::settings::Settings as CompositionSettings,
+        configuration::Pane as ConfigurationPane, provenance::Provenance,
+    },
+    project::Project,
+    windows::{About, AgilentWindow, CorrectionFactorsWindow, GithubWindow},
+};
+use crate::{
+    localization::ContextExt as _,
+    parsers::{clipboard, legacy},
+    utils::xlsx,
+    widgets::{FileDialog, save_as},
 };
-use crate::localization::ContextExt as _;
-use anyhow::Error;
+use anyhow::{Error, Result, bail};
 use chrono::Local;
 use eframe::{APP_KEY, CreationContext, Storage, get_value, set_value};
 use egui::{
     Align, Align2, CentralPanel, Color32, Context, FontDefinitions, Frame, Id, LayerId, Layout,
-    Order, RichText, ScrollArea, SidePanel, Sides, TextStyle, TopBottomPanel, Visuals, menu::bar,
-    util::IdTypeMap, warn_if_debug_build,
+    Order, RichText, ScrollArea, SidePanel, Sides, TextStyle, TopBottomPanel, Ui, Visuals,
+    menu::bar, util::IdTypeMap, warn_if_debug_build,
 };
 use egui_ext::{DroppedFileExt as _, HoveredFileExt, LightDarkButton};
 use egui_l20n::UiExt as _;
@@ -19,8 +29,9 @@ use egui_notify::Toasts;
 use egui_phosphor::{
     Variant, add_to_fonts,
     regular::{
-        ARROWS_CLOCKWISE, CLOUD_ARROW_DOWN, GEAR, GRID_FOUR, INFO, PLUS, SIDEBAR_SIMPLE,
-        SQUARE_SPLIT_HORIZONTAL, SQUARE_SPLIT_VERTICAL, TABS, TRASH,
+        ARROWS_CLOCKWISE, CLOUD_ARROW_DOWN, FILE_XLS, FLOPPY_DISK, FOLDER_OPEN, GEAR, GRID_FOUR,
+        INFO, MATH_OPERATIONS, PLUS, SIDEBAR_SIMPLE, SQUARE_SPLIT_HORIZONTAL,
+        SQUARE_SPLIT_VERTICAL, TABS, TRASH,
     },
 };
 use egui_tiles::{ContainerKind, Tile, Tree};
@@ -70,13 +81,18 @@ pub struct App {
 
     // Data channel
     #[serde(skip)]
-    data_channel: (Sender<Vec<u8>>, Receiver<Vec<u8>>),
+    data_channel: (Sender<(String, Vec<u8>)>, Receiver<(String, Vec<u8>)>),
     #[serde(skip)]
     error_channel: (Sender<Error>, Receiver<Error>),
+    #[serde(skip)]
+    file_dialog: FileDialog,
 
     // Windows
     #[serde(skip)]
     about: About,
+    #[serde(skip)]
+    agilent: AgilentWindow,
+    correction_factors: CorrectionFactorsWindow,
     github: GithubWindow,
     settings: SettingsWindow,
 
@@ -93,8 +109,11 @@ impl Default for App {
             data: Default::default(),
             data_channel: channel(),
             error_channel: channel(),
+            file_dialog: Default::default(),
             toasts: Default::default(),
             about: Default::default(),
+            agilent: Default::default(),
+            correction_factors: Default::default(),
             github: Default::default(),
             settings: SettingsWindow::default(),
         }
@@ -345,6 +364,16 @@ impl App {
                     {
                         self.settings.open = !self.settings.open;
                     }
+                    // Correction factors
+                    if ui
+                        .button(RichText::new(MATH_OPERATIONS).size(ICON_SIZE))
+                        .on_hover_ui(|ui| {
+                            ui.label(ui.localize("correction_factors"));
+                        })
+                        .clicked()
+                    {
+                        self.correction_factors.open = !self.correction_factors.open;
+                    }
                     ui.separator();
                     // Configuration
                     let frames = self.data.checked();
@@ -391,6 +420,49 @@ impl App {
                         self.github.toggle(ui);
                     }
                     ui.separator();
+                    // Open
+                    if ui
+                        .button(RichText::new(FOLDER_OPEN).size(ICON_SIZE))
+                        .on_hover_ui(|ui| {
+                            ui.label(ui.localize("open"));
+                        })
+                        .clicked()
+                    {
+                        if let Err(error) = self.file_dialog.load() {
+                            ui.ctx().error(error);
+                        }
+                    }
+                    // Save project
+                    ui.add_enabled_ui(!self.data.is_empty() || !self.tree.tiles.is_empty(), |ui| {
+                        if ui
+                            .button(RichText::new(FLOPPY_DISK).size(ICON_SIZE))
+                            .on_hover_ui(|ui| {
+                                ui.label(ui.localize("save_project"));
+                            })
+                            .clicked()
+                        {
+                            if let Err(error) = self.save_project() {
+                                ui.ctx().error(error);
+                            }
+                        }
+                    });
+                    ui.separator();
+                    // Export
+                    ui.add_enabled_ui(!self.tree.tiles.is_empty(), |ui| {
+                        if ui
+                            .button(RichText::new(FILE_XLS).size(ICON_SIZE))
+                            .on_hover_ui(|ui| {
+                                ui.label(ui.localize("export_workbook"));
+                            })
+                            .on_hover_text("utca.xlsx")
+                            .clicked()
+                        {
+                            if let Err(error) = self.export(ui) {
+                                ui.ctx().error(error);
+                            }
+                        }
+                    });
+                    ui.separator();
 
                     ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
                         // About
@@ -416,10 +488,61 @@ impl App {
     }
 }
 
+// Export
+impl App {
+    /// Exports the open panes to a workbook
+    fn export(&self, ui: &Ui) -> Result<()> {
+        let mut panes = self
+            .tree
+            .tiles
+            .tiles()
+            .filter_map(|tile| match tile {
+                Tile::Pane(pane) => Some(pane),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        panes.sort_by_key(|pane| pane.kind() as u8);
+        let mut metadata = Vec::<Metadata>::new();
+        let mut sheets = Vec::new();
+        for pane in panes {
+            for frame in pane.frames() {
+                if !metadata
+                    .iter()
+                    .any(|meta| meta.name == frame.meta.name && meta.date == frame.meta.date)
+                {
+                    metadata.push(frame.meta.clone());
+                }
+            }
+            sheets.extend(pane.sheets(ui)?);
+        }
+        let bytes = xlsx::workbook(&metadata, &sheets)?;
+        save_as("utca.xlsx", &bytes)
+    }
+}
+
+// Project
+impl App {
+    /// Saves the data, the panes and the layout to a project file
+    fn save_project(&self) -> Result<()> {
+        let text = project::to_string(&self.data, &self.tree)?;
+        self.file_dialog
+            .save(&format!("project.{}", project::EXTENSION), text)?;
+        Ok(())
+    }
+
+    /// Replaces the data, the panes and the layout with the project ones
+    fn open_project(&mut self, project: Project) {
+        self.data = project.data;
+        self.tree = project.tree;
+    }
+}
+
 // Windows
 impl App {
     fn windows(&mut self, ctx: &Context) {
         self.about.show(ctx);
+        self.agilent.show(ctx);
+        self.correction_factors.show(ctx);
         self.github.show(ctx);
         self.settings.show(ctx);
     }
@@ -444,11 +567,20 @@ impl App {
     }
 
     fn compose(&mut self, ctx: &Context) {
-        if let Some((frames, index)) = ctx.data_mut(|data| {
-            data.remove_temp::<(Vec<MetaDataFrame>, Option<usize>)>(Id::new("Compose"))
+        if let Some((frames, index, groups)) = ctx.data_mut(|data| {
+            data.remove_temp::<(Vec<MetaDataFrame>, Option<usize>, Vec<String>)>(Id::new("Compose"))
         }) {
             self.tree
-                .insert_pane::<VERTICAL>(Pane::composition(frames, index));
+                .insert_pane::<VERTICAL>(Pane::composition(frames, index, groups));
+        }
+    }
+
+    fn compare(&mut self, ctx: &Context) {
+        if let Some((frames, settings)) = ctx.data_mut(|data| {
+            data.remove_temp::<(Vec<MetaDataFrame>, CompositionSettings)>(Id::new("Compare"))
+        }) {
+            self.tree
+                .insert_pane::<VERTICAL>(Pane::comparison(frames, settings));
         }
     }
 
@@ -494,95 +626,69 @@ impl App {
                     }
                 };
                 trace!(?bytes);
-                self.data_channel.0.send(bytes).ok();
+                self.data_channel
+                    .0
+                    .send((dropped.display().to_string(), bytes))
+                    .ok();
             }
         }
     }
 
+    fn file_dialog(&mut self) {
+        if let Some(file) = self.file_dialog.take() {
+            self.data_channel.0.send(file).ok();
+        }
+    }
+
     fn parse(&mut self, ctx: &Context) {
-        for bytes in self.data_channel.1.try_iter() {
-            trace!(?bytes);
-            match MetaDataFrame::read(Cursor::new(bytes)) {
-                Ok(frame) => {
-                    trace!(?frame);
-                    self.data.add(frame);
-                    ctx.request_repaint();
+        let files = self.data_channel.1.try_iter().collect::<Vec<_>>();
+        for (name, bytes) in files {
+            trace!(%name, ?bytes);
+            if let Err(error) = self.import(&name, &bytes) {
+                ctx.error(error.context(name));
+            }
+            ctx.request_repaint();
+        }
+    }
+
+    /// Dispatches the file to the importer of the detected format
+    fn import(&mut self, name: &str, bytes: &[u8]) -> Result<()> {
+        let Some(format) = sniff(name, bytes) else {
+            bail!("unrecognized format");
+        };
+        trace!(?format);
+        match format {
+            Format::Ipc => {
+                let frame = MetaDataFrame::read(Cursor::new(bytes))?;
+                match Provenance::from_bytes(bytes) {
+                    Some(provenance) => self
+                        .tree
+                        .insert_pane::<VERTICAL>(provenance.pane(frame.data)),
+                    None => self.data.add(frame),
                 }
-                Err(error) => error!(%error),
-            };
+            }
+            Format::Project => self.open_project(Project::from_str(str::from_utf8(bytes)?)?),
+            Format::Agilent => self.agilent.load(str::from_utf8(bytes)?.parse()?),
+            Format::Toml => self.data.add(legacy::toml(str::from_utf8(bytes)?)?),
+            Format::Ron => self.data.add(legacy::ron(str::from_utf8(bytes)?)?),
+            Format::Table => self
+                .data
+                .add(clipboard::frame(name, str::from_utf8(bytes)?)?),
         }
+        Ok(())
     }
 
     fn error(&mut self, ctx: &Context) {
         let available_width = ctx.available_rect().width() / 2.0;
         for error in self.error_channel.1.try_iter() {
             self.toasts
-                .error(error.to_string())
+                .error(format!("{error:#}"))
                 .width(available_width)
                 .duration(Some(NOTIFICATIONS_DURATION))
                 .closable(true);
         }
     }
 
-    // fn paste(&mut self, ctx: &Context) {
-    //     if !ctx.memory(|memory| memory.focused().is_some()) {
-    //         ctx.input(|input| {
-    //             for event in &input.raw.events {
-    //                 if let Event::Paste(paste) = event {
-    //                     if let Err(error) = self.parse(paste) {
-    //                         error!(?error);
-    //                         self.toasts
-    //                             .error(error.to_string().chars().take(64).collect::<String>())
-    //                             .set_duration(Some(Duration::from_secs(5)))
-    //                             .set_closable(true);
-    //                     }
-    //                 }
-    //             }
-    //         });
-    //     }
-    // }
-
-    // fn parse(&mut self, paste: &str) -> Result<()> {
-    //     use crate::parsers::whitespace::Parser;
-    //     let parsed = Parser::parse(paste)?;
-    //     debug!(?parsed);
-    //     for parsed in parsed {
-    //         // self.docks.central.tabs.input.add(match parsed {
-    //         //     Parsed::All(label, (c, n), tag, dag, mag) => FattyAcid {
-    //         //         label,
-    //         //         formula: ether!(c as usize, n as usize),
-    //         //         values: [tag, dag, mag],
-    //         //     },
-    //         //     // Parsed::String(label) => Row { label, ..default() },
-    //         //     // Parsed::Integers(_) => Row { label, ..default() },
-    //         //     // Parsed::Float(tag) => Row { label, ..default() },
-    //         //     _ => unimplemented!(),
-    //         // })?;
-    //         // self.config.push_row(Row {
-    //         //     acylglycerols,
-    //         //     label:  parsed.,
-    //         //     ether: todo!(),
-    //         //     // ..default()
-    //         // })?;
-    //     }
-    //     // let mut rows = Vec::new();
-    //     // for row in paste.split('\n') {
-    //     //     let mut columns = [0.0; COUNT];
-    //     //     for (j, column) in row.split('\t').enumerate() {
-    //     //         ensure!(j < COUNT, "Invalid shape, columns: {COUNT} {j}");
-    //     //         columns[j] = column.replace(',', ".").parse()?;
-    //     //     }
-    //     //     rows.push(columns);
-    //     // }
-    //     // for acylglycerols in rows {
-    //     //     self.config.push_row(Row {
-    //     //         acylglycerol: acylglycerols,
-    //     //         ..default()
-    //     //     })?;
-    //     // }
-    //     Ok(())
-    // }
-
     // fn export(&self) -> Result<(), impl Debug> {
     //     let content = to_string(&TomlParsed {
     //         name: self.context.state.entry().meta.name.clone(),
@@ -614,12 +720,14 @@ impl eframe::App for App {
     fn update(&mut self, ctx: &Context, _frame: &mut eframe::Frame) {
         self.calculate(ctx);
         self.compose(ctx);
+        self.compare(ctx);
         // Pre update
         self.panels(ctx);
         self.windows(ctx);
         self.notifications(ctx);
         // Post update
         self.drag_and_drop(ctx);
+        self.file_dialog();
         self.parse(ctx);
         self.error(ctx);
     }
@@ -651,11 +759,13 @@ impl<T, E: Into<Error>> ResultExt<T, E> for Result<T, E> {
     }
 }
 
-mod computers;
+pub(crate) mod computers;
 mod data;
+mod format;
 mod identifiers;
-mod panes;
+pub(crate) mod panes;
 mod presets;
+mod project;
 mod text;
 mod widgets;
 mod windows;
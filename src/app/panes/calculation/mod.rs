@@ -1,16 +1,35 @@
 use self::{settings::Settings, state::State, table::TableView};
-use super::PaneDelegate;
-use crate::app::{
-    computers::{CalculationComputed, CalculationKey},
-    presets::CHRISTIE,
-    widgets::{FattyAcidWidget, FloatWidget},
+use super::{
+    PaneDelegate,
+    provenance::{Provenance, save_result},
+};
+use crate::{
+    app::{
+        ContextExt as _,
+        computers::{
+            CalculationComputed, CalculationKey, Diagnostics, DiagnosticsComputed,
+            DiagnosticsComputer, DiagnosticsKey,
+        },
+        panes::selection::float,
+        widgets::{FattyAcidWidget, FloatWidget},
+    },
+    utils::{polars::flatten, xlsx::Sheet},
+    widgets::save_as,
+};
+use anyhow::{Result, anyhow};
+use egui::{
+    CursorIcon, DragValue, Grid, Id, Response, RichText, ScrollArea, Ui, Window,
+    util::{cache::ComputerMut as _, hash},
 };
-use egui::{CursorIcon, Grid, Id, Response, RichText, ScrollArea, Ui, Window, util::hash};
 use egui_l20n::UiExt as _;
 use egui_phosphor::regular::{
-    ARROWS_CLOCKWISE, ARROWS_HORIZONTAL, CALCULATOR, GEAR, INTERSECT_THREE, LIST, MATH_OPERATIONS,
+    ARROWS_CLOCKWISE, ARROWS_HORIZONTAL, CALCULATOR, EXPORT, FLOPPY_DISK, GEAR, INTERSECT_THREE,
+    LIST, LOCK, MATH_OPERATIONS, SCALES,
+};
+use lipid::{
+    fatty_acid::display::{COMMON, DisplayWithOptions as _},
+    prelude::DataFrameExt as _,
 };
-use lipid::prelude::DataFrameExt as _;
 use metadata::MetaDataFrame;
 use polars::prelude::*;
 use polars_utils::format_list_truncated;
@@ -25,6 +44,9 @@ pub(crate) struct Pane {
     pub(crate) target: DataFrame,
     pub(crate) settings: Settings,
     state: State,
+    /// Saved result, not recomputed
+    #[serde(default)]
+    pub(crate) read_only: bool,
 }
 
 impl Pane {
@@ -34,6 +56,21 @@ impl Pane {
             target: DataFrame::empty(),
             settings: Settings::new(Some(index)),
             state: State::new(),
+            read_only: false,
+        }
+    }
+
+    pub(crate) const fn read_only(
+        frames: Vec<MetaDataFrame>,
+        target: DataFrame,
+        settings: Settings,
+    ) -> Self {
+        Self {
+            source: frames,
+            target,
+            settings,
+            state: State::new(),
+            read_only: true,
         }
     }
 
@@ -48,6 +85,57 @@ impl Pane {
         }
     }
 
+    /// Spreadsheet sheets (each frame and mean ± standard deviations)
+    pub(crate) fn sheets(&self, ui: &Ui) -> PolarsResult<Vec<Sheet>> {
+        if self.read_only {
+            return Ok(vec![Sheet {
+                name: format!("{} calculation", self.title()),
+                header: self.header(ui, &self.title()),
+                data: flatten(&self.target)?,
+                precision: self.settings.precision,
+                percent: self.settings.percent().then_some(percent as _),
+            }]);
+        }
+        let mut sheets = Vec::with_capacity(self.source.len() + 1);
+        for index in (0..self.source.len()).map(Some).chain([None]) {
+            let data = ui
+                .memory_mut(|memory| {
+                    memory
+                        .caches
+                        .cache::<CalculationComputed>()
+                        .get(CalculationKey {
+                            frames: &self.source,
+                            settings: &Settings {
+                                index,
+                                ..self.settings.clone()
+                            },
+                        })
+                })
+                .map_err(|error| polars_err!(ComputeError: "{error}"))?;
+            let title = match index {
+                Some(index) => self.source[index].meta.title(),
+                None => "Mean".to_owned(),
+            };
+            sheets.push(Sheet {
+                name: format!("{title} calculation"),
+                header: self.header(ui, &title),
+                data: flatten(&data)?,
+                precision: self.settings.precision,
+                percent: self.settings.percent().then_some(percent as _),
+            });
+        }
+        Ok(sheets)
+    }
+
+    /// Sheet header (with the unit of the absolute values)
+    fn header(&self, ui: &Ui, title: &str) -> Vec<String> {
+        let mut header = vec![format!("{}: {title}", ui.localize("calculation"))];
+        if let Some(abbreviation) = self.settings.unit.abbreviation() {
+            header.push(format!("{}: {abbreviation}", ui.localize("settings-unit")));
+        }
+        header
+    }
+
     fn header_content(&mut self, ui: &mut Ui) -> Response {
         let mut response = ui.heading(Self::icon()).on_hover_ui(|ui| {
             ui.label(ui.localize("calculation"));
@@ -57,27 +145,33 @@ impl Pane {
             .on_hover_text(format!("{:x}", self.hash()))
             .on_hover_cursor(CursorIcon::Grab);
         ui.separator();
-        // List
-        ui.menu_button(RichText::new(LIST).heading(), |ui| {
-            let mut clicked = false;
-            for index in 0..self.source.len() {
-                clicked |= ui
-                    .selectable_value(
-                        &mut self.settings.index,
-                        Some(index),
-                        self.source[index].meta.title(),
-                    )
-                    .clicked()
-            }
-            ui.selectable_value(&mut self.settings.index, None, "Mean ± standard deviations");
-            if clicked {
-                ui.close_menu();
-            }
-        })
-        .response
-        .on_hover_ui(|ui| {
-            ui.label(ui.localize("list"));
-        });
+        if self.read_only {
+            ui.heading(LOCK).on_hover_ui(|ui| {
+                ui.label(ui.localize("read_only"));
+            });
+        } else {
+            // List
+            ui.menu_button(RichText::new(LIST).heading(), |ui| {
+                let mut clicked = false;
+                for index in 0..self.source.len() {
+                    clicked |= ui
+                        .selectable_value(
+                            &mut self.settings.index,
+                            Some(index),
+                            self.source[index].meta.title(),
+                        )
+                        .clicked()
+                }
+                ui.selectable_value(&mut self.settings.index, None, "Mean ± standard deviations");
+                if clicked {
+                    ui.close_menu();
+                }
+            })
+            .response
+            .on_hover_ui(|ui| {
+                ui.label(ui.localize("list"));
+            });
+        }
         ui.separator();
         // Reset
         if ui
@@ -98,6 +192,55 @@ impl Pane {
             ui.label(ui.localize("resize_table"));
         });
         ui.separator();
+        // Export
+        ui.menu_button(RichText::new(EXPORT).heading(), |ui| {
+            ui.checkbox(
+                &mut self.settings.export_formatted,
+                ui.localize("export_formatted"),
+            )
+            .on_hover_ui(|ui| {
+                ui.label(ui.localize("export_formatted.hover"));
+            });
+            ui.separator();
+            for (text, separator) in [("CSV", b','), ("TSV", b'\t')] {
+                if ui.button(text).clicked() {
+                    if let Err(error) = self.export(separator) {
+                        ui.ctx().error(error);
+                    }
+                    ui.close_menu();
+                }
+            }
+        })
+        .response
+        .on_hover_ui(|ui| {
+            ui.label(ui.localize("export"));
+        });
+        // Save
+        if ui
+            .button(RichText::new(FLOPPY_DISK).heading())
+            .on_hover_ui(|ui| {
+                ui.label(ui.localize("save"));
+            })
+            .on_hover_text(format!("{}.calculation.utca.ipc", self.title()))
+            .clicked()
+        {
+            if let Err(error) = self.save() {
+                ui.ctx().error(error);
+            }
+        }
+        ui.separator();
+        // Diagnostics
+        ui.toggle_value(
+            &mut self.state.open_diagnostics_window,
+            RichText::new(SCALES).heading(),
+        )
+        .on_hover_ui(|ui| {
+            ui.label(ui.localize("diagnostics"));
+        });
+        ui.separator();
+        if self.read_only {
+            return response;
+        }
         // Settings
         ui.toggle_value(
             &mut self.state.open_settings_window,
@@ -118,7 +261,7 @@ impl Pane {
             let mut target = Vec::with_capacity(self.source.len());
             for index in 0..self.source.len() {
                 let meta = self.source[index].meta.clone();
-                let data = ui.memory_mut(|memory| {
+                let result = ui.memory_mut(|memory| {
                     memory
                         .caches
                         .cache::<CalculationComputed>()
@@ -126,29 +269,203 @@ impl Pane {
                             frames: &self.source,
                             settings: &Settings {
                                 index: Some(index),
-                                ..self.settings
+                                ..self.settings.clone()
                             },
                         })
                 });
-                target.push(MetaDataFrame::new(meta, data));
+                match result {
+                    Ok(data) => target.push(MetaDataFrame::new(meta, data)),
+                    Err(error) => {
+                        ui.ctx().error(anyhow!(error).context(meta.title()));
+                        break;
+                    }
+                }
+            }
+            if target.len() == self.source.len() {
+                ui.data_mut(|data| {
+                    data.insert_temp(
+                        Id::new("Compose"),
+                        (target, self.settings.index, self.settings.groups.clone()),
+                    )
+                });
             }
-            ui.data_mut(|data| data.insert_temp(Id::new("Compose"), (target, self.settings.index)));
         }
         ui.separator();
         response
     }
 
     fn body_content(&mut self, ui: &mut Ui) {
-        self.target = ui.memory_mut(|memory| {
-            memory
-                .caches
-                .cache::<CalculationComputed>()
-                .get(CalculationKey {
-                    frames: &self.source,
-                    settings: &self.settings,
-                })
+        if !self.read_only {
+            let result = ui.memory_mut(|memory| {
+                memory
+                    .caches
+                    .cache::<CalculationComputed>()
+                    .get(CalculationKey {
+                        frames: &self.source,
+                        settings: &self.settings,
+                    })
+            });
+            match result {
+                Ok(target) => self.target = target,
+                Err(error) => {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                    return;
+                }
+            }
+        }
+        let diagnostics = self.diagnostics(ui);
+        // Diagnostics (after the target is computed)
+        let mut open_diagnostics_window = self.state.open_diagnostics_window;
+        Window::new(format!("{SCALES} {}", ui.localize("diagnostics")))
+            .default_pos(ui.next_widget_position())
+            .id(ui.auto_id_with("Diagnostics"))
+            .open(&mut open_diagnostics_window)
+            .show(ui.ctx(), |ui| match &diagnostics {
+                Ok(diagnostics) => {
+                    if let Err(error) = self.diagnostics_content(ui, diagnostics) {
+                        ui.ctx().error(error);
+                    }
+                }
+                Err(error) => {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+            });
+        self.state.open_diagnostics_window = open_diagnostics_window;
+        let diagnostics = diagnostics.unwrap_or_default();
+        TableView::new(
+            &self.source,
+            &self.target,
+            &diagnostics,
+            &self.settings,
+            &mut self.state,
+        )
+        .show(ui);
+        // Exclude or restore the replicate
+        if let Some((index, row)) = self.state.exclude.take() {
+            if !self.read_only {
+                if let Ok(Some(label)) = self.target["Label"].str().map(|labels| labels.get(row)) {
+                    self.settings.outliers.toggle(index, label);
+                }
+            }
+        }
+    }
+
+    fn diagnostics(&self, ui: &Ui) -> Result<Diagnostics, String> {
+        let key = DiagnosticsKey {
+            frames: &self.source,
+            data_frame: &self.target,
+            settings: &self.settings,
+        };
+        // The saved result is not computed from the frames
+        if self.read_only {
+            return DiagnosticsComputer.compute(key);
+        }
+        ui.memory_mut(|memory| memory.caches.cache::<DiagnosticsComputed>().get(key))
+    }
+
+    fn diagnostics_content(&mut self, ui: &mut Ui, diagnostics: &Diagnostics) -> PolarsResult<()> {
+        let precision = self.settings.precision;
+        let percent = self.settings.percent();
+        Grid::new(ui.next_auto_id()).show(ui, |ui| {
+            ui.label(ui.localize("diagnostics-goodness_of_fit"))
+                .on_hover_text(ui.localize("diagnostics-goodness_of_fit.hover"));
+            FloatWidget::new(|| Ok(diagnostics.goodness_of_fit))
+                .precision(Some(precision))
+                .percent(true)
+                .hover()
+                .show(ui);
+            ui.end_row();
+
+            ui.label(ui.localize("diagnostics-root_mean_square"))
+                .on_hover_text(ui.localize("diagnostics-root_mean_square.hover"));
+            FloatWidget::new(|| Ok(diagnostics.root_mean_square))
+                .precision(Some(precision))
+                .percent(percent)
+                .hover()
+                .show(ui);
+            ui.end_row();
+
+            ui.label(ui.localize("diagnostics-tolerance"))
+                .on_hover_text(ui.localize("diagnostics-tolerance.hover"));
+            ui.add(
+                DragValue::new(&mut self.state.tolerance)
+                    .range(0.0..=1.0)
+                    .speed(0.001),
+            );
+            ui.end_row();
         });
-        TableView::new(&self.target, &self.settings, &mut self.state).show(ui);
+        ui.separator();
+        let data = &diagnostics.data;
+        let residuals = data["Residual"].struct_()?;
+        let residual = |name| residuals.field_by_name(name);
+        let clipped = data["Clipped"].struct_()?;
+        let balance = data["Balance"].f64()?;
+        ScrollArea::vertical()
+            .show(ui, |ui| {
+                Grid::new(ui.next_auto_id())
+                    .striped(true)
+                    .show(ui, |ui| -> PolarsResult<()> {
+                        ui.heading(ui.localize("label"));
+                        ui.heading(ui.localize("fatty_acid.abbreviation"));
+                        for (name, suffix) in [
+                            ("triacylglycerol", ""),
+                            ("diacylglycerol", "1223"),
+                            ("monoacylglycerol", "2"),
+                        ] {
+                            ui.heading(format!(
+                                "Δ{}{suffix}",
+                                ui.localize(&format!("{name}.abbreviation"))
+                            ))
+                            .on_hover_text(ui.localize("diagnostics-residual.hover"));
+                        }
+                        ui.heading(ui.localize("diagnostics-balance"))
+                            .on_hover_text(ui.localize("diagnostics-balance.hover"));
+                        ui.heading(ui.localize("diagnostics-clipped"))
+                            .on_hover_text(ui.localize("diagnostics-clipped.hover"));
+                        ui.end_row();
+                        for row in 0..data.height() {
+                            ui.label(data["Label"].str()?.get(row).unwrap_or_default());
+                            let fatty_acid = data.fa().get(row)?;
+                            ui.label(
+                                fatty_acid
+                                    .map(|fatty_acid| format!("{:#}", fatty_acid.display(COMMON)))
+                                    .unwrap_or_default(),
+                            );
+                            for name in
+                                ["Triacylglycerol", "Diacylglycerol1223", "Monoacylglycerol2"]
+                            {
+                                let value = residual(name)?.f64()?.get(row);
+                                FloatWidget::new(|| Ok(value))
+                                    .precision(Some(precision))
+                                    .percent(percent)
+                                    .hover()
+                                    .show(ui);
+                            }
+                            let value = balance.get(row);
+                            let text = float(value, precision, percent);
+                            if value.is_some_and(|value| value.abs() > self.state.tolerance) {
+                                ui.colored_label(ui.visuals().error_fg_color, text);
+                            } else {
+                                ui.label(text);
+                            }
+                            let mut names = Vec::new();
+                            for (name, key) in [
+                                ("Triacylglycerol", "triacylglycerol.abbreviation"),
+                                ("Monoacylglycerol2", "monoacylglycerol.abbreviation"),
+                                ("Diacylglycerol13", "diacylglycerol.abbreviation"),
+                            ] {
+                                if clipped.field_by_name(name)?.bool()?.get(row) == Some(true) {
+                                    names.push(ui.localize(key));
+                                }
+                            }
+                            ui.colored_label(ui.visuals().error_fg_color, names.join(", "));
+                            ui.end_row();
+                        }
+                        Ok(())
+                    })
+                    .inner
+            })
+            .inner
     }
 
     fn windows(&mut self, ui: &mut Ui) {
@@ -159,34 +476,93 @@ impl Pane {
             .default_pos(ui.next_widget_position())
             .open(&mut open_settings_window)
             .show(ui.ctx(), |ui| {
-                self.settings.show(ui, &mut self.state);
+                self.settings.show(ui, &mut self.state, &self.source);
             });
         self.state.open_settings_window = open_settings_window;
-        // Christie
-        let mut open_christie_window = self.state.open_christie_window;
-        Window::new(format!("{MATH_OPERATIONS} Christie"))
+        // Correction factors
+        let mut open_correction_factors_window = self.state.open_correction_factors_window;
+        if let Some(correction_factors) = &self.settings.correction_factors {
+            let data = &correction_factors.data;
+            Window::new(format!(
+                "{MATH_OPERATIONS} {}",
+                correction_factors.meta.name
+            ))
             .default_pos(ui.next_widget_position())
-            .id(ui.auto_id_with("Christie"))
-            .open(&mut open_christie_window)
+            .id(ui.auto_id_with("CorrectionFactors"))
+            .open(&mut open_correction_factors_window)
             .show(ui.ctx(), |ui| {
-                ScrollArea::vertical().show(ui, |ui| {
-                    Grid::new(ui.next_auto_id()).show(ui, |ui| {
-                        ui.heading("Fatty Acid");
-                        ui.heading("Value");
-                        ui.end_row();
-                        for index in 0..CHRISTIE.data.height() {
-                            let mut fatty_acid = CHRISTIE.data.fa().get(index).unwrap();
-                            FattyAcidWidget::new(fatty_acid.as_mut()).hover().show(ui);
-                            FloatWidget::new(move || {
-                                Ok(CHRISTIE.data["Christie"].f64()?.get(index))
+                let result = ScrollArea::vertical()
+                    .show(ui, |ui| {
+                        Grid::new(ui.next_auto_id())
+                            .show(ui, |ui| -> PolarsResult<()> {
+                                ui.heading(ui.localize("fatty_acid"));
+                                ui.heading(ui.localize("correction_factor"));
+                                ui.end_row();
+                                for index in 0..data.height() {
+                                    let mut fatty_acid = data.fa().get(index)?;
+                                    FattyAcidWidget::new(fatty_acid.as_mut()).hover().show(ui);
+                                    FloatWidget::new(move || Ok(data["Factor"].f64()?.get(index)))
+                                        .show(ui);
+                                    ui.end_row();
+                                }
+                                Ok(())
                             })
-                            .show(ui);
-                            ui.end_row();
-                        }
-                    });
-                });
+                            .inner
+                    })
+                    .inner;
+                if let Err(error) = result {
+                    ui.colored_label(ui.visuals().error_fg_color, error.to_string());
+                }
             });
-        self.state.open_christie_window = open_christie_window;
+        }
+        self.state.open_correction_factors_window = open_correction_factors_window;
+    }
+
+    fn export(&self, separator: u8) -> Result<()> {
+        let mut data_frame = flatten(&self.target)?;
+        let mut bytes = Vec::new();
+        let mut writer = CsvWriter::new(&mut bytes).with_separator(separator);
+        if self.settings.export_formatted {
+            if self.settings.percent() {
+                data_frame = data_frame
+                    .lazy()
+                    .with_column(
+                        dtype_col(&DataType::Float64).exclude([
+                            r#"^Factors\..*$"#,
+                            r#"^.*\.PValue$"#,
+                            "Amount",
+                        ]) * lit(100),
+                    )
+                    .collect()?;
+            }
+            writer = writer.with_float_precision(Some(self.settings.precision));
+        }
+        if let Some(abbreviation) = self.settings.unit.abbreviation() {
+            let names = data_frame
+                .get_column_names_str()
+                .into_iter()
+                .filter(|name| {
+                    name.starts_with("Experimental.") || name.starts_with("Theoretical.")
+                })
+                .map(ToOwned::to_owned)
+                .collect::<Vec<_>>();
+            for name in names {
+                data_frame.rename(&name, format!("{name}, {abbreviation}").into())?;
+            }
+        }
+        writer.finish(&mut data_frame)?;
+        let extension = if separator == b'\t' { "tsv" } else { "csv" };
+        save_as(&format!("{}.calculation.{extension}", self.title()), &bytes)
+    }
+
+    /// Saves the result with the source metadata and the settings
+    fn save(&self) -> Result<()> {
+        let provenance = Provenance::Calculation {
+            sources: self.source.iter().map(|frame| frame.meta.clone()).collect(),
+            settings: self.settings.clone(),
+        };
+        let name = format!("{}.calculation.utca.ipc", self.title());
+        save_result(&name, self.title(), &provenance, &self.target)
     }
 
     fn hash(&self) -> u64 {
@@ -194,6 +570,11 @@ impl Pane {
     }
 }
 
+/// Factors, amounts and p-values are not percents
+fn percent(name: &str) -> bool {
+    !name.starts_with("Factors.") && !name.ends_with(".PValue") && name != "Amount"
+}
+
 impl PaneDelegate for Pane {
     fn header(&mut self, ui: &mut Ui) -> Response {
         self.header_content(ui)
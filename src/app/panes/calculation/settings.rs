@@ -1,13 +1,26 @@
 use super::State;
-use crate::app::MAX_PRECISION;
-use egui::{ComboBox, Grid, Key, KeyboardShortcut, Modifiers, RichText, Slider, Ui};
+use crate::{
+    app::{MAX_PRECISION, identifiers::CORRECTION_FACTORS},
+    r#const::relative_atomic_mass::CH2,
+};
+use egui::{
+    ComboBox, DragValue, Grid, Key, KeyboardShortcut, Modifiers, RichText, Slider, TextEdit, Ui,
+    emath::Float as _,
+};
 use egui_ext::LabeledSeparator;
 use egui_l20n::UiExt as _;
-use egui_phosphor::regular::BROWSERS;
+use egui_phosphor::regular::{ARROW_COUNTER_CLOCKWISE, BROWSERS};
+use metadata::MetaDataFrame;
+use polars::prelude::PolarsResult;
 use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeSet,
+    hash::{Hash, Hasher},
+};
 
 /// Calculation settings
 #[derive(Clone, Debug, Deserialize, Hash, PartialEq, Serialize)]
+#[serde(default)]
 pub(crate) struct Settings {
     pub(crate) index: Option<usize>,
 
@@ -16,13 +29,23 @@ pub(crate) struct Settings {
     pub(crate) resizable: bool,
     pub(crate) sticky_columns: usize,
     pub(crate) truncate_headers: bool,
+    pub(crate) export_formatted: bool,
+    pub(crate) copy_headers: bool,
+    pub(crate) separate_standard_deviations: bool,
 
     pub(crate) fraction: Fraction,
+    pub(crate) form: Form,
     pub(crate) from: From,
     pub(crate) normalize: Normalize,
     pub(crate) unsigned: bool,
-    pub(crate) christie: bool,
+    pub(crate) correction_factors: Option<MetaDataFrame>,
+    pub(crate) standard: Standard,
+    pub(crate) unit: Unit,
     pub(crate) ddof: u8,
+    pub(crate) dispersion: Dispersion,
+    pub(crate) outliers: Outliers,
+    /// Group of each source frame (empty - not grouped)
+    pub(crate) groups: Vec<String>,
 
     pub(crate) factors: bool,
     pub(crate) theoretical: bool,
@@ -37,16 +60,30 @@ impl Settings {
             resizable: false,
             sticky_columns: 0,
             truncate_headers: false,
+            export_formatted: false,
+            copy_headers: false,
+            separate_standard_deviations: false,
             fraction: Fraction::AsIs,
+            form: Form::FattyAcid,
             from: From::Mag2,
             normalize: Normalize::new(),
             unsigned: true,
-            christie: false,
+            correction_factors: None,
+            standard: Standard::new(),
+            unit: Unit::Fraction,
             ddof: 1,
+            dispersion: Dispersion::new(),
+            outliers: Outliers::new(),
+            groups: Vec::new(),
             factors: true,
             theoretical: true,
         }
     }
+
+    /// Values are relative and shown in percents
+    pub(crate) fn percent(&self) -> bool {
+        self.percent && self.unit == Unit::Fraction
+    }
 }
 
 impl Default for Settings {
@@ -56,7 +93,12 @@ impl Default for Settings {
 }
 
 impl Settings {
-    pub(crate) fn show(&mut self, ui: &mut Ui, state: &mut State) {
+    /// The values are quantified by the internal standard
+    pub(crate) fn is_quantified(&self) -> bool {
+        self.standard.is_some() && self.fraction.is_molar()
+    }
+
+    pub(crate) fn show(&mut self, ui: &mut Ui, state: &mut State, source: &[MetaDataFrame]) {
         Grid::new("Calculation").show(ui, |ui| {
             // Precision
             let mut response = ui.label(ui.localize("settings-precision"));
@@ -90,6 +132,22 @@ impl Settings {
             });
             ui.end_row();
 
+            // Copy headers
+            let mut response = ui.label(ui.localize("settings-copy_headers"));
+            response |= ui.checkbox(&mut self.copy_headers, "");
+            response.on_hover_ui(|ui| {
+                ui.label(ui.localize("settings-copy_headers.hover"));
+            });
+            ui.end_row();
+
+            // Separate standard deviations
+            let mut response = ui.label(ui.localize("settings-separate_standard_deviations"));
+            response |= ui.checkbox(&mut self.separate_standard_deviations, "");
+            response.on_hover_ui(|ui| {
+                ui.label(ui.localize("settings-separate_standard_deviations.hover"));
+            });
+            ui.end_row();
+
             ui.separator();
             ui.separator();
             ui.end_row();
@@ -100,18 +158,15 @@ impl Settings {
             response |= ComboBox::from_id_salt("fraction")
                 .selected_text(ui.localize(fraction.text()))
                 .show_ui(ui, |ui| {
-                    ui.selectable_value(
-                        fraction,
+                    for variant in [
                         Fraction::AsIs,
-                        ui.localize(Fraction::AsIs.text()),
-                    )
-                    .on_hover_text(Fraction::AsIs.hover_text());
-                    ui.selectable_value(
-                        fraction,
+                        Fraction::ToMole,
+                        Fraction::ToMass,
                         Fraction::Fraction,
-                        ui.localize(Fraction::Fraction.text()),
-                    )
-                    .on_hover_text(Fraction::Fraction.hover_text());
+                    ] {
+                        ui.selectable_value(fraction, variant, ui.localize(variant.text()))
+                            .on_hover_text(variant.hover_text());
+                    }
                 })
                 .response
                 .on_hover_text(fraction.hover_text());
@@ -120,6 +175,26 @@ impl Settings {
             });
             ui.end_row();
 
+            // Form
+            if self.fraction != Fraction::AsIs {
+                let mut response = ui.label(ui.localize("settings-form"));
+                let form = &mut self.form;
+                response |= ComboBox::from_id_salt("form")
+                    .selected_text(ui.localize(form.text()))
+                    .show_ui(ui, |ui| {
+                        for variant in [Form::FattyAcid, Form::MethylEster, Form::EthylEster] {
+                            ui.selectable_value(form, variant, ui.localize(variant.text()))
+                                .on_hover_text(variant.hover_text());
+                        }
+                    })
+                    .response
+                    .on_hover_text(form.hover_text());
+                response.on_hover_ui(|ui| {
+                    ui.label(ui.localize("settings-form.hover"));
+                });
+                ui.end_row();
+            }
+
             // Calculate
             ui.label(ui.localize("settings-from"))
                 .on_hover_text(ui.localize("settings-from.hover"));
@@ -167,17 +242,136 @@ impl Settings {
             });
             ui.end_row();
 
-            // Christie
-            let mut response = ui.label(ui.localize("settings-christie"));
+            // Correction factors
+            let mut response = ui.label(ui.localize("settings-correction_factors"));
             ui.horizontal(|ui| {
-                response |= ui.checkbox(&mut self.christie, "");
-                ui.toggle_value(
-                    &mut state.open_christie_window,
-                    RichText::new(BROWSERS).heading(),
-                );
+                let sets = ui
+                    .data(|data| data.get_temp::<Vec<MetaDataFrame>>(*CORRECTION_FACTORS))
+                    .unwrap_or_default();
+                let correction_factors = &mut self.correction_factors;
+                let mut selected_text = match correction_factors {
+                    Some(selected) => selected.meta.name.clone(),
+                    None => ui.localize("settings-none"),
+                };
+                // Changed in the library since selected
+                let modified = correction_factors.as_ref().is_some_and(|selected| {
+                    sets.iter()
+                        .any(|set| set.meta.name == selected.meta.name && set != selected)
+                });
+                if modified {
+                    selected_text.push('*');
+                }
+                response |= ComboBox::from_id_salt("CorrectionFactors")
+                    .selected_text(selected_text)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(correction_factors, None, ui.localize("settings-none"));
+                        for set in sets {
+                            let selected = correction_factors.as_ref() == Some(&set);
+                            let text = set.meta.name.clone();
+                            if ui.selectable_label(selected, text).clicked() {
+                                *correction_factors = Some(set);
+                            }
+                        }
+                    })
+                    .response;
+                if modified {
+                    response = response.on_hover_ui(|ui| {
+                        ui.label(ui.localize("settings-correction_factors.modified"));
+                    });
+                }
+                ui.add_enabled_ui(correction_factors.is_some(), |ui| {
+                    ui.toggle_value(
+                        &mut state.open_correction_factors_window,
+                        RichText::new(BROWSERS).heading(),
+                    );
+                });
                 response.on_hover_ui(|ui| {
-                    ui.label(ui.localize("settings-christie.hover"));
+                    ui.label(ui.localize("settings-correction_factors.hover"));
+                });
+            });
+            ui.end_row();
+
+            // Internal standard
+            ui.separator();
+            ui.labeled_separator(
+                RichText::new(ui.localize("settings-internal_standard")).heading(),
+            );
+            ui.end_row();
+
+            let mut response = ui.label(ui.localize("settings-internal_standard"));
+            if !self.fraction.is_molar() {
+                response = response.on_hover_ui(|ui| {
+                    ui.label(ui.localize("settings-internal_standard.disabled"));
+                });
+            }
+            response.on_hover_ui(|ui| {
+                ui.label(ui.localize("settings-internal_standard.hover"));
+            });
+            let labels = source.first().map_or(Ok(Vec::new()), labels);
+            ui.add_enabled_ui(self.fraction.is_molar(), |ui| {
+                ComboBox::from_id_salt("InternalStandard")
+                    .selected_text(&self.standard.label)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut self.standard.label,
+                            String::new(),
+                            ui.localize("settings-none"),
+                        );
+                        for label in labels.iter().flatten() {
+                            ui.selectable_value(&mut self.standard.label, label.clone(), label);
+                        }
+                    });
+            });
+            ui.end_row();
+
+            ui.label(ui.localize("settings-standard_amount"))
+                .on_hover_ui(|ui| {
+                    ui.label(ui.localize("settings-standard_amount.hover"));
+                });
+            ui.add(
+                DragValue::new(&mut self.standard.amount)
+                    .range(0.0..=f64::MAX)
+                    .speed(0.1)
+                    .suffix(" µmol"),
+            );
+            ui.end_row();
+
+            ui.label(ui.localize("settings-sample_mass"))
+                .on_hover_ui(|ui| {
+                    ui.label(ui.localize("settings-sample_mass.hover"));
+                });
+            ui.add(
+                DragValue::new(&mut self.standard.mass)
+                    .range(0.0..=f64::MAX)
+                    .speed(0.001)
+                    .suffix(" g"),
+            );
+            ui.end_row();
+
+            // Unit
+            let mut response = ui.label(ui.localize("settings-unit"));
+            ui.add_enabled_ui(self.is_quantified(), |ui| {
+                let unit = &mut self.unit;
+                ComboBox::from_id_salt("unit")
+                    .selected_text(ui.localize(unit.text()))
+                    .show_ui(ui, |ui| {
+                        for variant in [
+                            Unit::Fraction,
+                            Unit::MicromolePerGram,
+                            Unit::MilligramPerGram,
+                        ] {
+                            ui.selectable_value(unit, variant, ui.localize(variant.text()));
+                        }
+                    });
+            });
+            if !self.is_quantified() {
+                self.unit = Unit::Fraction;
+                response = response.on_hover_ui(|ui| {
+                    ui.label(ui.localize("settings-unit.disabled"));
                 });
+            }
+            response.on_hover_ui(|ui| {
+                ui.label(ui.localize("settings-unit.hover"));
             });
             ui.end_row();
 
@@ -220,6 +414,24 @@ impl Settings {
                 });
                 ui.add(Slider::new(&mut self.ddof, 0..=2));
                 ui.end_row();
+
+                // Dispersion
+                self.dispersion.show(ui);
+
+                // Outliers
+                self.outliers.show(ui);
+
+                // Groups
+                ui.label(ui.localize("settings-groups")).on_hover_ui(|ui| {
+                    ui.label(ui.localize("settings-groups.hover"));
+                });
+                ui.end_row();
+                self.groups.resize(source.len(), String::new());
+                for (frame, group) in source.iter().zip(&mut self.groups) {
+                    ui.label(frame.meta.title());
+                    ui.add(TextEdit::singleline(group).hint_text(ui.localize("settings-group")));
+                    ui.end_row();
+                }
             }
         });
     }
@@ -231,6 +443,8 @@ impl Settings {
 #[derive(Clone, Copy, Debug, Deserialize, Hash, PartialEq, Serialize)]
 pub(crate) enum Fraction {
     AsIs,
+    ToMole,
+    ToMass,
     Fraction,
 }
 
@@ -238,19 +452,325 @@ impl Fraction {
     pub(crate) fn text(self) -> &'static str {
         match self {
             Self::AsIs => "settings-as_is",
+            Self::ToMole => "settings-to_mole_fraction",
+            Self::ToMass => "settings-to_mass_fraction",
             Self::Fraction => "settings-pchelkin",
         }
     }
 
+    /// The values are proportional to the amounts of substance (required by
+    /// the internal standard)
+    pub(crate) fn is_molar(self) -> bool {
+        matches!(self, Self::ToMole | Self::Fraction)
+    }
+
     // col(name) / (col(name) * col("FA").fa().mass() / lit(10)).sum()
     pub(crate) fn hover_text(self) -> &'static str {
         match self {
             Self::AsIs => "S / ∑ S",
+            Self::ToMole => "(S / M) / ∑(S / M)",
+            Self::ToMass => "(S * M) / ∑(S * M)",
             Self::Fraction => "S / ∑(S * M)",
         }
     }
 }
 
+/// Form of the fatty acid mass (M)
+#[derive(Clone, Copy, Debug, Default, Deserialize, Hash, PartialEq, Serialize)]
+pub(crate) enum Form {
+    #[default]
+    FattyAcid,
+    MethylEster,
+    EthylEster,
+}
+
+impl Form {
+    pub(crate) fn text(self) -> &'static str {
+        match self {
+            Self::FattyAcid => "settings-fatty_acid",
+            Self::MethylEster => "settings-methyl_ester",
+            Self::EthylEster => "settings-ethyl_ester",
+        }
+    }
+
+    pub(crate) fn hover_text(self) -> &'static str {
+        match self {
+            Self::FattyAcid => "RCOOH",
+            Self::MethylEster => "RCOOCH3",
+            Self::EthylEster => "RCOOC2H5",
+        }
+    }
+
+    /// Mass added to the fatty acid mass
+    pub(crate) const fn offset(self) -> f64 {
+        match self {
+            Self::FattyAcid => 0.0,
+            Self::MethylEster => CH2,
+            Self::EthylEster => 2.0 * CH2,
+        }
+    }
+}
+
+/// Configuration labels
+fn labels(frame: &MetaDataFrame) -> PolarsResult<Vec<String>> {
+    Ok(frame.data["Label"]
+        .str()?
+        .into_iter()
+        .flatten()
+        .map(ToOwned::to_owned)
+        .collect())
+}
+
+/// Internal standard
+///
+/// The configuration row (by label) with the known added amount.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub(crate) struct Standard {
+    pub(crate) label: String,
+    /// Added amount, µmol
+    pub(crate) amount: f64,
+    /// Sample mass, g
+    pub(crate) mass: f64,
+}
+
+impl Standard {
+    pub(crate) const fn new() -> Self {
+        Self {
+            label: String::new(),
+            amount: 0.0,
+            mass: 0.0,
+        }
+    }
+
+    pub(crate) fn is_some(&self) -> bool {
+        !self.label.is_empty() && self.amount > 0.0 && self.mass > 0.0
+    }
+}
+
+impl Hash for Standard {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.label.hash(state);
+        self.amount.ord().hash(state);
+        self.mass.ord().hash(state);
+    }
+}
+
+/// Dispersion of the mean
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub(crate) struct Dispersion {
+    pub(crate) kind: DispersionKind,
+    /// Confidence level of the confidence interval
+    pub(crate) confidence: f64,
+}
+
+impl Dispersion {
+    pub(crate) const fn new() -> Self {
+        Self {
+            kind: DispersionKind::StandardDeviation,
+            confidence: 0.95,
+        }
+    }
+
+    /// Dispersion settings rows
+    pub(crate) fn show(&mut self, ui: &mut Ui) {
+        ui.label(ui.localize("settings-dispersion"))
+            .on_hover_ui(|ui| {
+                ui.label(ui.localize("settings-dispersion.hover"));
+            });
+        ComboBox::from_id_salt("dispersion")
+            .selected_text(ui.localize(self.kind.text()))
+            .show_ui(ui, |ui| {
+                for kind in [
+                    DispersionKind::StandardDeviation,
+                    DispersionKind::StandardError,
+                    DispersionKind::ConfidenceInterval,
+                    DispersionKind::Range,
+                ] {
+                    ui.selectable_value(&mut self.kind, kind, ui.localize(kind.text()))
+                        .on_hover_ui(|ui| {
+                            ui.label(ui.localize(kind.hover_text()));
+                        });
+                }
+            });
+        ui.end_row();
+
+        if self.kind == DispersionKind::ConfidenceInterval {
+            ui.label(ui.localize("settings-confidence_level"));
+            ui.add(
+                DragValue::new(&mut self.confidence)
+                    .range(0.5..=0.999)
+                    .speed(0.001)
+                    .custom_formatter(|n, _| format!("{:.1}", n * 100.0))
+                    .custom_parser(|text| text.parse::<f64>().ok().map(|n| n / 100.0))
+                    .suffix("%"),
+            );
+            ui.end_row();
+        }
+    }
+}
+
+impl Default for Dispersion {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hash for Dispersion {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.kind.hash(state);
+        if self.kind == DispersionKind::ConfidenceInterval {
+            self.confidence.ord().hash(state);
+        }
+    }
+}
+
+/// Replicate outlier screening
+///
+/// Robust z-scores of the experimental values on the median and the median
+/// absolute deviation across the frames. The excluded replicates are skipped
+/// in the means.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub(crate) struct Outliers {
+    pub(crate) screen: bool,
+    /// Robust z-score threshold
+    pub(crate) threshold: f64,
+    /// Excluded replicates (frame index and fatty acid label)
+    pub(crate) excluded: BTreeSet<(usize, String)>,
+}
+
+impl Outliers {
+    pub(crate) const fn new() -> Self {
+        Self {
+            screen: false,
+            threshold: 3.5,
+            excluded: BTreeSet::new(),
+        }
+    }
+
+    pub(crate) fn is_excluded(&self, index: usize, label: &str) -> bool {
+        self.excluded.contains(&(index, label.to_owned()))
+    }
+
+    /// Excludes the replicate or restores the excluded one
+    pub(crate) fn toggle(&mut self, index: usize, label: &str) {
+        if !self.excluded.remove(&(index, label.to_owned())) {
+            self.excluded.insert((index, label.to_owned()));
+        }
+    }
+
+    /// Outliers settings rows
+    pub(crate) fn show(&mut self, ui: &mut Ui) {
+        let mut response = ui.label(ui.localize("settings-outliers"));
+        response |= ui.checkbox(&mut self.screen, "");
+        response.on_hover_ui(|ui| {
+            ui.label(ui.localize("settings-outliers.hover"));
+        });
+        ui.end_row();
+
+        if self.screen {
+            ui.label(ui.localize("settings-outlier_threshold"))
+                .on_hover_ui(|ui| {
+                    ui.label(ui.localize("settings-outlier_threshold.hover"));
+                });
+            ui.add(
+                DragValue::new(&mut self.threshold)
+                    .range(1.0..=10.0)
+                    .speed(0.1),
+            );
+            ui.end_row();
+        }
+
+        if !self.excluded.is_empty() {
+            ui.label(ui.localize("settings-excluded"));
+            if ui
+                .button(format!("{ARROW_COUNTER_CLOCKWISE} {}", self.excluded.len()))
+                .on_hover_ui(|ui| {
+                    ui.label(ui.localize("settings-excluded.hover"));
+                })
+                .clicked()
+            {
+                self.excluded.clear();
+            }
+            ui.end_row();
+        }
+    }
+}
+
+impl Default for Outliers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hash for Outliers {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.screen.hash(state);
+        self.threshold.ord().hash(state);
+        self.excluded.hash(state);
+    }
+}
+
+/// Dispersion kind
+#[derive(Clone, Copy, Debug, Deserialize, Hash, PartialEq, Serialize)]
+pub(crate) enum DispersionKind {
+    StandardDeviation,
+    StandardError,
+    ConfidenceInterval,
+    Range,
+}
+
+impl DispersionKind {
+    pub(crate) fn text(self) -> &'static str {
+        match self {
+            Self::StandardDeviation => "settings-standard_deviation",
+            Self::StandardError => "settings-standard_error",
+            Self::ConfidenceInterval => "settings-confidence_interval",
+            Self::Range => "settings-range",
+        }
+    }
+
+    pub(crate) fn hover_text(self) -> &'static str {
+        match self {
+            Self::StandardDeviation => "settings-standard_deviation.hover",
+            Self::StandardError => "settings-standard_error.hover",
+            Self::ConfidenceInterval => "settings-confidence_interval.hover",
+            Self::Range => "settings-range.hover",
+        }
+    }
+}
+
+/// Unit
+#[derive(Clone, Copy, Debug, Default, Deserialize, Hash, PartialEq, Serialize)]
+pub(crate) enum Unit {
+    /// Relative (fraction or percent)
+    #[default]
+    Fraction,
+    MicromolePerGram,
+    MilligramPerGram,
+}
+
+impl Unit {
+    pub(crate) fn text(self) -> &'static str {
+        match self {
+            Self::Fraction => "settings-relative",
+            Self::MicromolePerGram => "settings-micromole_per_gram",
+            Self::MilligramPerGram => "settings-milligram_per_gram",
+        }
+    }
+
+    /// Unit abbreviation (`None` for relative values)
+    pub(crate) const fn abbreviation(self) -> Option<&'static str> {
+        match self {
+            Self::Fraction => None,
+            Self::MicromolePerGram => Some("µmol/g"),
+            Self::MilligramPerGram => Some("mg/g"),
+        }
+    }
+}
+
 /// From
 #[derive(Clone, Copy, Debug, Deserialize, Hash, PartialEq, Serialize)]
 pub(crate) enum From {
@@ -276,6 +796,7 @@ impl From {
 
 /// Normalize
 #[derive(Clone, Copy, Debug, Deserialize, Hash, PartialEq, Serialize)]
+#[serde(default)]
 pub(crate) struct Normalize {
     pub(crate) experimental: bool,
     pub(crate) theoretical: bool,
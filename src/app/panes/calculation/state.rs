@@ -1,18 +1,44 @@
+use crate::app::panes::selection::Selection;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
 pub(crate) struct State {
-    pub(crate) open_christie_window: bool,
+    #[serde(alias = "open_christie_window")]
+    pub(crate) open_correction_factors_window: bool,
+    #[serde(default)]
+    pub(crate) open_diagnostics_window: bool,
     pub(crate) open_settings_window: bool,
     pub(crate) reset_table_state: bool,
+    #[serde(default)]
+    pub(crate) selection: Selection,
+    /// Mass balance tolerance
+    #[serde(default = "tolerance")]
+    pub(crate) tolerance: f64,
+    /// Replicate (frame index and row) to exclude or restore
+    #[serde(skip)]
+    pub(crate) exclude: Option<(usize, usize)>,
 }
 
 impl State {
     pub(crate) const fn new() -> Self {
         Self {
-            open_christie_window: false,
+            open_correction_factors_window: false,
+            open_diagnostics_window: false,
             open_settings_window: false,
             reset_table_state: false,
+            selection: Selection::new(),
+            tolerance: tolerance(),
+            exclude: None,
         }
     }
 }
+
+impl Default for State {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+const fn tolerance() -> f64 {
+    0.01
+}
@@ -2,18 +2,29 @@ use super::{
     ID_SOURCE, State,
     settings::{From, Settings},
 };
-use crate::app::{
-    ContextExt as _,
-    panes::MARGIN,
-    widgets::{FattyAcidWidget, FloatWidget},
+use crate::{
+    app::{
+        ContextExt as _, ResultExt as _,
+        computers::{Diagnostics, Groups, Spread},
+        panes::{
+            MARGIN,
+            selection::{float, groups, header, mean, tsv},
+        },
+        widgets::{FattyAcidWidget, FloatWidget},
+    },
+    utils::statistics::significance,
 };
-use egui::{Frame, Id, Margin, Response, TextStyle, TextWrapMode, Ui};
+use egui::{Frame, Grid, Id, Margin, Response, RichText, TextStyle, TextWrapMode, Ui};
 use egui_l20n::UiExt as _;
-use egui_phosphor::regular::HASH;
+use egui_phosphor::regular::{HASH, WARNING};
 use egui_table::{
     AutoSizeMode, CellInfo, Column, HeaderCellInfo, HeaderRow, Table, TableDelegate, TableState,
 };
-use lipid::prelude::*;
+use lipid::{
+    fatty_acid::display::{COMMON, DisplayWithOptions as _},
+    prelude::*,
+};
+use metadata::MetaDataFrame;
 use polars::prelude::*;
 use std::ops::Range;
 
@@ -41,19 +52,27 @@ const MIDDLE: &[Range<usize>] = &[
 
 /// Calculation table
 pub(crate) struct TableView<'a> {
+    source: &'a [MetaDataFrame],
     data_frame: &'a DataFrame,
+    diagnostics: &'a Diagnostics,
+    groups: Groups,
     settings: &'a Settings,
     state: &'a mut State,
 }
 
 impl<'a> TableView<'a> {
     pub(crate) fn new(
+        source: &'a [MetaDataFrame],
         data_frame: &'a DataFrame,
+        diagnostics: &'a Diagnostics,
         settings: &'a Settings,
         state: &'a mut State,
     ) -> Self {
         Self {
+            source,
             data_frame,
+            diagnostics,
+            groups: Groups::new(&settings.groups),
             settings,
             state,
         }
@@ -92,128 +111,258 @@ impl TableView<'_> {
             ])
             .auto_size_mode(AutoSizeMode::OnParentResize)
             .show(ui, self);
+        if self.state.selection.copy(ui) {
+            if let Some(text) = self.copy(ui).context(ui.ctx()) {
+                ui.ctx().copy_text(text);
+            }
+        }
     }
 
-    fn header_cell_content_ui(&mut self, ui: &mut Ui, row: usize, column: Range<usize>) {
-        if self.settings.truncate_headers {
-            ui.style_mut().wrap_mode = Some(TextWrapMode::Truncate);
+    /// Selected cells as tab separated text
+    fn copy(&self, ui: &Ui) -> PolarsResult<String> {
+        let Some((rows, columns)) = self.state.selection.ranges() else {
+            return Ok(String::new());
+        };
+        let mut headers = Vec::new();
+        if self.settings.copy_headers {
+            for (row, groups) in [TOP, MIDDLE, &[]].into_iter().enumerate() {
+                headers.push(header(groups, columns.clone(), |column| {
+                    self.header_text(ui, row, column).map(|(text, _)| text)
+                }));
+            }
         }
-        match (row, column) {
-            // Top
-            (0, ID) => {
-                ui.heading(ui.localize("identifier.abbreviation"))
-                    .on_hover_ui(|ui| {
-                        ui.label(ui.localize("identifier"));
-                    });
+        let rows = rows
+            .map(|row| {
+                columns
+                    .clone()
+                    .map(|column| self.cell_text(row, column))
+                    .collect()
+            })
+            .collect::<PolarsResult<Vec<_>>>()?;
+        Ok(tsv(&headers, &rows))
+    }
+
+    fn cell_text(&self, row: usize, column: usize) -> PolarsResult<Vec<String>> {
+        let footer = row == self.data_frame.height();
+        Ok(match column..column + 1 {
+            id::INDEX if !footer => vec![
+                self.data_frame["Index"]
+                    .u32()?
+                    .get(row)
+                    .unwrap()
+                    .to_string(),
+            ],
+            id::LABEL if !footer => vec![
+                self.data_frame["Label"]
+                    .str()?
+                    .get(row)
+                    .unwrap_or_default()
+                    .to_owned(),
+            ],
+            id::FA if !footer => vec![
+                self.data_frame
+                    .fa()
+                    .get(row)?
+                    .map(|fatty_acid| format!("{:#}", fatty_acid.display(COMMON)))
+                    .unwrap_or_default(),
+            ],
+            column => match self.column(column.clone())? {
+                Some(_) if footer && FACTORS.contains(&column.start) => vec![String::new()],
+                Some((series, percent, _)) => {
+                    self.value_text(series, (!footer).then_some(row), percent)?
+                }
+                None => vec![String::new()],
+            },
+        })
+    }
+
+    fn value_text(
+        &self,
+        series: Series,
+        row: Option<usize>,
+        percent: bool,
+    ) -> PolarsResult<Vec<String>> {
+        let precision = self.settings.precision;
+        let value = |series: &Series| -> PolarsResult<Option<f64>> {
+            let values = series.f64()?;
+            Ok(match row {
+                Some(row) => values.get(row),
+                None => values.sum(),
+            })
+        };
+        Ok(if let Some(r#struct) = series.try_struct() {
+            let mut texts = mean(
+                value(&r#struct.field_by_name("Mean")?)?,
+                Spread::fields(r#struct)?.try_map(|series| value(&series))?,
+                precision,
+                percent,
+                self.settings.separate_standard_deviations,
+            );
+            if let (Some(row), Ok(p_values)) = (row, r#struct.field_by_name("PValue")) {
+                if let Some(p_value) = p_values.f64()?.get(row) {
+                    if let Some(text) = texts.last_mut() {
+                        text.push_str(significance(p_value));
+                    }
+                }
             }
-            (0, EXPERIMENTAL) => {
-                ui.heading(ui.localize("experimental"));
+            texts
+        } else {
+            vec![float(value(&series)?, precision, percent)]
+        })
+    }
+
+    /// Value column series with the percent and disable flags
+    fn column(&self, column: Range<usize>) -> PolarsResult<Option<(Series, bool, bool)>> {
+        let percent = self.settings.percent();
+        let experimental = |name| {
+            self.data_frame["Experimental"]
+                .struct_()?
+                .field_by_name(name)
+        };
+        let theoretical = |name| {
+            self.data_frame["Theoretical"]
+                .struct_()?
+                .field_by_name(name)
+        };
+        let diacylglycerol13 = |name| {
+            theoretical("Diacylglycerol13")?
+                .struct_()?
+                .field_by_name(name)
+        };
+        let factors = |name| self.data_frame["Factors"].struct_()?.field_by_name(name);
+        Ok(Some(match column {
+            experimental::TAG => (experimental("Triacylglycerol")?, percent, false),
+            experimental::DAG1223 => (
+                experimental("Diacylglycerol1223")?,
+                percent,
+                self.settings.from != From::Dag1223,
+            ),
+            experimental::MAG2 => (
+                experimental("Monoacylglycerol2")?,
+                percent,
+                self.settings.from != From::Mag2,
+            ),
+            theoretical::TAG if self.settings.theoretical => {
+                (theoretical("Triacylglycerol")?, percent, true)
             }
-            (0, THEORETICAL) => {
-                ui.heading(ui.localize("theoretical"));
+            theoretical::DAG1223 if self.settings.theoretical => {
+                (theoretical("Diacylglycerol1223")?, percent, true)
             }
-            (0, FACTORS) if self.settings.factors => {
-                ui.heading(ui.localize("factors"));
+            theoretical::MAG2 if self.settings.theoretical => {
+                (theoretical("Monoacylglycerol2")?, percent, true)
+            }
+            theoretical::dag13::DAG1223 => (
+                diacylglycerol13("Diacylglycerol1223")?,
+                percent,
+                self.settings.from != From::Dag1223,
+            ),
+            theoretical::dag13::MAG2 => (
+                diacylglycerol13("Monoacylglycerol2")?,
+                percent,
+                self.settings.from != From::Mag2,
+            ),
+            factors::ef::MAG2 if self.settings.factors => (factors("Enrichment")?, false, false),
+            factors::sf::MAG2 if self.settings.factors => (factors("Selectivity")?, false, false),
+            _ => return Ok(None),
+        }))
+    }
+
+    fn header_cell_content_ui(&mut self, ui: &mut Ui, row: usize, column: Range<usize>) {
+        if self.settings.truncate_headers {
+            ui.style_mut().wrap_mode = Some(TextWrapMode::Truncate);
+        }
+        if row == 1 && column == id::INDEX {
+            ui.heading(HASH).on_hover_ui(|ui| {
+                ui.label(ui.localize("index"));
+            });
+        } else if let Some((text, hover_text)) = self.header_text(ui, row, column) {
+            let response = ui.heading(text);
+            if let Some(hover_text) = hover_text {
+                response.on_hover_text(hover_text);
             }
+        }
+    }
+
+    /// Header text and hover text
+    fn header_text(
+        &self,
+        ui: &Ui,
+        row: usize,
+        column: Range<usize>,
+    ) -> Option<(String, Option<String>)> {
+        let triacylglycerol = || {
+            (
+                ui.localize("triacylglycerol.abbreviation"),
+                Some(ui.localize("triacylglycerol")),
+            )
+        };
+        let diacylglycerol1223 = || {
+            (
+                format!("{}1223", ui.localize("diacylglycerol.abbreviation")),
+                Some(format!("sn-1,2/2,3 {}", ui.localize("diacylglycerol"))),
+            )
+        };
+        let monoacylglycerol2 = || {
+            (
+                format!("{}2", ui.localize("monoacylglycerol.abbreviation")),
+                Some(format!("sn-2 {}", ui.localize("monoacylglycerol"))),
+            )
+        };
+        let unit = |text: String| match self.settings.unit.abbreviation() {
+            Some(abbreviation) => format!("{text}, {abbreviation}"),
+            None => text,
+        };
+        Some(match (row, column) {
+            // Top
+            (0, ID) => (
+                ui.localize("identifier.abbreviation"),
+                Some(ui.localize("identifier")),
+            ),
+            (0, EXPERIMENTAL) => (unit(ui.localize("experimental")), None),
+            (0, THEORETICAL) => (unit(ui.localize("theoretical")), None),
+            (0, FACTORS) if self.settings.factors => (ui.localize("factors"), None),
             // Middle
-            (1, id::INDEX) => {
-                ui.heading(HASH).on_hover_ui(|ui| {
-                    ui.label(ui.localize("index"));
-                });
-            }
-            (1, id::LABEL) => {
-                ui.heading(ui.localize("label"));
-            }
-            (1, id::FA) => {
-                ui.heading(ui.localize("fatty_acid.abbreviation"))
-                    .on_hover_ui(|ui| {
-                        ui.label(ui.localize("fatty_acid"));
-                    });
-            }
-            (1, experimental::TAG) => {
-                ui.heading(ui.localize("triacylglycerol.abbreviation"))
-                    .on_hover_ui(|ui| {
-                        ui.label(ui.localize("triacylglycerol"));
-                    });
-            }
-            (1, experimental::DAG1223) => {
-                ui.heading(format!(
-                    "{}1223",
-                    ui.localize("diacylglycerol.abbreviation"),
-                ))
-                .on_hover_ui(|ui| {
-                    ui.label(format!("sn-1,2/2,3 {}", ui.localize("diacylglycerol"),));
-                });
-            }
-            (1, experimental::MAG2) => {
-                ui.heading(format!("{}2", ui.localize("monoacylglycerol.abbreviation")))
-                    .on_hover_ui(|ui| {
-                        ui.label(format!("sn-2 {}", ui.localize("monoacylglycerol"),));
-                    });
-            }
-            (1, theoretical::TAG) if self.settings.theoretical => {
-                ui.heading(ui.localize("triacylglycerol.abbreviation"))
-                    .on_hover_ui(|ui| {
-                        ui.label(ui.localize("triacylglycerol"));
-                    });
-            }
-            (1, theoretical::DAG1223) if self.settings.theoretical => {
-                ui.heading(format!(
-                    "{}1223",
-                    ui.localize("diacylglycerol.abbreviation"),
-                ))
-                .on_hover_ui(|ui| {
-                    ui.label(format!("sn-1,2/2,3 {}", ui.localize("diacylglycerol"),));
-                });
-            }
-            (1, theoretical::MAG2) if self.settings.theoretical => {
-                ui.heading(format!("{}2", ui.localize("monoacylglycerol.abbreviation")))
-                    .on_hover_ui(|ui| {
-                        ui.label(format!("sn-2 {}", ui.localize("monoacylglycerol"),));
-                    });
-            }
-            (1, theoretical::DAG13) => {
-                ui.heading(format!("{}13", ui.localize("diacylglycerol.abbreviation")))
-                    .on_hover_ui(|ui| {
-                        ui.label(format!("sn-13 {}", ui.localize("diacylglycerol"),));
-                    });
-            }
-            (1, factors::EF) if self.settings.factors => {
-                ui.heading(ui.localize("enrichment_factor.abbreviation"))
-                    .on_hover_ui(|ui| {
-                        ui.label(ui.localize("enrichment_factor"));
-                    });
-            }
-            (1, factors::SF) if self.settings.factors => {
-                ui.heading(ui.localize("selectivity_factor.abbreviation"))
-                    .on_hover_ui(|ui| {
-                        ui.label(ui.localize("selectivity_factor"));
-                    });
-            }
+            (1, id::INDEX) => ("#".to_owned(), Some(ui.localize("index"))),
+            (1, id::LABEL) => (ui.localize("label"), None),
+            (1, id::FA) => (
+                ui.localize("fatty_acid.abbreviation"),
+                Some(ui.localize("fatty_acid")),
+            ),
+            (1, experimental::TAG) => triacylglycerol(),
+            (1, experimental::DAG1223) => diacylglycerol1223(),
+            (1, experimental::MAG2) => monoacylglycerol2(),
+            (1, theoretical::TAG) if self.settings.theoretical => triacylglycerol(),
+            (1, theoretical::DAG1223) if self.settings.theoretical => diacylglycerol1223(),
+            (1, theoretical::MAG2) if self.settings.theoretical => monoacylglycerol2(),
+            (1, theoretical::DAG13) => (
+                format!("{}13", ui.localize("diacylglycerol.abbreviation")),
+                Some(format!("sn-13 {}", ui.localize("diacylglycerol"))),
+            ),
+            (1, factors::EF) if self.settings.factors => (
+                ui.localize("enrichment_factor.abbreviation"),
+                Some(ui.localize("enrichment_factor")),
+            ),
+            (1, factors::SF) if self.settings.factors => (
+                ui.localize("selectivity_factor.abbreviation"),
+                Some(ui.localize("selectivity_factor")),
+            ),
             // Bottom
-            (2, theoretical::dag13::DAG1223) => {
-                ui.heading(format!(
-                    "{}1223",
-                    ui.localize("diacylglycerol.abbreviation"),
-                ))
-                .on_hover_ui(|ui| {
-                    ui.label(format!(
-                        "Calculated from sn-1,2/2,3 {}",
-                        ui.localize("diacylglycerol?genus=genitive"),
-                    ));
-                });
-            }
-            (2, theoretical::dag13::MAG2) => {
-                ui.heading(format!("{}2", ui.localize("monoacylglycerol.abbreviation")))
-                    .on_hover_ui(|ui| {
-                        ui.label(format!(
-                            "Calculated from sn-2 {}",
-                            ui.localize("monoacylglycerol?genus=genitive"),
-                        ));
-                    });
-            }
-            _ => {}
-        };
+            (2, theoretical::dag13::DAG1223) => (
+                format!("{}1223", ui.localize("diacylglycerol.abbreviation")),
+                Some(format!(
+                    "Calculated from sn-1,2/2,3 {}",
+                    ui.localize("diacylglycerol?genus=genitive"),
+                )),
+            ),
+            (2, theoretical::dag13::MAG2) => (
+                format!("{}2", ui.localize("monoacylglycerol.abbreviation")),
+                Some(format!(
+                    "Calculated from sn-2 {}",
+                    ui.localize("monoacylglycerol?genus=genitive"),
+                )),
+            ),
+            _ => return None,
+        })
     }
 
     fn cell_content_ui(
@@ -251,121 +400,14 @@ impl TableView<'_> {
                 let mut fatty_acid = self.data_frame.fa().get(row)?;
                 FattyAcidWidget::new(fatty_acid.as_mut()).hover().show(ui);
             }
-            (row, experimental::TAG) => {
-                self.value(
-                    ui,
-                    self.data_frame["Experimental"]
-                        .struct_()?
-                        .field_by_name("Triacylglycerol")?,
-                    Some(row),
-                    self.settings.percent,
-                    false,
-                )?;
-            }
-            (row, experimental::DAG1223) => {
-                self.value(
-                    ui,
-                    self.data_frame["Experimental"]
-                        .struct_()?
-                        .field_by_name("Diacylglycerol1223")?,
-                    Some(row),
-                    self.settings.percent,
-                    self.settings.from != From::Dag1223,
-                )?;
-            }
-            (row, experimental::MAG2) => {
-                self.value(
-                    ui,
-                    self.data_frame["Experimental"]
-                        .struct_()?
-                        .field_by_name("Monoacylglycerol2")?,
-                    Some(row),
-                    self.settings.percent,
-                    self.settings.from != From::Mag2,
-                )?;
-            }
-            (row, theoretical::TAG) if self.settings.theoretical => {
-                self.value(
-                    ui,
-                    self.data_frame["Theoretical"]
-                        .struct_()?
-                        .field_by_name("Triacylglycerol")?,
-                    Some(row),
-                    self.settings.percent,
-                    true,
-                )?;
-            }
-            (row, theoretical::DAG1223) if self.settings.theoretical => {
-                self.value(
-                    ui,
-                    self.data_frame["Theoretical"]
-                        .struct_()?
-                        .field_by_name("Diacylglycerol1223")?,
-                    Some(row),
-                    self.settings.percent,
-                    true,
-                )?;
-            }
-            (row, theoretical::MAG2) if self.settings.theoretical => {
-                self.value(
-                    ui,
-                    self.data_frame["Theoretical"]
-                        .struct_()?
-                        .field_by_name("Monoacylglycerol2")?,
-                    Some(row),
-                    self.settings.percent,
-                    true,
-                )?;
-            }
-            (row, theoretical::dag13::DAG1223) => {
-                self.value(
-                    ui,
-                    self.data_frame["Theoretical"]
-                        .struct_()?
-                        .field_by_name("Diacylglycerol13")?
-                        .struct_()?
-                        .field_by_name("Diacylglycerol1223")?,
-                    Some(row),
-                    self.settings.percent,
-                    self.settings.from != From::Dag1223,
-                )?;
-            }
-            (row, theoretical::dag13::MAG2) => {
-                self.value(
-                    ui,
-                    self.data_frame["Theoretical"]
-                        .struct_()?
-                        .field_by_name("Diacylglycerol13")?
-                        .struct_()?
-                        .field_by_name("Monoacylglycerol2")?,
-                    Some(row),
-                    self.settings.percent,
-                    self.settings.from != From::Mag2,
-                )?;
-            }
-            (row, factors::ef::MAG2) if self.settings.factors => {
-                self.value(
-                    ui,
-                    self.data_frame["Factors"]
-                        .struct_()?
-                        .field_by_name("Enrichment")?,
-                    Some(row),
-                    false,
-                    false,
-                )?;
-            }
-            (row, factors::sf::MAG2) if self.settings.factors => {
-                self.value(
-                    ui,
-                    self.data_frame["Factors"]
-                        .struct_()?
-                        .field_by_name("Selectivity")?,
-                    Some(row),
-                    false,
-                    false,
-                )?;
+            (row, column) => {
+                if let Some((series, percent, disable)) = self.column(column.clone())? {
+                    self.value(ui, series.clone(), Some(row), percent, disable)?;
+                    if EXPERIMENTAL.contains(&column.start) {
+                        self.outliers(ui, &series, row)?;
+                    }
+                }
             }
-            _ => {}
         }
         Ok(())
     }
@@ -379,7 +421,7 @@ impl TableView<'_> {
                         .struct_()?
                         .field_by_name("Triacylglycerol")?,
                     None,
-                    self.settings.percent,
+                    self.settings.percent(),
                     false,
                 )?
                 .on_hover_text("∑ TAG");
@@ -391,7 +433,7 @@ impl TableView<'_> {
                         .struct_()?
                         .field_by_name("Diacylglycerol1223")?,
                     None,
-                    self.settings.percent,
+                    self.settings.percent(),
                     self.settings.from != From::Dag1223,
                 )?
                 .on_hover_text("∑ DAG1223");
@@ -403,7 +445,7 @@ impl TableView<'_> {
                         .struct_()?
                         .field_by_name("Monoacylglycerol2")?,
                     None,
-                    self.settings.percent,
+                    self.settings.percent(),
                     self.settings.from != From::Mag2,
                 )?
                 .on_hover_text("∑ MAG2");
@@ -415,7 +457,7 @@ impl TableView<'_> {
                         .struct_()?
                         .field_by_name("Triacylglycerol")?,
                     None,
-                    self.settings.percent,
+                    self.settings.percent(),
                     true,
                 )?
                 .on_hover_text("∑ TAG");
@@ -427,7 +469,7 @@ impl TableView<'_> {
                         .struct_()?
                         .field_by_name("Diacylglycerol1223")?,
                     None,
-                    self.settings.percent,
+                    self.settings.percent(),
                     true,
                 )?
                 .on_hover_text("∑ DAG1223");
@@ -439,7 +481,7 @@ impl TableView<'_> {
                         .struct_()?
                         .field_by_name("Monoacylglycerol2")?,
                     None,
-                    self.settings.percent,
+                    self.settings.percent(),
                     true,
                 )?
                 .on_hover_text("∑ MAG2");
@@ -453,7 +495,7 @@ impl TableView<'_> {
                         .struct_()?
                         .field_by_name("Diacylglycerol1223")?,
                     None,
-                    self.settings.percent,
+                    self.settings.percent(),
                     self.settings.from != From::Dag1223,
                 )?
                 .on_hover_text("∑ DAG13");
@@ -467,7 +509,7 @@ impl TableView<'_> {
                         .struct_()?
                         .field_by_name("Monoacylglycerol2")?,
                     None,
-                    self.settings.percent,
+                    self.settings.percent(),
                     self.settings.from != From::Mag2,
                 )?
                 .on_hover_text("∑ DAG13");
@@ -477,6 +519,37 @@ impl TableView<'_> {
         Ok(())
     }
 
+    /// Inconsistent experimental or clipped theoretical value
+    fn offending(&self, row: usize, column: usize) -> PolarsResult<bool> {
+        let data = &self.diagnostics.data;
+        if row >= data.height() {
+            return Ok(false);
+        }
+        let clipped = |name| -> PolarsResult<bool> {
+            Ok(data["Clipped"]
+                .struct_()?
+                .field_by_name(name)?
+                .bool()?
+                .get(row)
+                .unwrap_or_default())
+        };
+        Ok(match column..column + 1 {
+            experimental::TAG | experimental::DAG1223 | experimental::MAG2 => data["Balance"]
+                .f64()?
+                .get(row)
+                .is_some_and(|balance| balance.abs() > self.state.tolerance),
+            theoretical::TAG => clipped("Triacylglycerol")?,
+            theoretical::MAG2 => clipped("Monoacylglycerol2")?,
+            theoretical::dag13::DAG1223 if self.settings.from == From::Dag1223 => {
+                clipped("Diacylglycerol13")?
+            }
+            theoretical::dag13::MAG2 if self.settings.from == From::Mag2 => {
+                clipped("Diacylglycerol13")?
+            }
+            _ => false,
+        })
+    }
+
     fn value(
         &self,
         ui: &mut Ui,
@@ -497,22 +570,41 @@ impl TableView<'_> {
                 .disable(disable)
                 .hover()
                 .show(ui);
-            ui.label("±");
-            let standard_deviation = if let Some(row) = row {
-                r#struct
-                    .field_by_name("StandardDeviations")?
-                    .f64()?
-                    .get(row)
-            } else {
-                r#struct.field_by_name("StandardDeviations")?.f64()?.sum()
+            let value = |series: Series| -> PolarsResult<Option<f64>> {
+                let values = series.f64()?;
+                Ok(match row {
+                    Some(row) => values.get(row),
+                    None => values.sum(),
+                })
             };
-            FloatWidget::new(|| Ok(standard_deviation))
-                .percent(percent)
-                .precision(Some(self.settings.precision))
-                .disable(disable)
-                .hover()
-                .show(ui)
-                .response
+            let widget = |ui: &mut Ui, value: Option<f64>| {
+                FloatWidget::new(|| Ok(value))
+                    .percent(percent)
+                    .precision(Some(self.settings.precision))
+                    .disable(disable)
+                    .hover()
+                    .show(ui)
+                    .response
+            };
+            let text = Spread::new(r#struct.struct_fields()).map_or("", Spread::text);
+            let response = match Spread::fields(r#struct)?.try_map(value)? {
+                Spread::Interval(value) => {
+                    ui.label("±").on_hover_text(ui.localize(text));
+                    widget(ui, value)
+                }
+                Spread::Range(minimum, maximum) => {
+                    ui.label("(").on_hover_text(ui.localize(text));
+                    widget(ui, minimum);
+                    ui.label("–");
+                    let response = widget(ui, maximum);
+                    ui.label(")");
+                    response
+                }
+            };
+            if let Some(row) = row {
+                self.test(ui, r#struct, row, percent)?;
+            }
+            response
         } else {
             let values = series.f64()?;
             let value = if let Some(row) = row {
@@ -531,6 +623,136 @@ impl TableView<'_> {
     }
 }
 
+impl TableView<'_> {
+    /// Significance marker with the p-values on hover
+    fn test(
+        &self,
+        ui: &mut Ui,
+        r#struct: &StructChunked,
+        row: usize,
+        percent: bool,
+    ) -> PolarsResult<()> {
+        let Ok(p_values) = r#struct.field_by_name("PValue") else {
+            return Ok(());
+        };
+        let Some(p_value) = p_values.f64()?.get(row) else {
+            return Ok(());
+        };
+        let tukey = match r#struct.field_by_name("Tukey") {
+            Ok(tukey) => tukey.list()?.get_as_series(row),
+            Err(_) => None,
+        };
+        let group = |name: &str| -> PolarsResult<Option<Series>> {
+            Ok(r#struct.field_by_name(name)?.list()?.get_as_series(row))
+        };
+        let means = group("GroupMeans")?;
+        let standard_deviations = group("GroupStandardDeviations")?;
+        let precision = self.settings.precision;
+        ui.label(significance(p_value)).on_hover_ui(|ui| {
+            let groups = groups(
+                &self.groups.names,
+                means.as_ref(),
+                standard_deviations.as_ref(),
+                precision,
+                percent,
+            );
+            for text in &groups {
+                ui.label(text);
+            }
+            if !groups.is_empty() {
+                ui.separator();
+            }
+            ui.label(format!("p = {p_value:.precision$}"));
+            if let Some(tukey) = &tukey {
+                ui.separator();
+                ui.label(ui.localize("tukey"));
+                let p_values = tukey.f64().into_iter().flatten();
+                for ((a, b), p_value) in self.groups.pairs().into_iter().zip(p_values) {
+                    if let Some(p_value) = p_value {
+                        ui.label(format!(
+                            "{a} - {b}: p = {p_value:.precision$}{}",
+                            significance(p_value),
+                        ));
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+impl TableView<'_> {
+    /// Outlier marker with the replicate exclusion menu
+    fn outliers(&mut self, ui: &mut Ui, series: &Series, row: usize) -> PolarsResult<()> {
+        let Some(r#struct) = series.try_struct() else {
+            return Ok(());
+        };
+        let source = self.source;
+        let outliers = &self.settings.outliers;
+        let label = self.data_frame["Label"].str()?.get(row).unwrap_or_default();
+        let z_scores = match r#struct.field_by_name("ZScores") {
+            Ok(z_scores) => z_scores.list()?.get_as_series(row),
+            Err(_) => None,
+        };
+        let z_scores = match &z_scores {
+            Some(z_scores) => z_scores.f64()?.into_iter().collect(),
+            None => Vec::new(),
+        };
+        let is_outlier = |index: usize| {
+            z_scores
+                .get(index)
+                .copied()
+                .flatten()
+                .is_some_and(|z_score| z_score.abs() > outliers.threshold)
+        };
+        let flagged = (0..source.len()).any(is_outlier);
+        let excluded = (0..source.len()).any(|index| outliers.is_excluded(index, label));
+        if !flagged && !excluded {
+            return Ok(());
+        }
+        let color = if flagged {
+            ui.visuals().warn_fg_color
+        } else {
+            ui.visuals().weak_text_color()
+        };
+        let precision = self.settings.precision;
+        ui.menu_button(RichText::new(WARNING).color(color), |ui| {
+            Grid::new(ui.next_auto_id()).show(ui, |ui| {
+                ui.heading(ui.localize("replicate"));
+                ui.heading("z");
+                ui.end_row();
+                for (index, frame) in source.iter().enumerate() {
+                    let mut exclude = outliers.is_excluded(index, label);
+                    if ui
+                        .checkbox(&mut exclude, frame.meta.title())
+                        .on_hover_ui(|ui| {
+                            ui.label(ui.localize("exclude_replicate"));
+                        })
+                        .changed()
+                    {
+                        self.state.exclude = Some((index, row));
+                    }
+                    let text = match z_scores.get(index).copied().flatten() {
+                        Some(z_score) => format!("{z_score:.precision$}"),
+                        None => String::new(),
+                    };
+                    if is_outlier(index) {
+                        ui.colored_label(ui.visuals().warn_fg_color, text);
+                    } else {
+                        ui.label(text);
+                    }
+                    ui.end_row();
+                }
+            });
+        })
+        .response
+        .on_hover_ui(|ui| {
+            ui.label(ui.localize("outliers"));
+        });
+        Ok(())
+    }
+}
+
 impl TableDelegate for TableView<'_> {
     fn header_cell_ui(&mut self, ui: &mut Ui, cell: &HeaderCellInfo) {
         Frame::new()
@@ -545,6 +767,18 @@ impl TableDelegate for TableView<'_> {
             ui.painter()
                 .rect_filled(ui.max_rect(), 0.0, ui.visuals().faint_bg_color);
         }
+        if self
+            .offending(cell.row_nr as _, cell.col_nr)
+            .context(ui.ctx())
+            .unwrap_or_default()
+        {
+            ui.painter().rect_filled(
+                ui.max_rect(),
+                0.0,
+                ui.visuals().error_fg_color.gamma_multiply(0.25),
+            );
+        }
+        self.state.selection.cell(ui, cell.row_nr as _, cell.col_nr);
         Frame::new()
             .inner_margin(Margin::from(MARGIN))
             .show(ui, |ui| {
@@ -0,0 +1,231 @@
+use self::{settings::Settings, state::State, table::TableView};
+use super::{PaneDelegate, composition::settings::Settings as CompositionSettings};
+use crate::{
+    app::{
+        computers::{ComparisonComputed, ComparisonKey},
+        panes::{
+            composition::table::{key, key_text},
+            selection::float,
+        },
+        text::Text,
+    },
+    special::composition::{MNC, SSC},
+    utils::xlsx::Sheet,
+};
+use egui::{CursorIcon, Response, RichText, Ui, util::hash};
+use egui_l20n::UiExt as _;
+use egui_phosphor::regular::{ARROWS_CLOCKWISE, ARROWS_HORIZONTAL, GIT_DIFF, STACK};
+use metadata::MetaDataFrame;
+use polars::prelude::*;
+use polars_utils::format_list_truncated;
+use serde::{Deserialize, Serialize};
+
+const ID_SOURCE: &str = "Comparison";
+
+/// Comparison pane
+#[derive(Default, Deserialize, Serialize)]
+pub(crate) struct Pane {
+    pub(crate) source: Vec<MetaDataFrame>,
+    pub(crate) target: DataFrame,
+    pub(crate) settings: Settings,
+    state: State,
+}
+
+impl Pane {
+    pub(crate) fn new(frames: Vec<MetaDataFrame>, settings: CompositionSettings) -> Self {
+        Self {
+            source: frames,
+            target: DataFrame::empty(),
+            settings: Settings::new(settings),
+            state: State::new(),
+        }
+    }
+
+    pub(crate) const fn icon() -> &'static str {
+        GIT_DIFF
+    }
+
+    pub(crate) fn title(&self) -> String {
+        format_list_truncated!(self.source.iter().map(|frame| frame.meta.title()), 2)
+    }
+
+    /// Spreadsheet sheet with the keys, the comparative major numbers and the
+    /// values of the samples
+    pub(crate) fn sheet(&self, ui: &Ui) -> PolarsResult<Sheet> {
+        let target = self
+            .compute(ui)
+            .map_err(|error| polars_err!(ComputeError: "{error}"))?;
+        let reference = self.source[self.settings.reference].meta.title();
+        let mut header = vec![
+            format!("{}: {}", ui.localize("comparison"), self.title()),
+            format!("{}: {reference}", ui.localize("comparison-reference")),
+        ];
+        let mut columns = vec![target["Index"].clone()];
+        let selections = &self.settings.composition.confirmed.selections;
+        for (index, texts) in key_texts(&target, &self.settings)?.into_iter().enumerate() {
+            let composition = selections
+                .get(index)
+                .map_or(SSC, |selection| selection.composition);
+            header.push(format!(
+                "{}: {}",
+                ui.localize(composition.text()),
+                ui.localize(composition.hover_text()),
+            ));
+            let name = format!("Key{index}");
+            columns.push(Series::new(name.into(), texts).into_column());
+        }
+        let count = self.source.len();
+        let comparative_major_number = target["ComparativeMajorNumber"]
+            .u32()?
+            .into_iter()
+            .map(|number| table::comparative_major_number(number.unwrap_or_default(), count))
+            .collect::<Vec<_>>();
+        columns.push(
+            Series::new("ComparativeMajorNumber".into(), comparative_major_number).into_column(),
+        );
+        for (index, frame) in self.source.iter().enumerate() {
+            let title = frame.meta.title();
+            for name in ["Value", "Difference", "FoldChange"] {
+                columns.push(
+                    target[&*format!("{name}{index}")]
+                        .clone()
+                        .with_name(format!("{title}.{name}").into()),
+                );
+            }
+        }
+        Ok(Sheet {
+            name: format!("{} comparison", self.title()),
+            header,
+            data: DataFrame::new(columns)?,
+            precision: self.settings.composition.precision,
+            percent: self.settings.composition.percent().then_some(percent as _),
+        })
+    }
+
+    fn compute(&self, ui: &Ui) -> Result<DataFrame, String> {
+        ui.memory_mut(|memory| {
+            memory
+                .caches
+                .cache::<ComparisonComputed>()
+                .get(ComparisonKey {
+                    frames: &self.source,
+                    settings: &self.settings,
+                })
+        })
+    }
+
+    fn header_content(&mut self, ui: &mut Ui) -> Response {
+        let mut response = ui
+            .heading(Self::icon())
+            .on_hover_text(ui.localize("comparison"));
+        response |= ui.heading(self.title());
+        response = response
+            .on_hover_text(format!("{:x}", self.hash()))
+            .on_hover_cursor(CursorIcon::Grab);
+        ui.separator();
+        // Reference
+        ui.menu_button(RichText::new(STACK).heading(), |ui| {
+            let mut clicked = false;
+            for index in 0..self.source.len() {
+                clicked |= ui
+                    .selectable_value(
+                        &mut self.settings.reference,
+                        index,
+                        self.source[index].meta.title(),
+                    )
+                    .clicked();
+            }
+            if clicked {
+                ui.close_menu();
+            }
+        })
+        .response
+        .on_hover_text(ui.localize("comparison-reference.hover"));
+        // Comparative major number
+        ui.toggle_value(
+            &mut self.settings.comparative_major_number,
+            RichText::new(ui.localize("comparison-comparative_major_number.abbreviation"))
+                .heading(),
+        )
+        .on_hover_text(ui.localize("comparison-group"));
+        ui.separator();
+        // Reset
+        if ui
+            .button(RichText::new(ARROWS_CLOCKWISE).heading())
+            .clicked()
+        {
+            self.state.reset_table_state = true;
+        }
+        // Resize
+        ui.toggle_value(
+            &mut self.settings.composition.resizable,
+            RichText::new(ARROWS_HORIZONTAL).heading(),
+        )
+        .on_hover_text(ui.localize("resize"));
+        ui.separator();
+        response
+    }
+
+    fn body_content(&mut self, ui: &mut Ui) {
+        match self.compute(ui) {
+            Ok(target) => self.target = target,
+            Err(error) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+                return;
+            }
+        }
+        TableView::new(&self.source, &self.target, &self.settings, &mut self.state).show(ui);
+    }
+
+    fn hash(&self) -> u64 {
+        hash(&self.source)
+    }
+}
+
+impl PaneDelegate for Pane {
+    fn header(&mut self, ui: &mut Ui) -> Response {
+        self.header_content(ui)
+    }
+
+    fn body(&mut self, ui: &mut Ui) {
+        self.body_content(ui);
+    }
+}
+
+/// Key texts of each selection
+fn key_texts(data_frame: &DataFrame, settings: &Settings) -> PolarsResult<Vec<Vec<String>>> {
+    let selections = &settings.composition.confirmed.selections;
+    let precision = settings.composition.precision;
+    let keys = data_frame["Keys"].struct_()?.fields_as_series();
+    keys.iter()
+        .enumerate()
+        .map(|(index, series)| {
+            let composition = selections
+                .get(index)
+                .map_or(SSC, |selection| selection.composition);
+            match composition {
+                MNC => Ok(series
+                    .f64()?
+                    .into_iter()
+                    .map(|value| float(value, precision, false))
+                    .collect()),
+                _ => {
+                    let series = key(composition, series, precision)?;
+                    (0..series.len())
+                        .map(|row| key_text(composition, &series, row))
+                        .collect()
+                }
+            }
+        })
+        .collect()
+}
+
+/// Values and differences are percents, fold changes are not
+fn percent(name: &str) -> bool {
+    name.ends_with(".Value") || name.ends_with(".Difference")
+}
+
+pub(crate) mod settings;
+
+mod state;
+mod table;
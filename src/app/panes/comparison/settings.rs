@@ -0,0 +1,23 @@
+use crate::app::panes::composition::settings::Settings as CompositionSettings;
+use serde::{Deserialize, Serialize};
+
+/// Comparison settings
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub(crate) struct Settings {
+    /// Composition of each sample
+    pub(crate) composition: CompositionSettings,
+    /// Sample of the differences and the fold changes
+    pub(crate) reference: usize,
+    /// Group the species by the comparative major number
+    pub(crate) comparative_major_number: bool,
+}
+
+impl Settings {
+    pub(crate) fn new(composition: CompositionSettings) -> Self {
+        Self {
+            composition,
+            reference: 0,
+            comparative_major_number: true,
+        }
+    }
+}
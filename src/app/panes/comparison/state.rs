@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct State {
+    pub(crate) reset_table_state: bool,
+}
+
+impl State {
+    pub(crate) const fn new() -> Self {
+        Self {
+            reset_table_state: false,
+        }
+    }
+}
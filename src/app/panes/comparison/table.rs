@@ -0,0 +1,235 @@
+use super::{ID_SOURCE, Settings, State, key_texts};
+use crate::app::{
+    ResultExt,
+    panes::{MARGIN, selection::float},
+    text::Text,
+};
+use egui::{Frame, Id, Margin, TextStyle, Ui};
+use egui_l20n::UiExt as _;
+use egui_phosphor::regular::HASH;
+use egui_table::{
+    AutoSizeMode, CellInfo, Column, HeaderCellInfo, HeaderRow, Table, TableDelegate, TableState,
+};
+use metadata::MetaDataFrame;
+use polars::prelude::*;
+use std::ops::Range;
+
+const INDEX: Range<usize> = 0..1;
+const STEP: usize = 3;
+
+/// Comparison table
+#[derive(Debug)]
+pub(super) struct TableView<'a> {
+    source: &'a [MetaDataFrame],
+    data_frame: &'a DataFrame,
+    settings: &'a Settings,
+    state: &'a mut State,
+    key_texts: Vec<Vec<String>>,
+}
+
+impl<'a> TableView<'a> {
+    pub(super) fn new(
+        source: &'a [MetaDataFrame],
+        data_frame: &'a DataFrame,
+        settings: &'a Settings,
+        state: &'a mut State,
+    ) -> Self {
+        Self {
+            source,
+            data_frame,
+            settings,
+            state,
+            key_texts: Vec::new(),
+        }
+    }
+}
+
+impl TableView<'_> {
+    pub(super) fn show(&mut self, ui: &mut Ui) {
+        let id_salt = Id::new(ID_SOURCE).with("Table");
+        if self.state.reset_table_state {
+            let id = TableState::id(ui, Id::new(id_salt));
+            TableState::reset(ui.ctx(), id);
+            self.state.reset_table_state = false;
+        }
+        self.key_texts = key_texts(self.data_frame, self.settings)
+            .context(ui.ctx())
+            .unwrap_or_default();
+        let height = ui.text_style_height(&TextStyle::Heading);
+        let keys = self.keys();
+        let num_columns = keys.end + 1 + self.source.len() * STEP;
+        let mut top = vec![INDEX, 1..keys.end, keys.end..keys.end + 1];
+        for start in (keys.end + 1..num_columns).step_by(STEP) {
+            top.push(start..start + STEP);
+        }
+        Table::new()
+            .id_salt(id_salt)
+            .num_rows(self.data_frame.height() as _)
+            .columns(vec![
+                Column::default()
+                    .resizable(self.settings.composition.resizable);
+                num_columns
+            ])
+            .num_sticky_cols(keys.end + 1)
+            .headers([
+                HeaderRow {
+                    height,
+                    groups: top,
+                },
+                HeaderRow::new(height),
+            ])
+            .auto_size_mode(AutoSizeMode::OnParentResize)
+            .show(ui, self);
+    }
+
+    /// Key columns
+    fn keys(&self) -> Range<usize> {
+        1..1 + self.settings.composition.confirmed.selections.len().max(1)
+    }
+
+    fn header_cell_content_ui(&mut self, ui: &mut Ui, row: usize, column: Range<usize>) {
+        let keys = self.keys();
+        match (row, column) {
+            (0, INDEX) => {
+                ui.heading(HASH).on_hover_text(ui.localize("index"));
+            }
+            (0, column) if column.start == keys.start => {
+                ui.heading(ui.localize("comparison-keys"));
+            }
+            (0, column) if column.start == keys.end => {
+                ui.heading(ui.localize("comparison-comparative_major_number.abbreviation"))
+                    .on_hover_text(ui.localize("comparison-comparative_major_number"));
+            }
+            (0, column) => {
+                let index = (column.start - keys.end - 1) / STEP;
+                let mut title = self.source[index].meta.title();
+                if index == self.settings.reference {
+                    title = format!("{title} ({})", ui.localize("comparison-reference"));
+                }
+                ui.heading(title);
+            }
+            (1, column) if keys.contains(&column.start) => {
+                let selections = &self.settings.composition.confirmed.selections;
+                if let Some(selection) = selections.get(column.start - keys.start) {
+                    ui.heading(ui.localize(selection.composition.text()))
+                        .on_hover_text(ui.localize(selection.composition.hover_text()));
+                }
+            }
+            (1, column) if column.start > keys.end => {
+                let key = match (column.start - keys.end - 1) % STEP {
+                    0 => "comparison-value",
+                    1 => "comparison-difference",
+                    _ => "comparison-fold_change",
+                };
+                ui.heading(ui.localize(key))
+                    .on_hover_text(ui.localize(&format!("{key}.hover")));
+            }
+            _ => {}
+        }
+    }
+
+    fn cell_content_ui(
+        &mut self,
+        ui: &mut Ui,
+        row: usize,
+        column: Range<usize>,
+    ) -> PolarsResult<()> {
+        let keys = self.keys();
+        let precision = self.settings.composition.precision;
+        let percent = self.settings.composition.percent();
+        match column {
+            INDEX => {
+                let index = self.data_frame["Index"].u32()?.get(row).unwrap();
+                ui.label(index.to_string());
+            }
+            column if keys.contains(&column.start) => {
+                let index = column.start - keys.start;
+                if let Some(text) = self.key_texts.get(index).and_then(|texts| texts.get(row)) {
+                    ui.label(text);
+                }
+            }
+            column if column.start == keys.end => {
+                let number = self.data_frame["ComparativeMajorNumber"]
+                    .u32()?
+                    .get(row)
+                    .unwrap_or_default();
+                ui.label(comparative_major_number(number, self.source.len()))
+                    .on_hover_ui(|ui| {
+                        ui.label(ui.localize("comparison-major"));
+                        for (index, frame) in self.source.iter().enumerate() {
+                            if is_major(number, index) {
+                                ui.label(frame.meta.title());
+                            }
+                        }
+                    });
+            }
+            column => {
+                let index = (column.start - keys.end - 1) / STEP;
+                let text = match (column.start - keys.end - 1) % STEP {
+                    0 => float(
+                        self.data_frame[&*format!("Value{index}")].f64()?.get(row),
+                        precision,
+                        percent,
+                    ),
+                    1 => float(
+                        self.data_frame[&*format!("Difference{index}")]
+                            .f64()?
+                            .get(row),
+                        precision,
+                        percent,
+                    ),
+                    _ => float(
+                        self.data_frame[&*format!("FoldChange{index}")]
+                            .f64()?
+                            .get(row),
+                        precision,
+                        false,
+                    ),
+                };
+                ui.label(text);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TableDelegate for TableView<'_> {
+    fn header_cell_ui(&mut self, ui: &mut Ui, cell: &HeaderCellInfo) {
+        Frame::new()
+            .inner_margin(Margin::from(MARGIN))
+            .show(ui, |ui| {
+                self.header_cell_content_ui(ui, cell.row_nr, cell.col_range.clone())
+            });
+    }
+
+    fn cell_ui(&mut self, ui: &mut Ui, cell: &CellInfo) {
+        if cell.row_nr % 2 == 0 {
+            ui.painter()
+                .rect_filled(ui.max_rect(), 0.0, ui.visuals().faint_bg_color);
+        }
+        Frame::new()
+            .inner_margin(Margin::from(MARGIN))
+            .show(ui, |ui| {
+                self.cell_content_ui(ui, cell.row_nr as _, cell.col_nr..cell.col_nr + 1)
+                    .context(ui.ctx())
+            });
+    }
+}
+
+/// Value of the sample survives the filter
+pub(super) fn is_major(number: u32, index: usize) -> bool {
+    number.checked_shr(index as _).unwrap_or_default() & 1 == 1
+}
+
+/// Comparative major number text, one mark per sample (major `●`, minor `○`)
+pub(super) fn comparative_major_number(number: u32, count: usize) -> String {
+    (0..count)
+        .map(|index| {
+            if is_major(number, index) {
+                '●'
+            } else {
+                '○'
+            }
+        })
+        .collect()
+}
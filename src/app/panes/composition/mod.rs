@@ -4,23 +4,44 @@ use self::{
     state::{State, View},
     table::TableView,
 };
-use super::PaneDelegate;
-use crate::app::{
-    computers::{
-        FattyAcidCompositionComputed, FattyAcidCompositionKey, TriacylglycerolCompositionComputed,
-        TriacylglycerolCompositionKey,
+use super::{
+    PaneDelegate,
+    provenance::{Provenance, save_result},
+};
+use crate::{
+    app::{
+        ContextExt as _,
+        computers::{
+            FattyAcidCompositionComputed, FattyAcidCompositionKey, Groups, InverseComputed,
+            InverseKey, ModelsComputed, ModelsKey, Spread, TriacylglycerolCompositionComputed,
+            TriacylglycerolCompositionKey,
+        },
+        identifiers::DATA,
+        panes::selection::float,
+        text::Text,
+    },
+    parsers::clipboard::profile,
+    special::{
+        composition::{MNC, SSC},
+        inverse::Kind,
     },
-    text::Text,
+    utils::{statistics::fit, xlsx::Sheet},
 };
-use egui::{CursorIcon, Response, RichText, Ui, Window, util::hash};
+use anyhow::Result;
+use egui::{Button, CursorIcon, Grid, Id, Response, RichText, TextEdit, Ui, Window, util::hash};
 use egui_l20n::UiExt as _;
 use egui_phosphor::regular::{
-    ARROWS_CLOCKWISE, ARROWS_HORIZONTAL, CHECK, GEAR, INTERSECT_THREE, LIST,
+    ARROWS_CLOCKWISE, ARROWS_HORIZONTAL, CHECK, DATABASE, FLOPPY_DISK, FUNCTION, GEAR, GIT_DIFF,
+    INTERSECT_THREE, LIST, LOCK, WARNING,
 };
-use metadata::MetaDataFrame;
+use metadata::{MetaDataFrame, Metadata};
 use polars::prelude::*;
 use polars_utils::format_list_truncated;
 use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::mpsc::Sender,
+};
 
 const ID_SOURCE: &str = "Composition";
 
@@ -31,15 +52,40 @@ pub(crate) struct Pane {
     pub(crate) target: DataFrame,
     pub(crate) settings: Settings,
     state: State,
+    /// Saved result, not recomputed
+    #[serde(default)]
+    pub(crate) read_only: bool,
 }
 
 impl Pane {
-    pub(crate) fn new(frames: Vec<MetaDataFrame>, index: Option<usize>) -> Self {
+    pub(crate) fn new(
+        frames: Vec<MetaDataFrame>,
+        index: Option<usize>,
+        groups: Vec<String>,
+    ) -> Self {
         Self {
             source: frames,
             target: DataFrame::empty(),
-            settings: Settings::new(index),
+            settings: Settings {
+                groups,
+                ..Settings::new(index)
+            },
+            state: State::new(),
+            read_only: false,
+        }
+    }
+
+    pub(crate) fn read_only(
+        frames: Vec<MetaDataFrame>,
+        target: DataFrame,
+        settings: Settings,
+    ) -> Self {
+        Self {
+            source: frames,
+            target,
+            settings,
             state: State::new(),
+            read_only: true,
         }
     }
 
@@ -54,6 +100,124 @@ impl Pane {
         }
     }
 
+    /// Spreadsheet sheet with the selections described in the header
+    pub(crate) fn sheet(&self, ui: &Ui) -> PolarsResult<Sheet> {
+        let confirmed = &self.settings.confirmed;
+        let mut header = vec![
+            format!("{}: {}", ui.localize("composition"), self.title()),
+            format!(
+                "{}: {}",
+                ui.localize("settings-method"),
+                ui.localize(confirmed.method.text()),
+            ),
+        ];
+        if let Some(abbreviation) = confirmed.unit.abbreviation() {
+            header.push(format!("{}: {abbreviation}", ui.localize("settings-unit")));
+        }
+        let target = if self.read_only {
+            self.target.clone()
+        } else {
+            ui.memory_mut(|memory| {
+                memory
+                    .caches
+                    .cache::<TriacylglycerolCompositionComputed>()
+                    .get(TriacylglycerolCompositionKey {
+                        frames: &self.source,
+                        settings: &self.settings,
+                    })
+            })
+            .map_err(|error| polars_err!(ComputeError: "{error}"))?
+        };
+        let keys = target["Keys"].struct_()?.fields_as_series();
+        let values = target["Values"].as_materialized_series();
+        let value = |name: String, f: &dyn Fn(Series) -> PolarsResult<Option<f64>>| {
+            let values = (0..values.len())
+                .map(|row| table::array_value(values, row, f))
+                .collect::<PolarsResult<Vec<_>>>()?;
+            PolarsResult::Ok(Series::new(name.into(), values).into_column())
+        };
+        let mut columns = vec![target["Index"].clone()];
+        let mut names = Vec::<String>::with_capacity(confirmed.selections.len());
+        for (index, selection) in confirmed.selections.iter().enumerate() {
+            let composition = selection.composition;
+            let mut name = ui.localize(composition.text());
+            header.push(format!(
+                "{name}: {}, {} ≥ {}",
+                ui.localize(composition.hover_text()),
+                ui.localize("value"),
+                selection.filter.value,
+            ));
+            let count = names.iter().filter(|&other| *other == name).count();
+            names.push(name.clone());
+            if count > 0 {
+                name = format!("{name} ({})", count + 1);
+            }
+            // Key
+            let key = &keys[index];
+            columns.push(match composition {
+                MNC => key.clone().with_name(name.as_str().into()).into_column(),
+                _ => {
+                    let key = table::key(composition, key, self.settings.precision)?;
+                    let text = (0..key.len())
+                        .map(|row| table::key_text(composition, &key, row))
+                        .collect::<PolarsResult<Vec<_>>>()?;
+                    Series::new(name.as_str().into(), text).into_column()
+                }
+            });
+            // Value
+            match values.dtype() {
+                DataType::Array(inner, _) if inner.is_struct() => {
+                    let mut fields = vec!["Mean"];
+                    match table::spread(values)? {
+                        Spread::Interval(name) => fields.push(name),
+                        Spread::Range(minimum, maximum) => fields.extend([minimum, maximum]),
+                    }
+                    if table::is_propagated(values) {
+                        fields.push("Propagated");
+                    }
+                    if table::is_tested(values) {
+                        fields.push("PValue");
+                    }
+                    for field in fields {
+                        columns.push(value(format!("{name}.{field}"), &|list| {
+                            Ok(list.struct_()?.field_by_name(field)?.f64()?.get(index))
+                        })?);
+                    }
+                    if table::is_tested(values) {
+                        let groups = Groups::new(&self.settings.groups);
+                        for (group, group_name) in groups.names.iter().enumerate() {
+                            for (field, suffix) in [
+                                ("GroupMeans", "Mean"),
+                                ("GroupStandardDeviations", "StandardDeviation"),
+                            ] {
+                                columns.push(value(
+                                    format!("{name}.{group_name}.{suffix}"),
+                                    &|list| {
+                                        let lists = list.struct_()?.field_by_name(field)?;
+                                        Ok(lists
+                                            .list()?
+                                            .get_as_series(index)
+                                            .and_then(|series| series.f64().ok()?.get(group)))
+                                    },
+                                )?);
+                            }
+                        }
+                    }
+                }
+                _ => columns.push(value(format!("{name}.Value"), &|list| {
+                    Ok(list.f64()?.get(index))
+                })?),
+            }
+        }
+        Ok(Sheet {
+            name: format!("{} composition", self.title()),
+            header,
+            data: DataFrame::new(columns)?,
+            precision: self.settings.precision,
+            percent: self.settings.percent().then_some(percent as _),
+        })
+    }
+
     fn header_content(&mut self, ui: &mut Ui) -> Response {
         let mut response = ui
             .heading(Self::icon())
@@ -63,27 +227,31 @@ impl Pane {
             .on_hover_text(format!("{:x}", self.hash()))
             .on_hover_cursor(CursorIcon::Grab);
         ui.separator();
-        // List
-        ui.menu_button(RichText::new(LIST).heading(), |ui| {
-            let mut clicked = false;
-            for index in 0..self.source.len() {
+        if self.read_only {
+            ui.heading(LOCK).on_hover_text(ui.localize("read_only"));
+        } else {
+            // List
+            ui.menu_button(RichText::new(LIST).heading(), |ui| {
+                let mut clicked = false;
+                for index in 0..self.source.len() {
+                    clicked |= ui
+                        .selectable_value(
+                            &mut self.settings.index,
+                            Some(index),
+                            self.source[index].meta.title(),
+                        )
+                        .clicked()
+                }
                 clicked |= ui
-                    .selectable_value(
-                        &mut self.settings.index,
-                        Some(index),
-                        self.source[index].meta.title(),
-                    )
-                    .clicked()
-            }
-            clicked |= ui
-                .selectable_value(&mut self.settings.index, None, "Mean ± standard deviations")
-                .clicked();
-            if clicked {
-                ui.close_menu();
-            }
-        })
-        .response
-        .on_hover_text(ui.localize("list"));
+                    .selectable_value(&mut self.settings.index, None, "Mean ± standard deviations")
+                    .clicked();
+                if clicked {
+                    ui.close_menu();
+                }
+            })
+            .response
+            .on_hover_text(ui.localize("list"));
+        }
         ui.separator();
         // Reset
         if ui
@@ -99,12 +267,48 @@ impl Pane {
         )
         .on_hover_text(ui.localize("resize"));
         ui.separator();
-        // Settings
-        ui.toggle_value(
-            &mut self.state.open_settings_window,
-            RichText::new(GEAR).heading(),
-        );
+        // Save
+        if ui
+            .button(RichText::new(FLOPPY_DISK).heading())
+            .on_hover_text(ui.localize("save"))
+            .on_hover_text(format!("{}.composition.utca.ipc", self.title()))
+            .clicked()
+        {
+            if let Err(error) = self.save() {
+                ui.ctx().error(error);
+            }
+        }
         ui.separator();
+        // Settings
+        if !self.read_only {
+            ui.toggle_value(
+                &mut self.state.open_settings_window,
+                RichText::new(GEAR).heading(),
+            );
+            // Models
+            ui.toggle_value(
+                &mut self.state.open_models_window,
+                RichText::new(FUNCTION).heading(),
+            )
+            .on_hover_text(ui.localize("models"));
+            // Comparison
+            if ui
+                .add_enabled(
+                    self.source.len() > 1,
+                    Button::new(RichText::new(GIT_DIFF).heading()),
+                )
+                .on_hover_text(ui.localize("comparison"))
+                .clicked()
+            {
+                ui.data_mut(|data| {
+                    data.insert_temp(
+                        Id::new("Compare"),
+                        (self.source.clone(), self.settings.clone()),
+                    )
+                });
+            }
+            ui.separator();
+        }
         // View
         ui.menu_button(RichText::new(self.state.view.icon()).heading(), |ui| {
             ui.selectable_value(&mut self.state.view, View::Plot, View::Plot.text())
@@ -120,15 +324,24 @@ impl Pane {
     }
 
     fn body_content(&mut self, ui: &mut Ui) {
-        self.target = ui.memory_mut(|memory| {
-            memory
-                .caches
-                .cache::<TriacylglycerolCompositionComputed>()
-                .get(TriacylglycerolCompositionKey {
-                    frames: &self.source,
-                    settings: &self.settings,
-                })
-        });
+        if !self.read_only {
+            let result = ui.memory_mut(|memory| {
+                memory
+                    .caches
+                    .cache::<TriacylglycerolCompositionComputed>()
+                    .get(TriacylglycerolCompositionKey {
+                        frames: &self.source,
+                        settings: &self.settings,
+                    })
+            });
+            match result {
+                Ok(target) => self.target = target,
+                Err(error) => {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                    return;
+                }
+            }
+        }
         match self.state.view {
             View::Plot => PlotView::new(&self.target, &self.settings, &mut self.state).show(ui),
             View::Table => TableView::new(&self.target, &self.settings, &mut self.state).show(ui),
@@ -136,6 +349,18 @@ impl Pane {
     }
 
     fn windows(&mut self, ui: &mut Ui) {
+        // Models
+        let mut open_models_window = self.state.open_models_window;
+        Window::new(format!("{FUNCTION} {}", ui.localize("models")))
+            .id(ui.auto_id_with("Models"))
+            .default_pos(ui.next_widget_position())
+            .open(&mut open_models_window)
+            .show(ui.ctx(), |ui| {
+                if let Err(error) = self.models_content(ui) {
+                    ui.ctx().error(error);
+                }
+            });
+        self.state.open_models_window = open_models_window;
         Window::new(format!("{GEAR} Composition settings"))
             .id(ui.auto_id_with(ID_SOURCE))
             .default_pos(ui.next_widget_position())
@@ -149,7 +374,7 @@ impl Pane {
                 //         },
                 //     )
                 // });
-                self.settings.show(ui, &self.target);
+                self.settings.show(ui, &self.source, &self.target);
                 let enabled = hash(&self.settings.confirmed) != hash(&self.settings.unconfirmed);
                 ui.add_enabled_ui(enabled, |ui| {
                     ui.horizontal(|ui| {
@@ -170,11 +395,294 @@ impl Pane {
             });
     }
 
+    /// Goodness of fit of the methods to the measured species
+    fn models_content(&mut self, ui: &mut Ui) -> Result<()> {
+        ui.label(ui.localize("models-measured"))
+            .on_hover_text(ui.localize("models-measured.hover"));
+        ui.add(
+            TextEdit::multiline(&mut self.settings.measured)
+                .hint_text("{P,O,P}\t12,5")
+                .desired_width(f32::INFINITY),
+        );
+        let measured = profile(&self.settings.measured)?;
+        if measured.is_empty() {
+            return Ok(());
+        }
+        let models = match ui.memory_mut(|memory| {
+            memory.caches.cache::<ModelsComputed>().get(ModelsKey {
+                frames: &self.source,
+                settings: &self.settings,
+            })
+        }) {
+            Ok(models) => models,
+            Err(error) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+                return Ok(());
+            }
+        };
+        let composition = self
+            .settings
+            .confirmed
+            .selections
+            .front()
+            .map_or(SSC, |selection| selection.composition);
+        let precision = self.settings.precision;
+        let mut observed = HashMap::new();
+        for (key, value) in &measured {
+            *observed.entry(key.as_str()).or_default() += value;
+        }
+        let total = observed.values().sum::<f64>();
+        ui.separator();
+        Grid::new(ui.next_auto_id())
+            .striped(true)
+            .show(ui, |ui| -> Result<()> {
+                ui.heading(ui.localize("settings-method"));
+                ui.heading("χ²")
+                    .on_hover_text(ui.localize("models-chi_square"));
+                ui.heading(ui.localize("models-root_mean_square.abbreviation"))
+                    .on_hover_text(ui.localize("models-root_mean_square"));
+                ui.heading(ui.localize("models-similarity.abbreviation"))
+                    .on_hover_text(ui.localize("models-similarity"));
+                ui.end_row();
+                for (method, data_frame) in &models {
+                    let key = table::key(
+                        composition,
+                        data_frame["Key"].as_materialized_series(),
+                        precision,
+                    )?;
+                    let values = data_frame["Value"].f64()?;
+                    let mut expected = HashMap::new();
+                    for row in 0..key.len() {
+                        let value = values.get(row).unwrap_or_default();
+                        *expected
+                            .entry(table::key_text(composition, &key, row)?)
+                            .or_default() += value;
+                    }
+                    let keys = expected
+                        .keys()
+                        .map(String::as_str)
+                        .chain(observed.keys().copied())
+                        .collect::<BTreeSet<_>>();
+                    let (o, e): (Vec<_>, Vec<_>) = keys
+                        .into_iter()
+                        .map(|key| {
+                            (
+                                observed.get(key).map_or(0.0, |value| value / total),
+                                expected.get(key).copied().unwrap_or_default(),
+                            )
+                        })
+                        .unzip();
+                    let fit = fit(&o, &e);
+                    ui.label(ui.localize(method.text()))
+                        .on_hover_text(ui.localize(method.hover_text()));
+                    let response = ui.label(if fit.chi_square.is_finite() {
+                        format!("{:.precision$}", fit.chi_square)
+                    } else {
+                        "∞".to_owned()
+                    });
+                    if fit.unexpected > 0.0 {
+                        response.on_hover_text(format!(
+                            "{}: {}",
+                            ui.localize("models-unexpected"),
+                            float(Some(fit.unexpected), precision, true),
+                        ));
+                    }
+                    ui.label(float(Some(fit.root_mean_square), precision, true));
+                    ui.label(float(Some(fit.similarity), precision, true));
+                    ui.end_row();
+                }
+                Ok(())
+            })
+            .inner?;
+        if let Some(kind) = Kind::new(composition) {
+            ui.separator();
+            self.inverse_content(ui, kind)?;
+        }
+        Ok(())
+    }
+
+    /// sn-1,3 and sn-2 distributions fitted to the measured species
+    fn inverse_content(&self, ui: &mut Ui, kind: Kind) -> Result<()> {
+        let inverse = match ui.memory_mut(|memory| {
+            memory.caches.cache::<InverseComputed>().get(InverseKey {
+                frames: &self.source,
+                index: self.settings.index,
+                kind,
+                measured: &self.settings.measured,
+            })
+        }) {
+            Ok(inverse) => inverse,
+            Err(error) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+                return Ok(());
+            }
+        };
+        let precision = self.settings.precision;
+        ui.horizontal(|ui| {
+            ui.heading(ui.localize("inverse"))
+                .on_hover_text(ui.localize("inverse.hover"));
+            ui.label(format!(
+                "{}: {}",
+                ui.localize("models-root_mean_square.abbreviation"),
+                float(Some(inverse.root_mean_square), precision, true),
+            ));
+        });
+        if !inverse.unmatched.is_empty() {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!(
+                    "{WARNING} {}: {}",
+                    ui.localize("inverse-unmatched"),
+                    inverse.unmatched.join(" "),
+                ),
+            );
+        }
+        ui.columns(2, |columns| -> Result<()> {
+            Grid::new(columns[0].next_auto_id())
+                .striped(true)
+                .show(&mut columns[0], |ui| -> Result<()> {
+                    ui.heading(ui.localize("label"));
+                    ui.heading("sn-1,3");
+                    ui.heading("sn-2");
+                    ui.end_row();
+                    let labels = inverse.distribution["Label"].str()?;
+                    let sn13 = inverse.distribution["StereospecificNumber13"].f64()?;
+                    let sn2 = inverse.distribution["StereospecificNumber2"].f64()?;
+                    for row in 0..inverse.distribution.height() {
+                        ui.label(labels.get(row).unwrap_or_default());
+                        ui.label(float(sn13.get(row), precision, true));
+                        ui.label(float(sn2.get(row), precision, true));
+                        ui.end_row();
+                    }
+                    Ok(())
+                })
+                .inner?;
+            Grid::new(columns[1].next_auto_id())
+                .striped(true)
+                .show(&mut columns[1], |ui| -> Result<()> {
+                    ui.heading(ui.localize("inverse-species"));
+                    ui.heading(ui.localize("inverse-measured"));
+                    ui.heading(ui.localize("inverse-fitted"));
+                    ui.heading(ui.localize("inverse-residual"));
+                    ui.end_row();
+                    let keys = inverse.species["Key"].str()?;
+                    let measured = inverse.species["Measured"].f64()?;
+                    let fitted = inverse.species["Fitted"].f64()?;
+                    let residuals = inverse.species["Residual"].f64()?;
+                    for row in 0..inverse.species.height() {
+                        ui.label(keys.get(row).unwrap_or_default());
+                        ui.label(float(measured.get(row), precision, true));
+                        ui.label(float(fitted.get(row), precision, true));
+                        ui.label(float(residuals.get(row), precision, true));
+                        ui.end_row();
+                    }
+                    Ok(())
+                })
+                .inner
+        })?;
+        if ui
+            .button(format!(
+                "{DATABASE} {}",
+                ui.localize("inverse-configuration")
+            ))
+            .on_hover_text(ui.localize("inverse-configuration.hover"))
+            .clicked()
+        {
+            self.send_inverse(ui, &inverse.distribution)?;
+        }
+        Ok(())
+    }
+
+    /// Sends the synthetic configuration of the estimated distributions to
+    /// the data
+    ///
+    /// `TAG = (2 * sn13 + sn2) / 3`, `DAG1223 = (sn13 + sn2) / 2`, `MAG2 = sn2`.
+    fn send_inverse(&self, ui: &Ui, distribution: &DataFrame) -> Result<()> {
+        let frame = &self.source[self.settings.index.unwrap_or_default()];
+        // Fatty acids of the fitted labels (of the frame or of all frames)
+        let frames = match self.settings.index {
+            Some(index) => self.source.get(index..=index).unwrap_or_default(),
+            None => &self.source[..],
+        };
+        let fatty_acids = concat(
+            frames
+                .iter()
+                .map(|frame| {
+                    frame
+                        .data
+                        .clone()
+                        .lazy()
+                        .select([col("Label"), col("FattyAcid")])
+                })
+                .collect::<Vec<_>>(),
+            UnionArgs::default(),
+        )?
+        .group_by_stable([col("Label")])
+        .agg([col("FattyAcid").first()]);
+        let mut data = distribution
+            .clone()
+            .lazy()
+            .join(
+                fatty_acids,
+                [col("Label")],
+                [col("Label")],
+                JoinArgs::new(JoinType::Left),
+            )
+            .select([
+                col("Label"),
+                col("FattyAcid"),
+                ((lit(2) * col("StereospecificNumber13") + col("StereospecificNumber2")) / lit(3))
+                    .alias("Triacylglycerol"),
+                ((col("StereospecificNumber13") + col("StereospecificNumber2")) / lit(2))
+                    .alias("Diacylglycerol1223"),
+                col("StereospecificNumber2").alias("Monoacylglycerol2"),
+            ])
+            .with_row_index("Index", None)
+            .collect()?;
+        let name = format!("{} inverse", self.title());
+        let meta = Metadata {
+            name: name.clone(),
+            description: format!("Fitted to the measured species of {}", self.title()),
+            ..frame.meta.clone()
+        };
+        let mut bytes = Vec::new();
+        MetaDataFrame::new(meta, &mut data).write(&mut bytes)?;
+        if let Some(sender) = ui
+            .ctx()
+            .data_mut(|data| data.get_temp::<Sender<(String, Vec<u8>)>>(*DATA))
+        {
+            sender.send((format!("{name}.utca.ipc"), bytes))?;
+        }
+        Ok(())
+    }
+
+    /// Saves the result with the source metadata and the settings
+    fn save(&self) -> Result<()> {
+        let provenance = Provenance::Composition {
+            sources: self.source.iter().map(|frame| frame.meta.clone()).collect(),
+            settings: self.settings.clone(),
+        };
+        let name = format!("{}.composition.utca.ipc", self.title());
+        save_result(&name, self.title(), &provenance, &self.target)
+    }
+
     fn hash(&self) -> u64 {
         hash(&self.source)
     }
 }
 
+/// Values are percents, keys are not
+fn percent(name: &str) -> bool {
+    name.ends_with(".Value")
+        || name.ends_with(".Mean")
+        || name.ends_with(".StandardDeviation")
+        || name.ends_with(".StandardError")
+        || name.ends_with(".ConfidenceInterval")
+        || name.ends_with(".Minimum")
+        || name.ends_with(".Maximum")
+        || name.ends_with(".Propagated")
+}
+
 impl PaneDelegate for Pane {
     fn header(&mut self, ui: &mut Ui) -> Response {
         self.header_content(ui)
@@ -187,7 +695,7 @@ impl PaneDelegate for Pane {
 }
 
 pub(crate) mod settings;
+pub(crate) mod table;
 
 mod plot;
 mod state;
-mod table;
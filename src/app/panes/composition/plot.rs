@@ -60,7 +60,7 @@ impl PlotView<'_> {
                     UC => keys.i64()?.get(row).unwrap() as _,
                     _ => indices.u32()?.get(row).unwrap() as _,
                 };
-                if self.settings.percent {
+                if self.settings.percent() {
                     value *= 100.0;
                 }
                 bars.push(Bar::new(x, value).name(key));
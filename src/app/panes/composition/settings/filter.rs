@@ -28,14 +28,17 @@ use tracing::error;
 /// Filter
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Filter {
-    pub key: HashSet<AnyValue<'static>>,
+    /// Excluded keys of each stereospecific number (the first one for the not
+    /// stereospecific keys)
+    #[serde(default)]
+    pub keys: [HashSet<AnyValue<'static>>; 3],
     pub value: f64,
 }
 
 impl Filter {
     pub fn new() -> Self {
         Self {
-            key: HashSet::new(),
+            keys: Default::default(),
             value: 0.0,
         }
     }
@@ -45,20 +48,21 @@ impl Eq for Filter {}
 
 impl Hash for Filter {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        state.write_usize(self.key.len());
-        let hash = self
-            .key
-            .iter()
-            .map(|value| RandomState::with_seeds(1, 2, 3, 4).hash_one(value))
-            .fold(0, BitXor::bitxor);
-        state.write_u64(hash);
+        for keys in &self.keys {
+            state.write_usize(keys.len());
+            let hash = keys
+                .iter()
+                .map(|value| RandomState::with_seeds(1, 2, 3, 4).hash_one(value))
+                .fold(0, BitXor::bitxor);
+            state.write_u64(hash);
+        }
         self.value.ord().hash(state);
     }
 }
 
 impl PartialEq for Filter {
     fn eq(&self, other: &Self) -> bool {
-        self.key == other.key && self.value.ord() == other.value.ord()
+        self.keys == other.keys && self.value.ord() == other.value.ord()
     }
 }
 
@@ -100,32 +104,47 @@ impl Widget for FilterWidget<'_> {
             ));
             // Key
             ui.labeled_separator("Key");
-            let series = |index| -> PolarsResult<Series> {
-                let series = if let Some(r#struct) = self.series.try_struct() {
-                    &r#struct.fields_as_series()[index]
-                } else {
-                    self.series
-                };
-                Ok(series.unique()?.sort(Default::default())?)
+            let keys = &mut self.selection.filter.keys;
+            // Excluded keys are listed even if the rows are filtered out
+            let series = |series: &Series, keys: &HashSet<AnyValue<'static>>| {
+                let mut series = series.unique()?;
+                if !keys.is_empty() {
+                    let values = keys.iter().cloned().collect::<Vec<_>>();
+                    series.append(&Series::from_any_values(
+                        series.name().clone(),
+                        &values,
+                        false,
+                    )?)?;
+                }
+                series.unique()?.sort(Default::default())
             };
-            ui.columns_const(|ui: &mut [Ui; 3]| -> PolarsResult<()> {
-                ui[0].add(ColumnWidget1 {
-                    header: "sn1",
-                    selection: self.selection,
-                    series: series(0)?,
-                });
-                ui[1].add(ColumnWidget1 {
-                    header: "sn2",
-                    selection: self.selection,
-                    series: series(1)?,
+            if let Some(r#struct) = self.series.try_struct() {
+                let fields = r#struct.fields_as_series();
+                let [sn1, sn2, sn3] = keys;
+                let series = [
+                    series(&fields[0], sn1)?,
+                    series(&fields[1], sn2)?,
+                    series(&fields[2], sn3)?,
+                ];
+                ui.columns_const(|ui: &mut [Ui; 3]| {
+                    for (index, ((ui, key), series)) in
+                        ui.iter_mut().zip([sn1, sn2, sn3]).zip(series).enumerate()
+                    {
+                        ui.add(ColumnWidget1 {
+                            header: ["sn1", "sn2", "sn3"][index],
+                            key,
+                            series,
+                        });
+                    }
                 });
-                ui[2].add(ColumnWidget1 {
-                    header: "sn3",
-                    selection: self.selection,
-                    series: series(2)?,
+            } else {
+                let series = series(self.series, &keys[0])?;
+                ui.add(ColumnWidget1 {
+                    header: &ui.localize(self.selection.composition.text()),
+                    key: &mut keys[0],
+                    series,
                 });
-                Ok(())
-            })?;
+            }
             // Value
             ui.separator();
             ui.horizontal(|ui| {
@@ -157,7 +176,7 @@ impl Widget for FilterWidget<'_> {
 
 struct ColumnWidget1<'a> {
     header: &'a str,
-    selection: &'a mut Selection,
+    key: &'a mut HashSet<AnyValue<'static>>,
     series: Series,
 }
 
@@ -181,31 +200,23 @@ impl<'a> Widget for ColumnWidget1<'a> {
                     for index in range {
                         let value = self.series.get(index)?.into_static();
                         let text = value.str_value();
-                        let contains = self.selection.filter.key.contains(&value);
-                        println!("contains: {value} {contains}");
+                        let contains = self.key.contains(&value);
                         let mut selected = contains;
                         let response = ui.toggle_value(&mut selected, text);
                         if selected && !contains {
-                            println!("selected value: {value}");
-                            println!("self.selection.filter.key: {:?}", self.selection.filter.key);
-                            self.selection.filter.key.insert(value);
+                            self.key.insert(value);
                         } else if !selected && contains {
-                            println!("!selected value: {value}");
-                            self.selection.filter.key.remove(&value);
+                            self.key.remove(&value);
                         }
                         response.context_menu(|ui| {
                             if ui.button(format!("{FUNNEL} Select all")).clicked() {
                                 for key in self.series.iter() {
-                                    self.selection
-                                        .filter
-                                        .key
-                                        .entry(key.into_static())
-                                        .or_insert();
+                                    self.key.entry(key.into_static()).or_insert();
                                 }
                                 ui.close_menu();
                             }
                             if ui.button(format!("{FUNNEL_X} Unselect all")).clicked() {
-                                self.selection.filter.key.clear();
+                                self.key.clear();
                                 ui.close_menu();
                             }
                         });
@@ -224,7 +235,7 @@ impl<'a> Widget for ColumnWidget1<'a> {
 
 struct ColumnWidget<'a> {
     header: &'a str,
-    selection: &'a mut Selection,
+    key: &'a mut HashSet<AnyValue<'static>>,
     series: Series,
 }
 
@@ -258,27 +269,23 @@ impl<'a> Widget for ColumnWidget<'a> {
                     row.col(|ui| match self.series.get(index) {
                         Ok(value) => {
                             let text = value.str_value();
-                            let contains = self.selection.filter.key.contains(&value);
+                            let contains = self.key.contains(&value);
                             let mut selected = contains;
                             let response = ui.toggle_value(&mut selected, text);
                             if selected && !contains {
-                                self.selection.filter.key.insert(value.into_static());
+                                self.key.insert(value.into_static());
                             } else if !selected && contains {
-                                self.selection.filter.key.remove(&value.into_static());
+                                self.key.remove(&value.into_static());
                             }
                             response.context_menu(|ui| {
                                 if ui.button(format!("{FUNNEL} Select all")).clicked() {
                                     for key in self.series.iter() {
-                                        self.selection
-                                            .filter
-                                            .key
-                                            .entry(key.into_static())
-                                            .or_insert();
+                                        self.key.entry(key.into_static()).or_insert();
                                     }
                                     ui.close_menu();
                                 }
                                 if ui.button(format!("{FUNNEL_X} Unselect all")).clicked() {
-                                    self.selection.filter.key.clear();
+                                    self.key.clear();
                                     ui.close_menu();
                                 }
                             });
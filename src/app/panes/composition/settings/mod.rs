@@ -1,16 +1,23 @@
 pub(crate) use self::filter::{Filter, FilterWidget};
 
 use crate::{
-    app::{MAX_PRECISION, text::Text},
+    app::{
+        MAX_PRECISION,
+        computers::composition::triacylglycerol::Profile,
+        panes::calculation::settings::{Dispersion, Unit},
+        text::Text,
+    },
     r#const::relative_atomic_mass::{H, LI, NA, NH4},
     special::composition::{COMPOSITIONS, Composition},
 };
 use egui::{
-    ComboBox, DragValue, Grid, Key, KeyboardShortcut, Modifiers, RichText, Slider, Ui, emath::Float,
+    ComboBox, DragValue, Grid, Key, KeyboardShortcut, Modifiers, RichText, Slider, TextEdit, Ui,
+    emath::Float,
 };
 use egui_ext::LabeledSeparator;
 use egui_l20n::UiExt;
-use egui_phosphor::regular::{MINUS, PLUS};
+use egui_phosphor::regular::{MINUS, PLUS, WARNING};
+use metadata::MetaDataFrame;
 use polars::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -26,6 +33,17 @@ pub(crate) struct Settings {
     pub(crate) precision: usize,
     pub(crate) resizable: bool,
     pub(crate) sticky_columns: usize,
+    #[serde(default)]
+    pub(crate) copy_headers: bool,
+    #[serde(default)]
+    pub(crate) separate_standard_deviations: bool,
+    /// Group of each source frame, from the calculation
+    #[serde(default)]
+    pub(crate) groups: Vec<String>,
+    /// Measured species profile (key and value rows) for the goodness of fit
+    /// of the methods
+    #[serde(default)]
+    pub(crate) measured: String,
 
     pub(crate) confirmed: Confirmable,
     pub(super) unconfirmed: Confirmable,
@@ -39,13 +57,22 @@ impl Settings {
             precision: 1,
             resizable: false,
             sticky_columns: 0,
+            copy_headers: false,
+            separate_standard_deviations: false,
+            groups: Vec::new(),
+            measured: String::new(),
 
             confirmed: Confirmable::new(),
             unconfirmed: Confirmable::new(),
         }
     }
 
-    pub(crate) fn show(&mut self, ui: &mut Ui, data_frame: &DataFrame) {
+    /// Values are relative and shown in percents
+    pub(crate) fn percent(&self) -> bool {
+        self.percent && self.confirmed.unit == Unit::Fraction
+    }
+
+    pub(crate) fn show(&mut self, ui: &mut Ui, source: &[MetaDataFrame], data_frame: &DataFrame) {
         Grid::new("Composition").show(ui, |ui| {
             // Precision
             ui.label(ui.localize("settings-precision"));
@@ -65,6 +92,18 @@ impl Settings {
             ));
             ui.end_row();
 
+            // Copy headers
+            ui.label(ui.localize("settings-copy_headers"))
+                .on_hover_text(ui.localize("settings-copy_headers.hover"));
+            ui.checkbox(&mut self.copy_headers, "");
+            ui.end_row();
+
+            // Separate standard deviations
+            ui.label(ui.localize("settings-separate_standard_deviations"))
+                .on_hover_text(ui.localize("settings-separate_standard_deviations.hover"));
+            ui.checkbox(&mut self.separate_standard_deviations, "");
+            ui.end_row();
+
             ui.separator();
             ui.separator();
             ui.end_row();
@@ -98,7 +137,7 @@ impl Settings {
                         .on_hover_text(selection.composition.hover_text());
                     // Filter
                     let series = &data_frame["Keys"].struct_().unwrap().fields_as_series()[index];
-                    ui.add(FilterWidget::new(selection, series).percent(self.percent));
+                    ui.add(FilterWidget::new(selection, series).percent(self.percent()));
                 });
                 ui.end_row();
                 index += 1;
@@ -118,25 +157,74 @@ impl Settings {
                 self.unconfirmed.method = Method::VanderWal;
             }
             ComboBox::from_id_salt("method")
-                .selected_text(self.unconfirmed.method.text())
+                .selected_text(ui.localize(self.unconfirmed.method.text()))
                 .show_ui(ui, |ui| {
-                    ui.selectable_value(
-                        &mut self.unconfirmed.method,
-                        Method::Gunstone,
-                        Method::Gunstone.text(),
-                    )
-                    .on_hover_text(Method::Gunstone.hover_text());
-                    ui.selectable_value(
-                        &mut self.unconfirmed.method,
-                        Method::VanderWal,
-                        Method::VanderWal.text(),
-                    )
-                    .on_hover_text(Method::VanderWal.hover_text());
+                    for method in Method::ALL {
+                        ui.selectable_value(
+                            &mut self.unconfirmed.method,
+                            method,
+                            ui.localize(method.text()),
+                        )
+                        .on_hover_text(ui.localize(method.hover_text()));
+                    }
                 })
                 .response
-                .on_hover_text(self.unconfirmed.method.hover_text());
+                .on_hover_text(ui.localize(self.unconfirmed.method.hover_text()));
             ui.end_row();
 
+            match self.unconfirmed.method {
+                Method::Stereospecific => {
+                    ui.label(ui.localize("settings-stereospecific_number1"))
+                        .on_hover_text(ui.localize("settings-stereospecific_number1.hover"));
+                    ui.vertical(|ui| {
+                        ui.add(
+                            TextEdit::multiline(&mut self.unconfirmed.stereospecific_number1)
+                                .hint_text("P\t25,4")
+                                .desired_rows(4),
+                        );
+                        let frame = match self.index {
+                            Some(index) => source.get(index),
+                            None => source.first(),
+                        };
+                        let labels = frame
+                            .and_then(|frame| frame.data.column("Label").ok()?.str().ok())
+                            .map(|labels| labels.into_iter().flatten().collect::<Vec<_>>())
+                            .unwrap_or_default();
+                        match Profile::new(&self.unconfirmed.stereospecific_number1, &labels) {
+                            Ok(profile) if !profile.unmatched.is_empty() => {
+                                ui.colored_label(
+                                    ui.visuals().warn_fg_color,
+                                    format!(
+                                        "{WARNING} {}: {}",
+                                        ui.localize("settings-stereospecific_number1-unmatched"),
+                                        profile.unmatched.join(" "),
+                                    ),
+                                );
+                            }
+                            Ok(_) => {}
+                            Err(error) => {
+                                ui.colored_label(ui.visuals().error_fg_color, error.to_string());
+                            }
+                        }
+                    });
+                    ui.end_row();
+                }
+                Method::RestrictedRandom => {
+                    ui.label(ui.localize("settings-restriction"))
+                        .on_hover_text(ui.localize("settings-restriction.hover"));
+                    ui.add(
+                        DragValue::new(&mut self.unconfirmed.restriction)
+                            .range(0.0..=1.0)
+                            .speed(0.001)
+                            .custom_formatter(|n, _| format!("{:.1}", n * 100.0))
+                            .custom_parser(|text| text.parse::<f64>().ok().map(|n| n / 100.0))
+                            .suffix("%"),
+                    );
+                    ui.end_row();
+                }
+                _ => {}
+            }
+
             // Adduct
             ui.label(ui.localize("settings-adduct"));
             ui.horizontal(|ui| {
@@ -176,6 +264,27 @@ impl Settings {
             ));
             ui.end_row();
 
+            // Unit
+            ui.label(ui.localize("settings-unit")).on_hover_ui(|ui| {
+                ui.label(ui.localize("settings-unit.hover"));
+            });
+            ComboBox::from_id_salt("unit")
+                .selected_text(ui.localize(self.unconfirmed.unit.text()))
+                .show_ui(ui, |ui| {
+                    for unit in [
+                        Unit::Fraction,
+                        Unit::MicromolePerGram,
+                        Unit::MilligramPerGram,
+                    ] {
+                        ui.selectable_value(
+                            &mut self.unconfirmed.unit,
+                            unit,
+                            ui.localize(unit.text()),
+                        );
+                    }
+                });
+            ui.end_row();
+
             // View
             ui.separator();
             ui.labeled_separator(RichText::new(ui.localize("settings-view")).heading());
@@ -247,16 +356,39 @@ impl Settings {
                 .on_hover_text(self.unconfirmed.order.hover_text());
             ui.end_row();
 
-            if self.index.is_none() {
-                // Statistic
-                ui.separator();
-                ui.labeled_separator(RichText::new(ui.localize("settings-statistic")).heading());
-                ui.end_row();
+            // Statistic
+            ui.separator();
+            ui.labeled_separator(RichText::new(ui.localize("settings-statistic")).heading());
+            ui.end_row();
 
+            if self.index.is_none() {
                 // https://numpy.org/devdocs/reference/generated/numpy.std.html
                 ui.label(ui.localize("settings-ddof"));
                 ui.add(Slider::new(&mut self.unconfirmed.ddof, 0..=2));
                 ui.end_row();
+
+                // Dispersion
+                self.unconfirmed.dispersion.show(ui);
+            }
+
+            // Propagate
+            ui.label(ui.localize("settings-propagate"))
+                .on_hover_text(ui.localize("settings-propagate.hover"));
+            ui.checkbox(&mut self.unconfirmed.propagate, "");
+            ui.end_row();
+
+            if self.unconfirmed.propagate {
+                ui.label(ui.localize("settings-measurement_error"))
+                    .on_hover_text(ui.localize("settings-measurement_error.hover"));
+                ui.add(
+                    DragValue::new(&mut self.unconfirmed.measurement_error)
+                        .range(0.0..=1.0)
+                        .speed(0.001)
+                        .custom_formatter(|n, _| format!("{:.1}", n * 100.0))
+                        .custom_parser(|text| text.parse::<f64>().ok().map(|n| n / 100.0))
+                        .suffix("%"),
+                );
+                ui.end_row();
             }
 
             ui.separator();
@@ -267,16 +399,29 @@ impl Settings {
 
 /// Composition confirmable settings
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
 pub(crate) struct Confirmable {
     pub(crate) adduct: f64,
     pub(crate) ddof: u8,
+    pub(crate) dispersion: Dispersion,
     pub(crate) selections: VecDeque<Selection>,
     pub(crate) join: Join,
+    /// Relative measurement error of the sn fractions
+    pub(crate) measurement_error: f64,
     pub(crate) method: Method,
+    /// Trisaturated species limit of the restricted random method
+    pub(crate) restriction: f64,
+    /// Measured sn-1 profile (label and value rows) of the stereospecific
+    /// method
+    pub(crate) stereospecific_number1: String,
     pub(crate) order: Order,
+    /// Propagate the uncertainty of the sn fractions
+    pub(crate) propagate: bool,
     pub(crate) round_mass: u32,
     pub(crate) show_filtered: bool,
     pub(crate) sort: Sort,
+    /// Unit of the values (absolute units require the internal standard)
+    pub(crate) unit: Unit,
 }
 
 impl Confirmable {
@@ -284,13 +429,19 @@ impl Confirmable {
         Self {
             adduct: 0.0,
             ddof: 1,
+            dispersion: Dispersion::new(),
             selections: VecDeque::new(),
             join: Join::Left,
+            measurement_error: 0.0,
             method: Method::VanderWal,
+            restriction: 0.0,
+            stereospecific_number1: String::new(),
             order: Order::Descending,
+            propagate: false,
             round_mass: 2,
             show_filtered: false,
             sort: Sort::Value,
+            unit: Unit::Fraction,
         }
     }
 }
@@ -305,13 +456,19 @@ impl Hash for Confirmable {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.adduct.ord().hash(state);
         self.ddof.hash(state);
+        self.dispersion.hash(state);
         self.selections.hash(state);
         self.join.hash(state);
+        self.measurement_error.ord().hash(state);
         self.method.hash(state);
+        self.restriction.ord().hash(state);
+        self.stereospecific_number1.hash(state);
         self.order.hash(state);
+        self.propagate.hash(state);
         self.round_mass.hash(state);
         self.show_filtered.hash(state);
         self.sort.hash(state);
+        self.unit.hash(state);
     }
 }
 
@@ -351,25 +508,47 @@ impl From<Join> for JoinType {
     }
 }
 
-/// Method
+/// Method (positional distribution hypothesis)
 #[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub(crate) enum Method {
+    /// 1,2,3-random with the saturated acids restriction
     Gunstone,
+    /// 1,3-random-2-random
     VanderWal,
+    /// 1,2,3-random
+    Random,
+    /// 1-random-2-random-3-random
+    Stereospecific,
+    /// 1,2,3-random with the trisaturated species restriction (Kartha)
+    RestrictedRandom,
 }
 
 impl Method {
+    pub(crate) const ALL: [Self; 5] = [
+        Self::Gunstone,
+        Self::VanderWal,
+        Self::Random,
+        Self::Stereospecific,
+        Self::RestrictedRandom,
+    ];
+
     pub(crate) fn text(&self) -> &'static str {
         match self {
-            Self::Gunstone => "gunstone",
-            Self::VanderWal => "vander_wal",
+            Self::Gunstone => "settings-gunstone",
+            Self::VanderWal => "settings-vander_wal",
+            Self::Random => "settings-random",
+            Self::Stereospecific => "settings-stereospecific",
+            Self::RestrictedRandom => "settings-restricted_random",
         }
     }
 
     pub(crate) fn hover_text(&self) -> &'static str {
         match self {
-            Self::Gunstone => "gunstone.description",
-            Self::VanderWal => "vander_wal.description",
+            Self::Gunstone => "settings-gunstone.hover",
+            Self::VanderWal => "settings-vander_wal.hover",
+            Self::Random => "settings-random.hover",
+            Self::Stereospecific => "settings-stereospecific.hover",
+            Self::RestrictedRandom => "settings-restricted_random.hover",
         }
     }
 }
@@ -1,4 +1,4 @@
-use crate::app::text::Text;
+use crate::app::{panes::selection::Selection, text::Text};
 use constcat::concat;
 use egui::Vec2b;
 use egui_phosphor::regular::{CHART_BAR, TABLE};
@@ -6,10 +6,14 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
 pub(crate) struct State {
+    #[serde(default)]
+    pub(crate) open_models_window: bool,
     pub(crate) open_settings_window: bool,
     pub(crate) view: View,
     // Table
     pub(crate) reset_table_state: bool,
+    #[serde(default)]
+    pub(crate) selection: Selection,
     // Plot
     pub(crate) allow_drag: Vec2b,
     pub(crate) allow_scroll: Vec2b,
@@ -19,10 +23,12 @@ pub(crate) struct State {
 impl State {
     pub(crate) const fn new() -> Self {
         Self {
+            open_models_window: false,
             open_settings_window: false,
             view: View::Table,
 
             reset_table_state: false,
+            selection: Selection::new(),
 
             allow_drag: Vec2b { x: false, y: false },
             allow_scroll: Vec2b { x: false, y: false },
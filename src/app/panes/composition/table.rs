@@ -1,8 +1,22 @@
 use super::{ID_SOURCE, Settings, State};
 use crate::{
-    app::{ResultExt, panes::MARGIN, text::Text, widgets::FloatWidget},
-    special::composition::{MNC, MSC, NNC, NSC, SNC, SPC, SSC, TNC, TPC, TSC, UNC, USC},
-    utils::polars::{tag_map, r#type},
+    app::{
+        ResultExt,
+        computers::{Groups, Spread},
+        panes::{
+            MARGIN,
+            selection::{float, groups, header, mean, tsv},
+        },
+        text::Text,
+        widgets::FloatWidget,
+    },
+    special::composition::{
+        Composition, MNC, MSC, NNC, NSC, SNC, SPC, SSC, TNC, TPC, TSC, UNC, USC,
+    },
+    utils::{
+        polars::{tag_map, r#type},
+        statistics::significance,
+    },
 };
 use egui::{Frame, Id, Margin, TextStyle, Ui};
 use egui_l20n::UiExt as _;
@@ -20,6 +34,7 @@ const INDEX: Range<usize> = 0..1;
 #[derive(Debug)]
 pub(super) struct TableView<'a> {
     data_frame: &'a DataFrame,
+    groups: Groups,
     settings: &'a Settings,
     state: &'a mut State,
     // is_row_expanded: BTreeMap<u64, bool>,
@@ -34,6 +49,7 @@ impl<'a> TableView<'a> {
     ) -> Self {
         Self {
             data_frame,
+            groups: Groups::new(&settings.groups),
             settings,
             state,
         }
@@ -68,46 +84,180 @@ impl TableView<'_> {
             .headers([
                 HeaderRow {
                     height,
-                    groups: top,
+                    groups: top.clone(),
                 },
                 HeaderRow {
                     height,
-                    groups: middle,
+                    groups: middle.clone(),
                 },
                 HeaderRow::new(height),
             ])
             .auto_size_mode(AutoSizeMode::OnParentResize)
             .show(ui, self);
+        if self.state.selection.copy(ui) {
+            if let Some(text) = self.copy(ui, &top, &middle).context(ui.ctx()) {
+                ui.ctx().copy_text(text);
+            }
+        }
     }
 
-    fn header_cell_content_ui(&mut self, ui: &mut Ui, row: usize, column: Range<usize>) {
-        match (row, column) {
-            (0, INDEX) => {
-                ui.heading(HASH).on_hover_ui(|ui| {
-                    ui.label(ui.localize("index"));
-                });
+    /// Selected cells as tab separated text
+    fn copy(&self, ui: &Ui, top: &[Range<usize>], middle: &[Range<usize>]) -> PolarsResult<String> {
+        let Some((rows, columns)) = self.state.selection.ranges() else {
+            return Ok(String::new());
+        };
+        let mut headers = Vec::new();
+        if self.settings.copy_headers {
+            for (row, groups) in [top, middle, &[]].into_iter().enumerate() {
+                headers.push(header(groups, columns.clone(), |column| {
+                    self.header_text(ui, row, column).map(|(text, _)| text)
+                }));
             }
-            (0, _) => {
-                ui.heading("Compositions");
+        }
+        let rows = rows
+            .map(|row| {
+                columns
+                    .clone()
+                    .map(|column| self.cell_text(row, column))
+                    .collect()
+            })
+            .collect::<PolarsResult<Vec<_>>>()?;
+        Ok(tsv(&headers, &rows))
+    }
+
+    fn cell_text(&self, row: usize, column: usize) -> PolarsResult<Vec<String>> {
+        let footer = row == self.data_frame.height();
+        let selections = &self.settings.confirmed.selections;
+        if column == 0 {
+            if footer {
+                return Ok(vec![String::new()]);
             }
-            (1, column) => {
-                if column.start % 2 == 1 {
-                    let index = column.start / 2;
-                    let composition = self.settings.confirmed.selections[index].composition;
-                    ui.heading(ui.localize(composition.text()))
-                        .on_hover_text(ui.localize(composition.hover_text()));
-                } else if column.start != 0 {
-                    ui.heading("Value");
-                }
+            let index = self.data_frame["Index"].u32()?.get(row).unwrap();
+            return Ok(vec![index.to_string()]);
+        }
+        let index = (column + 1) / 2 - 1;
+        if column % 2 == 1 {
+            if footer {
+                return Ok(vec![String::new()]);
             }
-            (2, column) => {
-                if column.start % 2 == 1 {
-                    ui.heading("Key");
-                } else if column.start != 0 {
-                    ui.heading("Value");
+            let keys = self.data_frame["Keys"].struct_()?;
+            let key = &keys.fields_as_series()[index];
+            let composition = selections[index].composition;
+            return Ok(vec![match composition {
+                MNC => float(key.f64()?.get(row), self.settings.precision, false),
+                _ => key_text(
+                    composition,
+                    &self::key(composition, key, self.settings.precision)?,
+                    row,
+                )?,
+            }]);
+        }
+        if footer && index != selections.len() - 1 {
+            return Ok(vec![String::new()]);
+        }
+        let series = self.data_frame["Values"].as_materialized_series();
+        let value = |f: &dyn Fn(Series) -> PolarsResult<Option<f64>>| {
+            if footer {
+                array_sum(series, f)
+            } else {
+                array_value(series, row, f)
+            }
+        };
+        let precision = self.settings.precision;
+        let percent = self.settings.percent();
+        Ok(match series.dtype() {
+            DataType::Array(inner, _) if inner.is_struct() => {
+                let separate = self.settings.separate_standard_deviations;
+                let field = |name: &str| {
+                    value(&|list| Ok(list.struct_()?.field_by_name(name)?.f64()?.get(index)))
+                };
+                let mut text = mean(
+                    field("Mean")?,
+                    spread(series)?.try_map(field)?,
+                    precision,
+                    percent,
+                    separate,
+                );
+                if !footer && is_tested(series) {
+                    let p_value = array_value(series, row, |list| {
+                        Ok(list.struct_()?.field_by_name("PValue")?.f64()?.get(index))
+                    })?;
+                    if let Some(p_value) = p_value {
+                        text[0].push_str(significance(p_value));
+                    }
+                }
+                if is_propagated(series) {
+                    let propagated = if footer {
+                        None
+                    } else {
+                        array_value(series, row, |list| {
+                            Ok(list
+                                .struct_()?
+                                .field_by_name("Propagated")?
+                                .f64()?
+                                .get(index))
+                        })?
+                    };
+                    let propagated = float(propagated, precision, percent);
+                    if separate {
+                        text.push(propagated);
+                    } else {
+                        text[0] = format!("{} (± {propagated})", text[0]);
+                    }
                 }
+                text
+            }
+            _ => vec![float(
+                value(&|list| Ok(list.f64()?.get(index)))?,
+                precision,
+                percent,
+            )],
+        })
+    }
+
+    /// Header text and hover text
+    fn header_text(
+        &self,
+        ui: &Ui,
+        row: usize,
+        column: Range<usize>,
+    ) -> Option<(String, Option<String>)> {
+        match (row, column) {
+            (0, INDEX) => Some(("#".to_owned(), Some(ui.localize("index")))),
+            (0, _) => Some(("Compositions".to_owned(), None)),
+            (1, column) if column.start % 2 == 1 => {
+                let index = column.start / 2;
+                let composition = self.settings.confirmed.selections[index].composition;
+                Some((
+                    ui.localize(composition.text()),
+                    Some(ui.localize(composition.hover_text())),
+                ))
+            }
+            (1, column) if column.start != 0 => Some((self.value_header(), None)),
+            (2, column) if column.start % 2 == 1 => Some(("Key".to_owned(), None)),
+            (2, column) if column.start != 0 => Some((self.value_header(), None)),
+            _ => None,
+        }
+    }
+
+    /// Value header with the unit of the absolute values
+    fn value_header(&self) -> String {
+        match self.settings.confirmed.unit.abbreviation() {
+            Some(abbreviation) => format!("Value, {abbreviation}"),
+            None => "Value".to_owned(),
+        }
+    }
+
+    fn header_cell_content_ui(&mut self, ui: &mut Ui, row: usize, column: Range<usize>) {
+        if row == 0 && column == INDEX {
+            ui.heading(HASH).on_hover_ui(|ui| {
+                ui.label(ui.localize("index"));
+            });
+        } else if let Some((text, hover_text)) = self.header_text(ui, row, column) {
+            let response = ui.heading(text);
+            if let Some(hover_text) = hover_text {
+                response.on_hover_text(hover_text);
             }
-            _ => {}
         }
     }
 
@@ -142,59 +292,17 @@ impl TableView<'_> {
                 if column.start % 2 == 1 {
                     let keys = self.data_frame["Keys"].struct_()?;
                     let key = &keys.fields_as_series()[index];
-                    match self.settings.confirmed.selections[index].composition {
+                    let composition = self.settings.confirmed.selections[index].composition;
+                    match composition {
                         MNC => {
                             FloatWidget::new(|| Ok(key.f64()?.get(row)))
                                 .precision(Some(self.settings.precision))
                                 .hover()
                                 .show(ui);
                         }
-                        MSC => {
-                            let key = tag_map(round(self.settings.precision as _))(key)?;
-                            ui.label(key.str_value(row)?);
-                        }
-                        NNC | NSC => {
-                            ui.label(key.str_value(row)?);
-                        }
-                        SNC | SPC | SSC => {
-                            let sn1 = key
-                                .struct_()?
-                                .field_by_name("StereospecificNumber1")?
-                                .str_value(row)?
-                                .to_string();
-                            let sn2 = key
-                                .struct_()?
-                                .field_by_name("StereospecificNumber2")?
-                                .str_value(row)?
-                                .to_string();
-                            let sn3 = key
-                                .struct_()?
-                                .field_by_name("StereospecificNumber3")?
-                                .str_value(row)?
-                                .to_string();
-                            ui.label(format!("{{{sn1},{sn2},{sn3}}}"));
-                        }
-                        TNC | TPC | TSC => {
-                            let r#type = tag_map(r#type)(key)?;
-                            let sn1 = r#type
-                                .struct_()?
-                                .field_by_name("StereospecificNumber1")?
-                                .str_value(row)?
-                                .to_string();
-                            let sn2 = r#type
-                                .struct_()?
-                                .field_by_name("StereospecificNumber2")?
-                                .str_value(row)?
-                                .to_string();
-                            let sn3 = r#type
-                                .struct_()?
-                                .field_by_name("StereospecificNumber3")?
-                                .str_value(row)?
-                                .to_string();
-                            ui.label(format!("{{{sn1},{sn2},{sn3}}}"));
-                        }
-                        UNC | USC => {
-                            ui.label(key.str_value(row)?);
+                        _ => {
+                            let key = self::key(composition, key, self.settings.precision)?;
+                            ui.label(key_text(composition, &key, row)?);
                         }
                     }
                 } else {
@@ -203,7 +311,7 @@ impl TableView<'_> {
                         self.data_frame["Values"].as_materialized_series(),
                         Some(row),
                         index,
-                        self.settings.percent,
+                        self.settings.percent(),
                     )?;
                 }
             }
@@ -219,7 +327,7 @@ impl TableView<'_> {
                 self.data_frame["Values"].as_materialized_series(),
                 None,
                 self.settings.confirmed.selections.len() - 1,
-                self.settings.percent,
+                self.settings.percent(),
             )?;
         }
         Ok(())
@@ -263,36 +371,140 @@ impl TableView<'_> {
                 .precision(Some(self.settings.precision))
                 .hover()
                 .show(ui);
-                ui.label("±");
-                FloatWidget::new(|| {
-                    Ok(if let Some(row) = row {
+                let widget = |ui: &mut Ui, name: &str| {
+                    FloatWidget::new(|| {
+                        let f = |list: Series| {
+                            Ok(list.struct_()?.field_by_name(name)?.f64()?.get(index))
+                        };
+                        Ok(if let Some(row) = row {
+                            array_value(series, row, f)?
+                        } else {
+                            array_sum(series, f)?
+                        })
+                    })
+                    .percent(percent)
+                    .precision(Some(self.settings.precision))
+                    .hover()
+                    .show(ui);
+                };
+                let spread = spread(series)?;
+                match spread {
+                    Spread::Interval(name) => {
+                        ui.label("±").on_hover_text(ui.localize(spread.text()));
+                        widget(ui, name);
+                    }
+                    Spread::Range(minimum, maximum) => {
+                        ui.label("(").on_hover_text(ui.localize(spread.text()));
+                        widget(ui, minimum);
+                        ui.label("–");
+                        widget(ui, maximum);
+                        ui.label(")");
+                    }
+                }
+                if let Some(row) = row.filter(|_| is_propagated(series)) {
+                    ui.label("(±");
+                    FloatWidget::new(|| {
                         array_value(series, row, |list| {
                             Ok(list
                                 .struct_()?
-                                .field_by_name("StandardDeviation")?
+                                .field_by_name("Propagated")?
                                 .f64()?
                                 .get(index))
-                        })?
-                    } else {
-                        array_sum(series, |list| {
-                            Ok(list
-                                .struct_()?
-                                .field_by_name("StandardDeviation")?
-                                .f64()?
-                                .get(index))
-                        })?
+                        })
                     })
-                })
-                .percent(percent)
-                .precision(Some(self.settings.precision))
-                .hover()
-                .show(ui);
+                    .percent(percent)
+                    .precision(Some(self.settings.precision))
+                    .hover()
+                    .show(ui)
+                    .response
+                    .on_hover_text(ui.localize("propagated"));
+                    ui.label(")");
+                }
+                if let Some(row) = row.filter(|_| is_tested(series)) {
+                    self.test(ui, series, row, index, percent)?;
+                }
             }
             data_type => panic!("value not implemented for {data_type:?}"),
         })
     }
 }
 
+impl TableView<'_> {
+    /// Row is filtered out, but shown
+    fn is_filtered(&self, row: usize) -> bool {
+        self.data_frame
+            .column("Filtered")
+            .ok()
+            .and_then(|column| column.bool().ok()?.get(row))
+            .unwrap_or_default()
+    }
+
+    /// Significance marker with the p-values on hover
+    fn test(
+        &self,
+        ui: &mut Ui,
+        series: &Series,
+        row: usize,
+        index: usize,
+        percent: bool,
+    ) -> PolarsResult<()> {
+        let Some(p_value) = array_value(series, row, |list| {
+            Ok(list.struct_()?.field_by_name("PValue")?.f64()?.get(index))
+        })?
+        else {
+            return Ok(());
+        };
+        let tukey = array_value(series, row, |list| {
+            Ok(match list.struct_()?.field_by_name("Tukey") {
+                Ok(tukey) => tukey.list()?.get_as_series(index),
+                Err(_) => None,
+            })
+        })?;
+        let group = |name: &str| {
+            array_value(series, row, |list| {
+                Ok(list
+                    .struct_()?
+                    .field_by_name(name)?
+                    .list()?
+                    .get_as_series(index))
+            })
+        };
+        let means = group("GroupMeans")?;
+        let standard_deviations = group("GroupStandardDeviations")?;
+        let precision = self.settings.precision;
+        ui.label(significance(p_value)).on_hover_ui(|ui| {
+            let groups = groups(
+                &self.groups.names,
+                means.as_ref(),
+                standard_deviations.as_ref(),
+                precision,
+                percent,
+            );
+            for text in &groups {
+                ui.label(text);
+            }
+            if !groups.is_empty() {
+                ui.separator();
+            }
+            ui.label(format!("p = {p_value:.precision$}"));
+            if let Some(tukey) = &tukey {
+                ui.separator();
+                ui.label(ui.localize("tukey"));
+                let p_values = tukey.f64().into_iter().flatten();
+                for ((a, b), p_value) in self.groups.pairs().into_iter().zip(p_values) {
+                    if let Some(p_value) = p_value {
+                        ui.label(format!(
+                            "{a} - {b}: p = {p_value:.precision$}{}",
+                            significance(p_value),
+                        ));
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
 impl TableDelegate for TableView<'_> {
     fn header_cell_ui(&mut self, ui: &mut Ui, cell: &HeaderCellInfo) {
         Frame::new()
@@ -307,6 +519,10 @@ impl TableDelegate for TableView<'_> {
             ui.painter()
                 .rect_filled(ui.max_rect(), 0.0, ui.visuals().faint_bg_color);
         }
+        self.state.selection.cell(ui, cell.row_nr as _, cell.col_nr);
+        if self.is_filtered(cell.row_nr as _) {
+            ui.multiply_opacity(0.5);
+        }
         Frame::new()
             .inner_margin(Margin::from(MARGIN))
             .show(ui, |ui| {
@@ -316,7 +532,71 @@ impl TableDelegate for TableView<'_> {
     }
 }
 
-fn array_value<T>(
+/// Key series prepared for [`key_text`]
+pub(crate) fn key(
+    composition: Composition,
+    series: &Series,
+    precision: usize,
+) -> PolarsResult<Series> {
+    match composition {
+        MSC => tag_map(round(precision as _))(series),
+        TNC | TPC | TSC => tag_map(r#type)(series),
+        _ => Ok(series.clone()),
+    }
+}
+
+/// Key text
+pub(crate) fn key_text(composition: Composition, key: &Series, row: usize) -> PolarsResult<String> {
+    Ok(match composition {
+        MNC | MSC | NNC | NSC | UNC | USC => key.str_value(row)?.to_string(),
+        SNC | SPC | SSC | TNC | TPC | TSC => {
+            let r#struct = key.struct_()?;
+            let sn1 = r#struct
+                .field_by_name("StereospecificNumber1")?
+                .str_value(row)?
+                .to_string();
+            let sn2 = r#struct
+                .field_by_name("StereospecificNumber2")?
+                .str_value(row)?
+                .to_string();
+            let sn3 = r#struct
+                .field_by_name("StereospecificNumber3")?
+                .str_value(row)?
+                .to_string();
+            format!("{{{sn1},{sn2},{sn3}}}")
+        }
+    })
+}
+
+/// Dispersion field names of the values array
+pub(super) fn spread(series: &Series) -> PolarsResult<Spread<&'static str>> {
+    if let DataType::Array(inner, _) = series.dtype() {
+        if let DataType::Struct(fields) = &**inner {
+            if let Some(spread) = Spread::new(fields) {
+                return Ok(spread);
+            }
+        }
+    }
+    polars_bail!(StructFieldNotFound: "dispersion")
+}
+
+/// Values have the propagated uncertainty
+pub(super) fn is_propagated(series: &Series) -> bool {
+    has_field(series, "Propagated")
+}
+
+pub(super) fn is_tested(series: &Series) -> bool {
+    has_field(series, "PValue")
+}
+
+fn has_field(series: &Series, name: &str) -> bool {
+    matches!(series.dtype(), DataType::Array(inner, _) if matches!(
+        &**inner,
+        DataType::Struct(fields) if fields.iter().any(|field| field.name() == name),
+    ))
+}
+
+pub(super) fn array_value<T>(
     series: &Series,
     row: usize,
     f: impl Fn(Series) -> PolarsResult<Option<T>>,
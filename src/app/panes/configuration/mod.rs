@@ -1,8 +1,17 @@
-use self::{settings::Settings, state::State, table::TableView};
+use self::{
+    settings::Settings,
+    state::State,
+    table::{TableView, change_label, update_f64, update_fatty_acid},
+};
 use super::PaneDelegate;
-use crate::{app::ContextExt, utils::save};
+use crate::{
+    app::ContextExt,
+    parsers::clipboard::{self, Row},
+    utils::{polars::flatten, to_bytes, xlsx::Sheet},
+    widgets::save_as,
+};
 use anyhow::Result;
-use egui::{CursorIcon, Id, Response, RichText, Ui, Window, util::hash};
+use egui::{CursorIcon, Event, Id, Response, RichText, Ui, Window, util::hash};
 use egui_l20n::UiExt as _;
 use egui_phosphor::regular::{
     ARROWS_CLOCKWISE, ARROWS_HORIZONTAL, CALCULATOR, ERASER, FLOPPY_DISK, GEAR, LIST, NOTE_PENCIL,
@@ -10,6 +19,7 @@ use egui_phosphor::regular::{
 };
 use metadata::MetaDataFrame;
 use polars::prelude::*;
+use polars_ext::DataFrameExt as _;
 use serde::{Deserialize, Serialize};
 use std::sync::LazyLock;
 
@@ -64,6 +74,21 @@ impl Pane {
         self.frames[self.settings.index].meta.title()
     }
 
+    /// Spreadsheet sheets (one per frame)
+    pub(crate) fn sheets(&self) -> PolarsResult<Vec<Sheet>> {
+        let mut sheets = Vec::with_capacity(self.frames.len());
+        for frame in &self.frames {
+            sheets.push(Sheet {
+                name: frame.meta.title(),
+                header: Vec::new(),
+                data: flatten(&frame.data)?,
+                precision: self.settings.precision,
+                percent: None,
+            });
+        }
+        Ok(sheets)
+    }
+
     fn header_content(&mut self, ui: &mut Ui) -> Response {
         let mut response = ui
             .heading(Self::icon())
@@ -200,6 +225,34 @@ impl Pane {
         TableView::new(data_frame, &self.settings, &mut self.state).show(ui);
     }
 
+    /// Paste spreadsheet rows, rows with an existing label are overwritten
+    fn paste(&mut self, ui: &mut Ui) {
+        if !self.settings.editable
+            || !ui.ui_contains_pointer()
+            || ui.memory(|memory| memory.focused().is_some())
+        {
+            return;
+        }
+        let texts = ui.input(|input| {
+            input
+                .events
+                .iter()
+                .filter_map(|event| match event {
+                    Event::Paste(text) => Some(text.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        });
+        for text in texts {
+            for (line, row) in clipboard::rows(&text) {
+                let data_frame = &mut self.frames[self.settings.index].data;
+                if let Err(error) = row.and_then(|row| Ok(paste_row(data_frame, row)?)) {
+                    ui.ctx().error(error.context(format!("paste line {line}")));
+                }
+            }
+        }
+    }
+
     fn _add_row(&mut self) -> PolarsResult<()> {
         let data_frame = &mut self.frames[self.settings.index].data;
         *data_frame = concat(
@@ -258,7 +311,8 @@ impl Pane {
 
     fn save(&mut self) -> Result<()> {
         let name = format!("{}.utca.ipc", self.title());
-        save(&name, &mut self.frames[self.settings.index])?;
+        let bytes = to_bytes(&mut self.frames[self.settings.index])?;
+        save_as(&name, &bytes)?;
         Ok(())
     }
 }
@@ -270,6 +324,7 @@ impl PaneDelegate for Pane {
 
     fn body(&mut self, ui: &mut Ui) {
         self.windows(ui);
+        self.paste(ui);
         if self.settings.editable {
             self.body_content_meta(ui, self.settings.index);
         }
@@ -277,6 +332,32 @@ impl PaneDelegate for Pane {
     }
 }
 
+fn paste_row(data_frame: &mut DataFrame, row: Row) -> PolarsResult<()> {
+    let index = match data_frame["Label"]
+        .str()?
+        .iter()
+        .position(|label| !row.label.is_empty() && label == Some(row.label.as_str()))
+    {
+        Some(index) => index,
+        None => {
+            data_frame.add_row()?;
+            data_frame.height() - 1
+        }
+    };
+    data_frame.try_apply("Label", change_label(index, &row.label))?;
+    data_frame.try_apply("FattyAcid", update_fatty_acid(index, row.fatty_acid))?;
+    data_frame.try_apply("Triacylglycerol", update_f64(index, row.triacylglycerol))?;
+    data_frame.try_apply(
+        "Diacylglycerol1223",
+        update_f64(index, row.diacylglycerol1223),
+    )?;
+    data_frame.try_apply(
+        "Monoacylglycerol2",
+        update_f64(index, row.monoacylglycerol2),
+    )?;
+    Ok(())
+}
+
 pub(crate) mod settings;
 
 mod state;
@@ -13,6 +13,8 @@ pub(crate) struct Settings {
     pub(crate) precision: usize,
     pub(crate) sticky: usize,
     pub(crate) truncate: bool,
+    #[serde(default)]
+    pub(crate) copy_headers: bool,
 
     pub(crate) names: bool,
     pub(crate) properties: bool,
@@ -27,6 +29,7 @@ impl Settings {
             precision: 2,
             sticky: 0,
             truncate: false,
+            copy_headers: false,
             names: true,
             properties: true,
         }
@@ -42,6 +45,14 @@ impl Settings {
             });
             ui.end_row();
 
+            // Copy headers
+            let mut response = ui.label(ui.localize("settings-copy_headers"));
+            response |= ui.checkbox(&mut self.copy_headers, "");
+            response.on_hover_ui(|ui| {
+                ui.label(ui.localize("settings-copy_headers.hover"));
+            });
+            ui.end_row();
+
             ui.separator();
             ui.separator();
             ui.end_row();
@@ -1,3 +1,4 @@
+use crate::app::panes::selection::Selection;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
@@ -6,6 +7,8 @@ pub(crate) struct State {
     pub(crate) delete_table_row: Option<usize>,
     pub(crate) open_settings_window: bool,
     pub(crate) reset_table_state: bool,
+    #[serde(default)]
+    pub(crate) selection: Selection,
 }
 
 impl State {
@@ -15,6 +18,7 @@ impl State {
             delete_table_row: None,
             open_settings_window: false,
             reset_table_state: false,
+            selection: Selection::new(),
         }
     }
 }
@@ -1,6 +1,10 @@
 use super::{ContextExt as _, ID_SOURCE, Settings, State};
 use crate::app::{
-    panes::MARGIN,
+    ResultExt as _,
+    panes::{
+        MARGIN,
+        selection::{float, header, tsv},
+    },
     widgets::{FattyAcidWidget, FloatWidget, NamesWidget},
 };
 use egui::{Context, Frame, Id, Margin, Response, TextStyle, TextWrapMode, Ui};
@@ -9,7 +13,10 @@ use egui_phosphor::regular::{HASH, MINUS, PLUS};
 use egui_table::{
     AutoSizeMode, CellInfo, Column, HeaderCellInfo, HeaderRow, Table, TableDelegate, TableState,
 };
-use lipid::prelude::*;
+use lipid::{
+    fatty_acid::display::{COMMON, DisplayWithOptions as _},
+    prelude::*,
+};
 use polars::{chunked_array::builder::AnonymousOwnedListBuilder, prelude::*};
 use polars_ext::DataFrameExt as _;
 use std::ops::Range;
@@ -73,6 +80,114 @@ impl TableView<'_> {
             self.data_frame.delete_row(index).unwrap();
             self.state.delete_table_row = None;
         }
+        if self.state.selection.copy(ui) {
+            if let Some(text) = self.copy(ui).context(ui.ctx()) {
+                ui.ctx().copy_text(text);
+            }
+        }
+    }
+
+    /// Selected cells as tab separated text
+    fn copy(&self, ui: &Ui) -> PolarsResult<String> {
+        let Some((rows, columns)) = self.state.selection.ranges() else {
+            return Ok(String::new());
+        };
+        let mut headers = Vec::new();
+        if self.settings.copy_headers {
+            headers.push(header(&[], columns.clone(), |column| {
+                self.header_text(ui, column).map(|(text, _)| text)
+            }));
+        }
+        let rows = rows
+            .map(|row| {
+                columns
+                    .clone()
+                    .map(|column| self.cell_text(row, column))
+                    .collect()
+            })
+            .collect::<PolarsResult<Vec<_>>>()?;
+        Ok(tsv(&headers, &rows))
+    }
+
+    fn cell_text(&self, row: usize, column: usize) -> PolarsResult<Vec<String>> {
+        let precision = self.settings.precision;
+        let text = if row == self.data_frame.height() {
+            match column..column + 1 {
+                TAG => float(
+                    self.data_frame["Triacylglycerol"].f64()?.sum(),
+                    precision,
+                    false,
+                ),
+                DAG1223 => float(
+                    self.data_frame["Diacylglycerol1223"].f64()?.sum(),
+                    precision,
+                    false,
+                ),
+                MAG2 => float(
+                    self.data_frame["Monoacylglycerol2"].f64()?.sum(),
+                    precision,
+                    false,
+                ),
+                _ => String::new(),
+            }
+        } else {
+            match column..column + 1 {
+                INDEX => row.to_string(),
+                LABEL => self.data_frame["Label"]
+                    .str()?
+                    .get(row)
+                    .unwrap_or_default()
+                    .to_owned(),
+                FA => self
+                    .data_frame
+                    .fa()
+                    .get(row)?
+                    .map(|fatty_acid| format!("{:#}", fatty_acid.display(COMMON)))
+                    .unwrap_or_default(),
+                TAG => float(
+                    self.data_frame["Triacylglycerol"].f64()?.get(row),
+                    precision,
+                    false,
+                ),
+                DAG1223 => float(
+                    self.data_frame["Diacylglycerol1223"].f64()?.get(row),
+                    precision,
+                    false,
+                ),
+                MAG2 => float(
+                    self.data_frame["Monoacylglycerol2"].f64()?.get(row),
+                    precision,
+                    false,
+                ),
+                _ => String::new(),
+            }
+        };
+        Ok(vec![text])
+    }
+
+    /// Header text and hover text
+    fn header_text(&self, ui: &Ui, column: Range<usize>) -> Option<(String, String)> {
+        Some(match column {
+            INDEX => ("#".to_owned(), ui.localize("index")),
+            LABEL => (ui.localize("label"), ui.localize("label")),
+            FA => (
+                ui.localize("fatty_acid.abbreviation"),
+                ui.localize("fatty_acid"),
+            ),
+            TAG => (
+                ui.localize("triacylglycerol.abbreviation"),
+                ui.localize("triacylglycerol"),
+            ),
+            DAG1223 => (
+                format!("{}1223", ui.localize("diacylglycerol.abbreviation")),
+                format!("sn-1,2/2,3 {}", ui.localize("diacylglycerol")),
+            ),
+            MAG2 => (
+                format!("{}2", ui.localize("monoacylglycerol.abbreviation")),
+                format!("sn-2 {}", ui.localize("monoacylglycerol")),
+            ),
+            _ => return None,
+        })
     }
 
     fn header_cell_content_ui(&mut self, ui: &mut Ui, row: usize, column: Range<usize>) {
@@ -88,32 +203,10 @@ impl TableView<'_> {
             (0, LABEL) => {
                 ui.heading(ui.localize("label"));
             }
-            (0, FA) => {
-                ui.heading(ui.localize("fatty_acid.abbreviation"))
-                    .on_hover_ui(|ui| {
-                        ui.label(ui.localize("fatty_acid"));
-                    });
-            }
-            (0, TAG) => {
-                ui.heading(ui.localize("triacylglycerol.abbreviation"))
-                    .on_hover_ui(|ui| {
-                        ui.label(ui.localize("triacylglycerol"));
-                    });
-            }
-            (0, DAG1223) => {
-                ui.heading(format!(
-                    "{}1223",
-                    ui.localize("diacylglycerol.abbreviation"),
-                ))
-                .on_hover_ui(|ui| {
-                    ui.label(format!("sn-1,2/2,3 {}", ui.localize("diacylglycerol"),));
-                });
-            }
-            (0, MAG2) => {
-                ui.heading(format!("{}2", ui.localize("monoacylglycerol.abbreviation")))
-                    .on_hover_ui(|ui| {
-                        ui.label(format!("sn-2 {}", ui.localize("monoacylglycerol"),));
-                    });
+            (0, column) => {
+                if let Some((text, hover_text)) = self.header_text(ui, column) {
+                    ui.heading(text).on_hover_text(hover_text);
+                }
             }
             _ => {}
         };
@@ -259,6 +352,7 @@ impl TableDelegate for TableView<'_> {
             ui.painter()
                 .rect_filled(ui.max_rect(), 0.0, ui.visuals().faint_bg_color);
         }
+        self.state.selection.cell(ui, cell.row_nr as _, cell.col_nr);
         Frame::new()
             .inner_margin(Margin::from(MARGIN))
             .show(ui, |ui| {
@@ -277,7 +371,7 @@ impl TableDelegate for TableView<'_> {
 }
 
 // TODO: change existing `ChunkedArrays` rather than creating new ones
-fn update_fatty_acid(
+pub(super) fn update_fatty_acid(
     row: usize,
     value: Option<FattyAcid>,
 ) -> impl FnMut(&Series) -> PolarsResult<Series> + 'static {
@@ -353,7 +447,10 @@ fn update_fatty_acid(
     }
 }
 
-fn update_f64(row: usize, value: Option<f64>) -> impl FnMut(&Series) -> PolarsResult<Series> {
+pub(super) fn update_f64(
+    row: usize,
+    value: Option<f64>,
+) -> impl FnMut(&Series) -> PolarsResult<Series> {
     move |series| {
         Ok(series
             .f64()?
@@ -365,7 +462,7 @@ fn update_f64(row: usize, value: Option<f64>) -> impl FnMut(&Series) -> PolarsRe
     }
 }
 
-fn change_label(row: usize, new: &str) -> impl FnMut(&Series) -> PolarsResult<Series> {
+pub(super) fn change_label(row: usize, new: &str) -> impl FnMut(&Series) -> PolarsResult<Series> {
     move |series| {
         Ok(series
             .str()?
@@ -1,5 +1,7 @@
+use crate::utils::xlsx::Sheet;
 use egui::{Response, Ui, Vec2, vec2};
 use metadata::MetaDataFrame;
+use polars::prelude::*;
 use serde::{Deserialize, Serialize};
 
 const MARGIN: Vec2 = vec2(4.0, 2.0);
@@ -10,6 +12,7 @@ pub(crate) enum Pane {
     Configuration(configuration::Pane),
     Calculation(calculation::Pane),
     Composition(composition::Pane),
+    Comparison(comparison::Pane),
 }
 
 impl Pane {
@@ -17,8 +20,19 @@ impl Pane {
         Self::Calculation(calculation::Pane::new(frames, index))
     }
 
-    pub(crate) fn composition(frames: Vec<MetaDataFrame>, index: Option<usize>) -> Self {
-        Self::Composition(composition::Pane::new(frames, index))
+    pub(crate) fn composition(
+        frames: Vec<MetaDataFrame>,
+        index: Option<usize>,
+        groups: Vec<String>,
+    ) -> Self {
+        Self::Composition(composition::Pane::new(frames, index, groups))
+    }
+
+    pub(crate) fn comparison(
+        frames: Vec<MetaDataFrame>,
+        settings: composition::settings::Settings,
+    ) -> Self {
+        Self::Comparison(comparison::Pane::new(frames, settings))
     }
 
     pub(crate) const fn kind(&self) -> Kind {
@@ -26,6 +40,7 @@ impl Pane {
             Self::Configuration(_) => Kind::Configuration,
             Self::Calculation(_) => Kind::Calculation,
             Self::Composition(_) => Kind::Composition,
+            Self::Comparison(_) => Kind::Comparison,
         }
     }
 
@@ -34,6 +49,25 @@ impl Pane {
             Self::Configuration(pane) => pane.title(),
             Self::Calculation(pane) => pane.title(),
             Self::Composition(pane) => pane.title(),
+            Self::Comparison(pane) => pane.title(),
+        }
+    }
+
+    pub(crate) fn frames(&self) -> &[MetaDataFrame] {
+        match self {
+            Self::Configuration(pane) => &pane.frames,
+            Self::Calculation(pane) => &pane.source,
+            Self::Composition(pane) => &pane.source,
+            Self::Comparison(pane) => &pane.source,
+        }
+    }
+
+    pub(crate) fn sheets(&self, ui: &Ui) -> PolarsResult<Vec<Sheet>> {
+        match self {
+            Self::Configuration(pane) => pane.sheets(),
+            Self::Calculation(pane) => pane.sheets(ui),
+            Self::Composition(pane) => Ok(vec![pane.sheet(ui)?]),
+            Self::Comparison(pane) => Ok(vec![pane.sheet(ui)?]),
         }
     }
 
@@ -51,6 +85,7 @@ impl Pane {
             Self::Configuration(pane) => pane.header(ui),
             Self::Calculation(pane) => pane.header(ui),
             Self::Composition(pane) => pane.header(ui),
+            Self::Comparison(pane) => pane.header(ui),
         }
     }
 
@@ -59,6 +94,7 @@ impl Pane {
             Self::Configuration(pane) => pane.body(ui),
             Self::Calculation(pane) => pane.body(ui),
             Self::Composition(pane) => pane.body(ui),
+            Self::Comparison(pane) => pane.body(ui),
         }
     }
 }
@@ -88,9 +124,13 @@ pub(crate) enum Kind {
     Configuration,
     Calculation,
     Composition,
+    Comparison,
 }
 
 pub(crate) mod behavior;
 pub(crate) mod calculation;
+pub(crate) mod comparison;
 pub(crate) mod composition;
 pub(crate) mod configuration;
+pub(crate) mod provenance;
+pub(crate) mod selection;
@@ -0,0 +1,104 @@
+//! Saved results
+//!
+//! Calculation and composition results are saved as meta data frames. The
+//! `provenance` key of the IPC schema metadata holds the provenance: the source
+//! metadata and the settings that produced the result.
+
+use super::{Pane, calculation, composition};
+use crate::{utils::to_bytes, widgets::save_as};
+use anyhow::Result;
+use chrono::Local;
+use metadata::{MetaDataFrame, Metadata};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{io::Cursor, sync::Arc};
+
+/// Provenance schema metadata key
+const PROVENANCE: &str = "provenance";
+
+/// Result provenance
+#[derive(Clone, Deserialize, Serialize)]
+pub(crate) enum Provenance {
+    Calculation {
+        sources: Vec<Metadata>,
+        settings: calculation::settings::Settings,
+    },
+    Composition {
+        sources: Vec<Metadata>,
+        settings: composition::settings::Settings,
+    },
+}
+
+impl Provenance {
+    /// Provenance of the saved result IPC file (`None` for other frames)
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let metadata = IpcReader::new(Cursor::new(bytes))
+            .custom_metadata()
+            .ok()??;
+        ron::from_str(metadata.get(PROVENANCE)?).ok()
+    }
+
+    /// Read-only pane with the saved result
+    pub(crate) fn pane(self, data: DataFrame) -> Pane {
+        let frames = |sources: Vec<Metadata>| {
+            sources
+                .into_iter()
+                .map(|meta| MetaDataFrame::new(meta, DataFrame::empty()))
+                .collect()
+        };
+        match self {
+            Self::Calculation { sources, settings } => Pane::Calculation(
+                calculation::Pane::read_only(frames(sources), data, settings),
+            ),
+            Self::Composition { sources, settings } => Pane::Composition(
+                composition::Pane::read_only(frames(sources), data, settings),
+            ),
+        }
+    }
+
+    fn sources(&self) -> &[Metadata] {
+        match self {
+            Self::Calculation { sources, .. } | Self::Composition { sources, .. } => sources,
+        }
+    }
+}
+
+/// Saves the result with the provenance in the schema metadata
+pub(crate) fn save_result(
+    name: &str,
+    title: String,
+    provenance: &Provenance,
+    data: &DataFrame,
+) -> Result<()> {
+    let mut authors = Vec::new();
+    for author in provenance.sources().iter().flat_map(|meta| &meta.authors) {
+        if !authors.contains(author) {
+            authors.push(author.clone());
+        }
+    }
+    let mut frame = MetaDataFrame {
+        meta: Metadata {
+            version: None,
+            name: title,
+            description: "".to_owned(),
+            authors,
+            date: Some(Local::now().date_naive()),
+        },
+        data: data.clone(),
+    };
+    // Rewrite with the provenance next to the frame metadata
+    let bytes = to_bytes(&mut frame)?;
+    let mut reader = IpcReader::new(Cursor::new(&bytes));
+    let mut metadata = reader
+        .custom_metadata()?
+        .map(Arc::unwrap_or_clone)
+        .unwrap_or_default();
+    metadata.insert(PROVENANCE.into(), ron::to_string(provenance)?.into());
+    let mut data = reader.finish()?;
+    let mut bytes = Vec::new();
+    let mut writer = IpcWriter::new(&mut bytes);
+    writer.set_custom_schema_metadata(Arc::new(metadata));
+    writer.finish(&mut data)?;
+    save_as(name, &bytes)?;
+    Ok(())
+}
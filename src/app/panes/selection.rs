@@ -0,0 +1,213 @@
+use crate::app::computers::Spread;
+use egui::{Event, Ui};
+use polars::prelude::Series;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Rectangular table cell selection
+///
+/// Click selects a cell, drag or shift-click extends the selection.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct Selection {
+    anchor: Option<(usize, usize)>,
+    cursor: Option<(usize, usize)>,
+    #[serde(skip)]
+    dragging: bool,
+}
+
+impl Selection {
+    pub(crate) const fn new() -> Self {
+        Self {
+            anchor: None,
+            cursor: None,
+            dragging: false,
+        }
+    }
+
+    /// Selected rows and columns
+    pub(crate) fn ranges(&self) -> Option<(Range<usize>, Range<usize>)> {
+        let (anchor, cursor) = (self.anchor?, self.cursor?);
+        Some((
+            anchor.0.min(cursor.0)..anchor.0.max(cursor.0) + 1,
+            anchor.1.min(cursor.1)..anchor.1.max(cursor.1) + 1,
+        ))
+    }
+
+    pub(crate) fn contains(&self, row: usize, column: usize) -> bool {
+        self.ranges()
+            .is_some_and(|(rows, columns)| rows.contains(&row) && columns.contains(&column))
+    }
+
+    /// Handles the pointer over the cell and highlights the selected cell
+    pub(crate) fn cell(&mut self, ui: &Ui, row: usize, column: usize) {
+        let rect = ui.max_rect();
+        let (pressed, down, shift) = ui.input(|input| {
+            (
+                input.pointer.primary_pressed(),
+                input.pointer.primary_down(),
+                input.modifiers.shift,
+            )
+        });
+        if ui.rect_contains_pointer(rect) {
+            if pressed {
+                if !shift || self.anchor.is_none() {
+                    self.anchor = Some((row, column));
+                }
+                self.cursor = Some((row, column));
+                self.dragging = true;
+            } else if down && self.dragging {
+                self.cursor = Some((row, column));
+            }
+        }
+        if !down {
+            self.dragging = false;
+        }
+        if self.contains(row, column) {
+            ui.painter().rect_filled(
+                rect,
+                0.0,
+                ui.visuals().selection.bg_fill.gamma_multiply(0.25),
+            );
+        }
+    }
+
+    /// Checks whether the selection should be copied (`Ctrl+C` with the
+    /// pointer over the table)
+    pub(crate) fn copy(&self, ui: &Ui) -> bool {
+        self.anchor.is_some()
+            && ui.ui_contains_pointer()
+            && ui.input(|input| {
+                input
+                    .events
+                    .iter()
+                    .any(|event| matches!(event, Event::Copy))
+            })
+    }
+}
+
+/// Header row text for the selected columns
+///
+/// The group text is placed in the first selected column of the group.
+pub(crate) fn header(
+    groups: &[Range<usize>],
+    columns: Range<usize>,
+    text: impl Fn(Range<usize>) -> Option<String>,
+) -> Vec<Option<String>> {
+    columns
+        .clone()
+        .map(|column| {
+            let group = groups
+                .iter()
+                .find(|group| group.contains(&column))
+                .cloned()
+                .unwrap_or(column..column + 1);
+            if column == group.start.max(columns.start) {
+                text(group)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Tab separated text
+///
+/// A cell may span several text columns (mean and standard deviation), each
+/// table column is padded to its widest cell.
+pub(crate) fn tsv(headers: &[Vec<Option<String>>], rows: &[Vec<Vec<String>>]) -> String {
+    let mut widths = Vec::new();
+    for cells in rows {
+        widths.resize(cells.len(), 1);
+        for (width, cell) in widths.iter_mut().zip(cells) {
+            *width = cell.len().max(*width);
+        }
+    }
+    let mut lines = Vec::with_capacity(headers.len() + rows.len());
+    for header in headers {
+        let mut fields = Vec::new();
+        for (index, text) in header.iter().enumerate() {
+            fields.push(text.clone().unwrap_or_default());
+            let width = widths.get(index).copied().unwrap_or(1);
+            fields.resize(fields.len() + width - 1, String::new());
+        }
+        lines.push(fields.join("\t"));
+    }
+    for cells in rows {
+        let mut fields = Vec::new();
+        for (index, cell) in cells.iter().enumerate() {
+            let length = fields.len() + widths[index];
+            fields.extend(cell.iter().cloned());
+            fields.resize(length, String::new());
+        }
+        lines.push(fields.join("\t"));
+    }
+    lines.join("\n")
+}
+
+/// Float text with the precision and percent settings
+pub(crate) fn float(value: Option<f64>, precision: usize, percent: bool) -> String {
+    match value {
+        Some(value) if percent => format!("{:.precision$}", value * 100.0),
+        Some(value) => format!("{value:.precision$}"),
+        None => String::new(),
+    }
+}
+
+/// Mean and dispersion text, in one or two columns
+pub(crate) fn mean(
+    mean: Option<f64>,
+    dispersion: Spread<Option<f64>>,
+    precision: usize,
+    percent: bool,
+    separate: bool,
+) -> Vec<String> {
+    let mean = float(mean, precision, percent);
+    match dispersion {
+        Spread::Interval(value) => {
+            let value = float(value, precision, percent);
+            if separate {
+                vec![mean, value]
+            } else {
+                vec![format!("{mean} ± {value}")]
+            }
+        }
+        Spread::Range(minimum, maximum) => {
+            let range = format!(
+                "{}–{}",
+                float(minimum, precision, percent),
+                float(maximum, precision, percent),
+            );
+            if separate {
+                vec![mean, range]
+            } else {
+                vec![format!("{mean} ({range})")]
+            }
+        }
+    }
+}
+
+/// Group means and standard deviations text, one line per group
+pub(crate) fn groups(
+    names: &[String],
+    means: Option<&Series>,
+    standard_deviations: Option<&Series>,
+    precision: usize,
+    percent: bool,
+) -> Vec<String> {
+    let value =
+        |series: Option<&Series>, index| series.and_then(|series| series.f64().ok()?.get(index));
+    names
+        .iter()
+        .enumerate()
+        .map(|(index, name)| {
+            let mean = mean(
+                value(means, index),
+                Spread::Interval(value(standard_deviations, index)),
+                precision,
+                percent,
+                false,
+            );
+            format!("{name}: {}", mean[0])
+        })
+        .collect()
+}
@@ -0,0 +1,66 @@
+//! Project file
+//!
+//! All data frames with the checked state, the panes with their settings and
+//! the tile layout.
+//!
+//! ```text
+//! Project(version: 1, data: (frames: [...], checked: [...]), tree: (...))
+//! ```
+
+use super::{data::Data, panes::Pane};
+use anyhow::{Result, ensure};
+use egui_tiles::Tree;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+/// Project file format version
+pub(crate) const VERSION: u32 = 1;
+
+/// Project file extension (distinct from the legacy `utca.ron` configs)
+pub(crate) const EXTENSION: &str = "utca.project.ron";
+
+const PREFIX: &str = "Project(";
+
+/// Project
+#[derive(Deserialize, Serialize)]
+pub(crate) struct Project {
+    pub(crate) version: u32,
+    pub(crate) data: Data,
+    pub(crate) tree: Tree<Pane>,
+}
+
+impl Project {
+    pub(crate) fn is(text: &str) -> bool {
+        text.trim_start().starts_with(PREFIX)
+    }
+
+    pub(crate) fn from_str(text: &str) -> Result<Self> {
+        let project: Self = ron::from_str(text)?;
+        ensure!(
+            project.version <= VERSION,
+            "unsupported project version {} (expected {VERSION} or earlier)",
+            project.version,
+        );
+        Ok(project)
+    }
+}
+
+/// Project serialized without cloning the workspace
+#[derive(Serialize)]
+#[serde(rename = "Project")]
+struct Borrowed<'a> {
+    version: u32,
+    data: &'a Data,
+    tree: &'a Tree<Pane>,
+}
+
+/// Serialize the workspace to the project text
+pub(crate) fn to_string(data: &Data, tree: &Tree<Pane>) -> Result<String> {
+    let project = Borrowed {
+        version: VERSION,
+        data,
+        tree,
+    };
+    let config = PrettyConfig::new().struct_names(true).compact_arrays(true);
+    Ok(ron::ser::to_string_pretty(&project, config)?)
+}
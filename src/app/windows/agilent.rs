@@ -0,0 +1,130 @@
+use crate::{
+    app::{ContextExt as _, identifiers::DATA, widgets::FattyAcidWidget},
+    parsers::agilent::Report,
+};
+use anyhow::{Result, bail};
+use egui::{Button, Context, Grid, ScrollArea, TextEdit, Ui, Window};
+use egui_l20n::UiExt as _;
+use egui_phosphor::regular::{CHECK, FILE_TEXT, X};
+use lipid::prelude::*;
+use metadata::MetaDataFrame;
+use std::sync::mpsc::Sender;
+
+/// Agilent report window
+///
+/// Assigns labels and fatty acids to the report peaks.
+#[derive(Debug, Default)]
+pub(crate) struct AgilentWindow {
+    pub(crate) open: bool,
+    report: Report,
+    name: String,
+    labels: Vec<String>,
+    fatty_acids: Vec<Option<FattyAcid>>,
+}
+
+impl AgilentWindow {
+    pub(crate) fn load(&mut self, report: Report) {
+        self.name = report.name.clone();
+        self.labels = (0..report.peaks.len())
+            .map(|index| report.labels.get(index).cloned().unwrap_or_default())
+            .collect();
+        self.fatty_acids = vec![None; report.peaks.len()];
+        self.report = report;
+        self.open = true;
+    }
+
+    pub(crate) fn show(&mut self, ctx: &Context) {
+        let mut open = self.open;
+        Window::new(format!("{FILE_TEXT} Agilent"))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(ui.localize("name"));
+                    ui.add(TextEdit::singleline(&mut self.name).desired_width(f32::INFINITY));
+                });
+                ui.separator();
+                ScrollArea::vertical().max_height(512.0).show(ui, |ui| {
+                    self.grid(ui);
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    // Every peak needs a fatty acid
+                    let assigned = self.fatty_acids.iter().all(Option::is_some);
+                    if ui
+                        .add_enabled(
+                            assigned,
+                            Button::new(format!("{CHECK} {}", ui.localize("import"))),
+                        )
+                        .clicked()
+                    {
+                        if let Err(error) = self.import(ctx) {
+                            ctx.error(error);
+                        } else {
+                            self.open = false;
+                        }
+                    }
+                    if ui
+                        .button(format!("{X} {}", ui.localize("cancel")))
+                        .clicked()
+                    {
+                        self.open = false;
+                    }
+                });
+            });
+        self.open &= open;
+    }
+
+    fn grid(&mut self, ui: &mut Ui) {
+        Grid::new("Agilent").striped(true).show(ui, |ui| {
+            ui.heading("#");
+            ui.heading(ui.localize("label"));
+            ui.heading(ui.localize("fatty_acid"));
+            ui.heading(ui.localize("triacylglycerol"));
+            ui.heading(ui.localize("diacylglycerol"));
+            ui.heading(ui.localize("monoacylglycerol"));
+            ui.end_row();
+            for (index, peak) in self.report.peaks.iter().enumerate() {
+                ui.label(peak.index.to_string());
+                ui.add(TextEdit::singleline(&mut self.labels[index]).desired_width(64.0));
+                let fatty_acid = &mut self.fatty_acids[index];
+                let inner_response = FattyAcidWidget::new(fatty_acid.as_mut())
+                    .id_salt(("Agilent", index))
+                    .editable(true)
+                    .show(ui);
+                if inner_response.response.changed() {
+                    *fatty_acid = inner_response.inner;
+                }
+                for value in [
+                    peak.triacylglycerol,
+                    peak.diacylglycerol1223,
+                    peak.monoacylglycerol2,
+                ] {
+                    match value {
+                        Some(value) => ui.label(value.to_string()),
+                        None => ui.label("None"),
+                    };
+                }
+                ui.end_row();
+            }
+        });
+    }
+
+    fn import(&self, ctx: &Context) -> Result<()> {
+        if let Some(index) = self.fatty_acids.iter().position(Option::is_none) {
+            bail!(
+                "fatty acid is not assigned to the peak {}",
+                self.report.peaks[index].index
+            );
+        }
+        let MetaDataFrame { meta, mut data } =
+            self.report
+                .frame(&self.name, &self.labels, &self.fatty_acids)?;
+        let mut bytes = Vec::new();
+        MetaDataFrame::new(meta, &mut data).write(&mut bytes)?;
+        if let Some(sender) = ctx.data_mut(|data| data.get_temp::<Sender<(String, Vec<u8>)>>(*DATA))
+        {
+            sender.send((format!("{}.utca.ipc", self.name), bytes))?;
+        }
+        Ok(())
+    }
+}
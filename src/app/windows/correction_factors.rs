@@ -0,0 +1,248 @@
+use crate::{
+    app::{
+        ContextExt as _, identifiers::CORRECTION_FACTORS, presets::CHRISTIE,
+        widgets::FattyAcidWidget,
+    },
+    parsers::{clipboard, factors},
+    widgets::FileDialog,
+};
+use anyhow::{Result, anyhow, ensure};
+use chrono::Local;
+use egui::{ComboBox, Context, DragValue, Grid, Id, ScrollArea, TextEdit, Ui, Window};
+use egui_l20n::{ContextExt as _, UiExt as _};
+use egui_phosphor::regular::{COPY, FOLDER_OPEN, MATH_OPERATIONS, MINUS, PLUS, TRASH};
+use lipid::prelude::*;
+use metadata::{MetaDataFrame, Metadata};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{io::Cursor, str, sync::LazyLock};
+
+/// Built-in correction factors (the error is reported on the publication)
+static PRESETS: LazyLock<PolarsResult<Vec<MetaDataFrame>>> = LazyLock::new(|| {
+    let data = CHRISTIE
+        .data
+        .clone()
+        .lazy()
+        .select([col("FattyAcid"), col("Christie").alias("Factor")])
+        .collect()?;
+    Ok(vec![MetaDataFrame::new(CHRISTIE.meta.clone(), data)])
+});
+
+/// Correction factors window
+///
+/// User defined correction factor sets (response factors), selectable in the
+/// calculation settings. The built-in sets are read-only.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
+pub(crate) struct CorrectionFactorsWindow {
+    pub(crate) open: bool,
+    sets: Vec<MetaDataFrame>,
+    selected: Option<usize>,
+    #[serde(skip)]
+    file_dialog: FileDialog,
+    /// The sets are published for the calculation settings
+    #[serde(skip)]
+    published: bool,
+}
+
+impl CorrectionFactorsWindow {
+    pub(crate) fn show(&mut self, ctx: &Context) {
+        if let Some((name, bytes)) = self.file_dialog.take() {
+            match import(&name, &bytes) {
+                Ok(set) => {
+                    self.sets.push(set);
+                    self.selected = Some(self.sets.len() - 1);
+                    self.published = false;
+                }
+                Err(error) => ctx.error(error.context(name)),
+            }
+        }
+        // Publish the sets for the calculation settings (on change only)
+        if !self.published {
+            let presets = match &*PRESETS {
+                Ok(presets) => presets.as_slice(),
+                Err(error) => {
+                    ctx.error(anyhow!("built-in correction factors: {error}"));
+                    &[]
+                }
+            };
+            let sets = presets
+                .iter()
+                .chain(&self.sets)
+                .cloned()
+                .collect::<Vec<_>>();
+            ctx.data_mut(|data| data.insert_temp(*CORRECTION_FACTORS, sets));
+            self.published = true;
+        }
+        let mut open = self.open;
+        Window::new(format!(
+            "{MATH_OPERATIONS} {}",
+            ctx.localize("correction_factors"),
+        ))
+        .id(Id::new("CorrectionFactors"))
+        .open(&mut open)
+        .show(ctx, |ui| {
+            if let Err(error) = self.content(ui) {
+                ctx.error(error);
+            }
+        });
+        self.open &= open;
+    }
+
+    fn content(&mut self, ui: &mut Ui) -> Result<()> {
+        ui.horizontal(|ui| {
+            let selected_text = match self.selected {
+                Some(index) => self.sets[index].meta.name.clone(),
+                None => String::new(),
+            };
+            ComboBox::from_id_salt("CorrectionFactors")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    for (index, set) in self.sets.iter().enumerate() {
+                        ui.selectable_value(&mut self.selected, Some(index), &set.meta.name);
+                    }
+                });
+            // Create
+            if ui
+                .button(PLUS)
+                .on_hover_text(ui.localize("create"))
+                .clicked()
+            {
+                self.sets.push(MetaDataFrame::new(
+                    Metadata {
+                        version: None,
+                        name: "Untitled".to_owned(),
+                        description: "".to_owned(),
+                        authors: Vec::new(),
+                        date: Some(Local::now().date_naive()),
+                    },
+                    factors(&[], &[])?,
+                ));
+                self.selected = Some(self.sets.len() - 1);
+                self.published = false;
+            }
+            // Copy the built-in sets
+            ui.menu_button(COPY, |ui| {
+                for preset in PRESETS.iter().flatten() {
+                    if ui.button(&preset.meta.name).clicked() {
+                        self.sets.push(preset.clone());
+                        self.selected = Some(self.sets.len() - 1);
+                        self.published = false;
+                        ui.close_menu();
+                    }
+                }
+            })
+            .response
+            .on_hover_text(ui.localize("copy"));
+            // Import
+            if ui
+                .button(FOLDER_OPEN)
+                .on_hover_text(ui.localize("import"))
+                .clicked()
+            {
+                self.file_dialog.load()?;
+            }
+            // Delete
+            if let Some(index) = self.selected {
+                if ui
+                    .button(TRASH)
+                    .on_hover_text(ui.localize("delete"))
+                    .clicked()
+                {
+                    self.sets.remove(index);
+                    self.selected = None;
+                    self.published = false;
+                }
+            }
+            Ok(())
+        })
+        .inner?;
+        let Some(set) = self.selected.and_then(|index| self.sets.get_mut(index)) else {
+            return Ok(());
+        };
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(ui.localize("name"));
+            if ui
+                .add(TextEdit::singleline(&mut set.meta.name).desired_width(f32::INFINITY))
+                .changed()
+            {
+                self.published = false;
+            }
+        });
+        ui.separator();
+        let mut fatty_acids = (0..set.data.height())
+            .map(|row| set.data.fa().get(row))
+            .collect::<PolarsResult<Vec<_>>>()?;
+        let mut factors = set.data["Factor"]
+            .f64()?
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect::<Vec<_>>();
+        let mut changed = false;
+        let mut delete = None;
+        ScrollArea::vertical().max_height(512.0).show(ui, |ui| {
+            Grid::new("CorrectionFactors").striped(true).show(ui, |ui| {
+                ui.label("");
+                ui.heading(ui.localize("fatty_acid"));
+                ui.heading(ui.localize("correction_factor"));
+                ui.end_row();
+                for index in 0..factors.len() {
+                    if ui.button(MINUS).clicked() {
+                        delete = Some(index);
+                    }
+                    let fatty_acid = &mut fatty_acids[index];
+                    let inner_response = FattyAcidWidget::new(fatty_acid.as_mut())
+                        .id_salt(("CorrectionFactors", index))
+                        .editable(true)
+                        .show(ui);
+                    if inner_response.response.changed() {
+                        *fatty_acid = inner_response.inner;
+                        changed = true;
+                    }
+                    changed |= ui
+                        .add(DragValue::new(&mut factors[index]).speed(0.001))
+                        .changed();
+                    ui.end_row();
+                }
+                if ui.button(PLUS).clicked() {
+                    fatty_acids.push(Some(FattyAcid {
+                        carbons: 0,
+                        unsaturated: Vec::new(),
+                    }));
+                    factors.push(1.0);
+                    changed = true;
+                }
+                ui.end_row();
+            });
+        });
+        if let Some(index) = delete {
+            fatty_acids.remove(index);
+            factors.remove(index);
+            changed = true;
+        }
+        if changed {
+            set.data = self::factors(&fatty_acids, &factors)?;
+            self.published = false;
+        }
+        Ok(())
+    }
+}
+
+/// Imports the correction factors from IPC (`FattyAcid` and `Factor`
+/// columns) or CSV/TSV
+fn import(name: &str, bytes: &[u8]) -> Result<MetaDataFrame> {
+    if let Ok(frame) = MetaDataFrame::read(Cursor::new(bytes)) {
+        ensure!(
+            frame.data.schema().contains("FattyAcid") && frame.data.schema().contains("Factor"),
+            "expected `FattyAcid` and `Factor` columns",
+        );
+        let data = frame
+            .data
+            .lazy()
+            .select([col("FattyAcid"), col("Factor").cast(DataType::Float64)])
+            .collect()?;
+        return Ok(MetaDataFrame::new(frame.meta, data));
+    }
+    clipboard::factors(name, str::from_utf8(bytes)?)
+}
@@ -1,123 +1,219 @@
 use crate::{
-    app::identifiers::{DATA, GITHUB_TOKEN as ID},
+    app::{
+        ContextExt as _,
+        identifiers::{DATA, GITHUB_TOKEN as ID},
+    },
     utils::spawn,
 };
 use anyhow::{Error, Result};
 use base64::prelude::*;
-use egui::{
-    CollapsingHeader, Context, Grid, Id, Label, RichText, ScrollArea, Sense, TextEdit, Ui, Widget,
-    Window,
+use egui::{Context, Grid, RichText, ScrollArea, TextEdit, Ui, Window};
+use egui_l20n::UiExt as _;
+use egui_phosphor::regular::{
+    ARROWS_CLOCKWISE, CLOUD_ARROW_DOWN, CLOUD_CHECK, MAGNIFYING_GLASS, TRASH,
 };
-use egui_phosphor::regular::CLOUD_ARROW_DOWN;
-use ehttp::{Headers, Request, Response, fetch, fetch_async};
-use itertools::{Either, Itertools};
+use ehttp::{Headers, Request, fetch_async};
+use itertools::Itertools;
 use poll_promise::Promise;
-use radix_trie::{SubTrie, Trie, TrieCommon, iter::Children};
-use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use radix_trie::{Trie, TrieCommon, iter::Children};
+use serde::{Deserialize, Serialize};
 use std::{
-    env::var,
-    f32::INFINITY,
-    fmt::{Debug, Display},
-    future::Future,
-    path::{Components, Path},
-    sync::mpsc::Sender,
+    collections::HashMap,
+    sync::mpsc::{Receiver, Sender, channel},
 };
-use tracing::{error, info, trace, warn};
-use url::Url;
+use tracing::trace;
 
 // https://api.github.com/repos/ippras/utca/gh-pages/configs/H242_Tamia_Peroxide.toml
 // /repos/repos/ippras/git/trees/{tree_sha}
-// const URL: &str = "https://api.github.com/repos/ippras/utca/contents/configs";
-// const URL: &str = "https://api.github.com/repos/ippras/utca/contents/configs";
 // /repos/{owner}/{repo}/git/trees/{tree_sha}
 // https://api.github.com/repos/ippras/utca/git/trees/gh-pages?recursive=true
-// https://api.github.com/repos/ippras/utca/git/trees/gh-pages/configs?recursive=true
 
 const ENVIRONMENT: Option<&str> = option_env!("GITHUB_TOKEN");
 const URL: &str = "https://api.github.com/repos/ippras/utca-configs/git/trees/main?recursive=true";
 
 /// Github window
 ///
-/// `github.com tree` renders a nested list of debugger values.
+/// The source is a git tree JSON endpoint
+/// (`/repos/{owner}/{repo}/git/trees/{tree_sha}?recursive=true`) or a local
+/// directory laid out the same way. Loaded configs are cached and available
+/// offline.
 #[derive(Deserialize, Serialize)]
 pub struct GithubWindow {
     pub open: bool,
-    #[serde(skip, default = "promise_none")]
-    promise: Promise<Option<Tree>>,
+    #[serde(default = "url")]
+    source: String,
+    #[serde(default)]
+    search: String,
+    #[serde(default)]
+    cache: Cache,
+    /// Fetched source and tree
+    #[serde(skip)]
+    promise: Option<Promise<(String, Result<Tree>)>>,
+    #[serde(skip, default = "channel")]
+    blobs: (Sender<(Node, Vec<u8>)>, Receiver<(Node, Vec<u8>)>),
 }
 
 impl GithubWindow {
     pub fn new() -> Self {
         Self {
             open: false,
-            promise: Promise::from_ready(None),
+            source: url(),
+            search: String::new(),
+            cache: Cache::default(),
+            promise: None,
+            blobs: channel(),
         }
     }
 
     pub fn toggle(&mut self, ui: &Ui) {
         self.open ^= true;
-        self.promise = if self.open {
-            let mut github_token = ui.data_mut(|data| data.get_persisted::<String>(*ID));
-            github_token = github_token.or(ENVIRONMENT.map(ToOwned::to_owned));
-            let Some(github_token) = github_token else {
-                warn!("GITHUB_TOKEN not found");
-                return;
-            };
-            load_tree(URL, &github_token)
-        } else {
-            Promise::from_ready(None)
-        };
+        if self.open && self.cache.source != self.source {
+            self.refresh(ui.ctx());
+        }
     }
 
-    // if self.show_confirmation_dialog {
-    //     egui::Window::new("Do you want to quit?")
-    //         .collapsible(false)
-    //         .resizable(false)
-    //         .show(ctx, |ui| {
-    //             ui.horizontal(|ui| {
-    //                 if ui.button("No").clicked() {
-    //                     self.show_confirmation_dialog = false;
-    //                     self.allowed_to_close = false;
-    //                 }
-    //                 if ui.button("Yes").clicked() {
-    //                     self.show_confirmation_dialog = false;
-    //                     self.allowed_to_close = true;
-    //                     ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close);
-    //                 }
-    //             });
-    //         });
-    // }
+    /// Fetches the tree, the cached blobs are kept unless the tree SHA changes
+    fn refresh(&mut self, ctx: &Context) {
+        self.promise = Some(load_tree(self.source.clone(), token(ctx)));
+    }
 
     pub fn show(&mut self, ctx: &Context) {
+        self.update(ctx);
+        let mut open = self.open;
         Window::new(format!("{CLOUD_ARROW_DOWN} Load config"))
-            .open(&mut self.open)
+            .open(&mut open)
             .show(ctx, |ui| {
                 ui.visuals_mut().collapsing_header_frame = true;
-                // ui.collapsing(localize!("settings"), |ui| {
-                //     Grid::new(ui.next_auto_id()).show(ui, |ui| {
-                //         ui.label(localize!("github token"));
-                //         // ui.text_edit_singleline(&mut self.token)
-                //         ui.add(TextEdit::singleline(&mut self.token).desired_width(INFINITY));
-                //         ctx.request_repaint();
-                //     });
-                // });
-                // ui.separator();
+                self.header(ui);
+                ui.separator();
                 ScrollArea::vertical().show(ui, |ui| {
-                    if let Some(Some(tree)) = self.promise.ready() {
+                    if let Some(tree) = &self.cache.tree {
+                        let search = self.search.to_lowercase();
+                        let blobs = tree
+                            .tree
+                            .iter()
+                            .filter(|node| {
+                                node.r#type == "blob" && node.path.to_lowercase().contains(&search)
+                            })
+                            .collect::<Vec<_>>();
                         let mut trie = Trie::new();
                         for node in &tree.tree {
-                            // if node.r#type == "blob" {
-                            // if let Some(path) = node.path.strip_prefix("configs/") {
-                            //     trie.insert(path, &*node.url);
-                            // }
-                            trie.insert(&*node.path, &*node.url);
+                            if node.r#type == "blob" && !blobs.contains(&node) {
+                                continue;
+                            }
+                            if node.r#type == "tree" {
+                                let prefix = format!("{}/", node.path);
+                                if !blobs.iter().any(|blob| blob.path.starts_with(&prefix)) {
+                                    continue;
+                                }
+                            }
+                            trie.insert(&*node.path, node);
+                        }
+                        let mut clicked = None;
+                        show_children(ui, trie.children(), &self.cache, &mut clicked);
+                        if let Some(node) = clicked {
+                            self.load(ctx, node.clone());
                         }
-                        show_children(ui, trie.children());
-                    } else {
+                    } else if self.promise.is_some() {
                         ui.spinner();
                     }
                 });
             });
+        self.open = open;
+    }
+
+    fn header(&mut self, ui: &mut Ui) {
+        Grid::new(ui.next_auto_id()).show(ui, |ui| {
+            // Source
+            ui.label(ui.localize("github-source"))
+                .on_hover_text(ui.localize("github-source.hover"));
+            ui.horizontal(|ui| {
+                ui.add(TextEdit::singleline(&mut self.source).desired_width(f32::INFINITY));
+            });
+            ui.horizontal(|ui| {
+                if self.promise.is_some() {
+                    ui.spinner();
+                } else if ui
+                    .button(RichText::new(ARROWS_CLOCKWISE).heading())
+                    .on_hover_text(ui.localize("github-refresh"))
+                    .clicked()
+                {
+                    self.refresh(ui.ctx());
+                }
+                if ui
+                    .button(RichText::new(TRASH).heading())
+                    .on_hover_text(ui.localize("github-clear_cache"))
+                    .clicked()
+                {
+                    self.cache = Cache::default();
+                }
+            });
+            ui.end_row();
+            // Search
+            ui.label(MAGNIFYING_GLASS)
+                .on_hover_text(ui.localize("github-search"));
+            ui.add(TextEdit::singleline(&mut self.search).desired_width(f32::INFINITY));
+            ui.end_row();
+        });
+        if let Some(tree) = &self.cache.tree {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(format!("SHA: {:.7}", tree.sha)).small())
+                    .on_hover_text(tree.sha.as_str());
+                ui.label(
+                    RichText::new(format!(
+                        "{}: {}/{}",
+                        self.cache.source,
+                        self.cache.blobs.len(),
+                        tree.tree
+                            .iter()
+                            .filter(|node| node.r#type == "blob")
+                            .count(),
+                    ))
+                    .small(),
+                )
+                .on_hover_text(ui.localize("github-cached"));
+            });
+        }
+    }
+
+    /// Receives the fetched tree and blobs
+    fn update(&mut self, ctx: &Context) {
+        if let Some(promise) = self.promise.take() {
+            match promise.try_take() {
+                Ok((source, Ok(tree))) => self.cache.update(&source, tree),
+                Ok((source, Err(error))) => ctx.error(error.context(source)),
+                Err(promise) => self.promise = Some(promise),
+            }
+        }
+        for (node, bytes) in self.blobs.1.try_iter() {
+            self.cache
+                .blobs
+                .insert(node.sha.clone(), BASE64_STANDARD.encode(&bytes));
+            send(ctx, &node, bytes);
+        }
+    }
+
+    /// Loads the blob from the cache or from the source
+    fn load(&self, ctx: &Context, node: Node) {
+        if let Some(blob) = self.cache.blobs.get(&node.sha) {
+            match BASE64_STANDARD.decode(blob) {
+                Ok(bytes) => send(ctx, &node, bytes),
+                Err(error) => ctx.error(error),
+            }
+            return;
+        }
+        let token = token(ctx);
+        let ctx = ctx.clone();
+        let sender = self.blobs.0.clone();
+        let _ = spawn(async move {
+            match try_load_blob(&node, token).await {
+                Ok(bytes) => {
+                    sender.send((node, bytes)).ok();
+                    ctx.request_repaint();
+                }
+                Err(error) => ctx.error(error.context(node.path)),
+            }
+        });
     }
 }
 
@@ -127,99 +223,110 @@ impl Default for GithubWindow {
     }
 }
 
-fn promise_none() -> Promise<Option<Tree>> {
-    Promise::from_ready(None)
+fn url() -> String {
+    URL.to_owned()
+}
+
+/// Persisted token or the build time one
+fn token(ctx: &Context) -> Option<String> {
+    ctx.data_mut(|data| data.get_persisted::<String>(*ID))
+        .filter(|token| !token.is_empty())
+        .or(ENVIRONMENT.map(ToOwned::to_owned))
+}
+
+/// Sends the blob with the file name to the data channel
+fn send(ctx: &Context, node: &Node, bytes: Vec<u8>) {
+    let name = node
+        .path
+        .rsplit_once('/')
+        .map_or(&*node.path, |(_, name)| name);
+    ctx.data_mut(|data| {
+        if let Some(sender) = data.get_temp::<Sender<(String, Vec<u8>)>>(*DATA) {
+            sender.send((name.to_owned(), bytes)).ok();
+        }
+    });
 }
 
-fn show_children(ui: &mut Ui, children: Children<'_, &str, &str>) {
+fn show_children<'a>(
+    ui: &mut Ui,
+    children: Children<'_, &str, &'a Node>,
+    cache: &Cache,
+    clicked: &mut Option<&'a Node>,
+) {
     for trie in children.sorted_by_cached_key(|trie| trie.is_leaf()) {
         if let Some(&path) = trie.key() {
             let name = path.rsplit_once('/').map_or(path, |(_, suffix)| suffix);
             if trie.is_leaf() {
-                if let Some(&url) = trie.value() {
+                if let Some(&node) = trie.value() {
                     ui.horizontal(|ui| {
-                        if ui.button(CLOUD_ARROW_DOWN).on_hover_text(url).clicked() {
-                            load_blob(ui.ctx(), url);
+                        let icon = if cache.blobs.contains_key(&node.sha) {
+                            CLOUD_CHECK
+                        } else {
+                            CLOUD_ARROW_DOWN
+                        };
+                        if ui.button(icon).on_hover_text(node.url.as_str()).clicked() {
+                            *clicked = Some(node);
                         }
                         ui.label(name);
                     });
                 }
             } else {
                 ui.collapsing(RichText::new(name).heading(), |ui| {
-                    show_children(ui, trie.children());
+                    show_children(ui, trie.children(), cache, clicked);
                 });
             }
         } else {
-            show_children(ui, trie.children());
+            show_children(ui, trie.children(), cache, clicked);
         }
     }
 }
 
-fn load_tree(url: impl ToString, github_token: impl ToString) -> Promise<Option<Tree>> {
-    let url = url.to_string();
-    let github_token = github_token.to_string();
-    spawn(async {
-        match try_load_tree(url, github_token).await {
-            Ok(tree) => Some(tree),
-            Err(error) => {
-                error!(%error);
-                None
-            }
-        }
+fn load_tree(source: String, token: Option<String>) -> Promise<(String, Result<Tree>)> {
+    spawn(async move {
+        let tree = if is_remote(&source) {
+            try_load_tree(&source, token).await
+        } else {
+            local::tree(&source)
+        };
+        (source, tree)
     })
-    // let (sender, promise) = Promise::new();
-    // fetch(request, move |response| {
-    //     if let Err(error) = try_load_tree(sender, response) {
-    //         error!(%error);
-    //     }
-    //     // match response {
-    //     // Ok(response) => match response.json::<Tree>() {
-    //     //     Ok(tree) => sender.send(tree),
-    //     //     Err(error) => {
-    //     //         error!(%error);
-    //     //         info!("Status code: {}", response.status);
-    //     //         sender.send(Default::default());
-    //     //     }
-    //     // },
-    //     // Err(error) => {
-    //     //     error!(%error);
-    //     //     sender.send(Default::default());
-    //     // }
-    // });
-    // promise
 }
 
-fn load_blob(ctx: &Context, url: impl ToString) {
-    let ctx = ctx.clone();
-    let url = url.to_string();
-    let _ = spawn(async move {
-        match try_load_blob(url).await {
-            Ok(blob) => ctx.data_mut(|data| {
-                if let Some(sender) = data.get_temp::<Sender<Vec<u8>>>(*DATA) {
-                    sender.send(blob).ok();
-                }
-            }),
-            Err(error) => error!(%error),
-        }
-    });
+fn is_remote(source: &str) -> bool {
+    source.starts_with("https://") || source.starts_with("http://")
 }
 
-async fn try_load_tree(url: impl ToString, github_token: impl Display) -> Result<Tree> {
-    let request = Request {
-        headers: Headers::new(&[
-            ("Accept", "application/vnd.github+json"),
-            ("Authorization", &format!("Bearer {github_token}")),
-            ("X-GitHub-Api-Version", "2022-11-28"),
-        ]),
+/// GitHub API request with the token
+fn request(url: &str, token: Option<String>) -> Request {
+    let mut headers = vec![
+        ("Accept", "application/vnd.github+json".to_owned()),
+        ("X-GitHub-Api-Version", "2022-11-28".to_owned()),
+    ];
+    if let Some(token) = token {
+        headers.push(("Authorization", format!("Bearer {token}")));
+    }
+    let headers = headers
+        .iter()
+        .map(|(key, value)| (*key, value.as_str()))
+        .collect::<Vec<_>>();
+    Request {
+        headers: Headers::new(&headers),
         ..Request::get(url)
-    };
+    }
+}
+
+async fn try_load_tree(url: &str, token: Option<String>) -> Result<Tree> {
+    let request = request(url, token);
     let response = fetch_async(request).await.map_err(Error::msg)?;
     let tree = response.json::<Tree>()?;
     Ok(tree)
 }
 
-async fn try_load_blob(url: impl ToString) -> Result<Vec<u8>> {
-    let request = Request::get(url);
+async fn try_load_blob(node: &Node, token: Option<String>) -> Result<Vec<u8>> {
+    if !is_remote(&node.url) {
+        return local::blob(&node.url);
+    }
+    let request = request(&node.url, token);
     let response = fetch_async(request).await.map_err(Error::msg)?;
     let blob = response.json::<Blob>()?;
     trace!(?blob);
@@ -230,6 +337,110 @@ async fn try_load_blob(url: impl ToString) -> Result<Vec<u8>> {
     Ok(bytes)
 }
 
+/// Local directory source
+#[cfg(not(target_arch = "wasm32"))]
+mod local {
+    use super::{Node, Tree};
+    use anyhow::Result;
+    use egui::util::hash;
+    use itertools::Itertools as _;
+    use std::{fs, path::Path};
+
+    pub(super) fn tree(source: &str) -> Result<Tree> {
+        let root = Path::new(source);
+        let mut tree = Vec::new();
+        visit(root, root, &mut tree)?;
+        tree.sort_by(|left, right| left.path.cmp(&right.path));
+        Ok(Tree {
+            sha: format!("{:016x}", hash(&tree)),
+            url: source.to_owned(),
+            truncated: false,
+            tree,
+        })
+    }
+
+    pub(super) fn blob(path: &str) -> Result<Vec<u8>> {
+        Ok(fs::read(path)?)
+    }
+
+    fn visit(root: &Path, directory: &Path, tree: &mut Vec<Node>) -> Result<()> {
+        for entry in fs::read_dir(directory)? {
+            let path = entry?.path();
+            if path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+            {
+                continue;
+            }
+            let relative = path
+                .strip_prefix(root)?
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .join("/");
+            if path.is_dir() {
+                tree.push(Node {
+                    path: relative,
+                    mode: "040000".to_owned(),
+                    r#type: "tree".to_owned(),
+                    sha: String::new(),
+                    size: None,
+                    url: path.to_string_lossy().into_owned(),
+                });
+                visit(root, &path, tree)?;
+            } else {
+                let bytes = fs::read(&path)?;
+                tree.push(Node {
+                    path: relative,
+                    mode: "100644".to_owned(),
+                    r#type: "blob".to_owned(),
+                    sha: format!("{:016x}", hash(&bytes)),
+                    size: Some(bytes.len() as _),
+                    url: path.to_string_lossy().into_owned(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Local directory source
+#[cfg(target_arch = "wasm32")]
+mod local {
+    use super::Tree;
+    use anyhow::{Result, bail};
+
+    pub(super) fn tree(_: &str) -> Result<Tree> {
+        bail!("local directory sources are not supported in the browser")
+    }
+
+    pub(super) fn blob(_: &str) -> Result<Vec<u8>> {
+        bail!("local directory sources are not supported in the browser")
+    }
+}
+
+/// Offline cache
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+struct Cache {
+    source: String,
+    tree: Option<Tree>,
+    /// Base64 encoded blobs by SHA
+    blobs: HashMap<String, String>,
+}
+
+impl Cache {
+    /// Keeps the blobs if the tree SHA is not changed, otherwise keeps only
+    /// the blobs of the new tree
+    fn update(&mut self, source: &str, tree: Tree) {
+        if self.source == source && self.tree.as_ref().is_some_and(|old| old.sha == tree.sha) {
+            return;
+        }
+        self.blobs
+            .retain(|sha, _| tree.tree.iter().any(|node| node.sha == *sha));
+        self.source = source.to_owned();
+        self.tree = Some(tree);
+    }
+}
+
 /// Tree
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 struct Tree {
@@ -240,7 +451,7 @@ struct Tree {
 }
 
 /// Node
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Hash, PartialEq, Serialize)]
 struct Node {
     path: String,
     mode: String,
@@ -260,18 +471,3 @@ struct Blob {
     size: u64,
     node_id: String,
 }
-
-// #[derive(Clone, Debug, Deserialize, Serialize)]
-// pub struct Entry {
-//     pub name: String,
-//     pub size: usize,
-//     pub download_url: Option<Url>,
-//     pub r#type: Type,
-// }
-
-// #[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
-// #[serde(rename_all = "lowercase")]
-// pub enum Type {
-//     Dir,
-//     File,
-// }
@@ -1,5 +1,10 @@
-pub(super) use self::{about::About, github::GithubWindow, settings::SettingsWindow};
+pub(super) use self::{
+    about::About, agilent::AgilentWindow, correction_factors::CorrectionFactorsWindow,
+    github::GithubWindow, settings::SettingsWindow,
+};
 
 mod about;
+mod agilent;
+mod correction_factors;
 mod github;
 mod settings;
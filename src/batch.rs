@@ -0,0 +1,145 @@
+//! Batch processing
+//!
+//! Runs the configuration → calculation → composition pipeline without the
+//! user interface.
+
+use crate::{
+    app::{
+        computers::{calculation, composition::triacylglycerol},
+        panes::{
+            calculation::settings::Settings as CalculationSettings,
+            composition::settings::{Confirmable, Settings as CompositionSettings},
+        },
+    },
+    utils::save,
+};
+use anyhow::{Context as _, Result, bail};
+use metadata::MetaDataFrame;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{File, read_to_string},
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+/// Batch config
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    calculation: CalculationSettings,
+    composition: Confirmable,
+}
+
+impl Config {
+    /// Loads config from a `toml` or `ron` file
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+        Ok(
+            match path.extension().and_then(|extension| extension.to_str()) {
+                Some("toml") => toml::from_str(&text)?,
+                Some("ron") => ron::from_str(&text)?,
+                _ => bail!("unsupported config format: {}", path.display()),
+            },
+        )
+    }
+}
+
+/// Batch
+#[derive(Clone, Debug, Default)]
+pub struct Batch {
+    pub config: Config,
+    pub inputs: Vec<PathBuf>,
+    pub output: PathBuf,
+    pub mean: bool,
+}
+
+impl Batch {
+    pub fn run(&self) -> Result<()> {
+        let mut configurations = Vec::with_capacity(self.inputs.len());
+        for path in &self.inputs {
+            let file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+            let frame =
+                MetaDataFrame::read(file).with_context(|| format!("read {}", path.display()))?;
+            configurations.push(frame);
+        }
+        if configurations.is_empty() {
+            bail!("no input files");
+        }
+        // Calculation
+        let mut calculations = Vec::with_capacity(configurations.len());
+        for index in 0..configurations.len() {
+            let settings = CalculationSettings {
+                index: Some(index),
+                ..self.config.calculation.clone()
+            };
+            let data = calculation::Computer.try_compute(calculation::Key {
+                frames: &configurations,
+                settings: &settings,
+            })?;
+            let meta = configurations[index].meta.clone();
+            calculations.push(MetaDataFrame::new(meta, data));
+        }
+        // Output names (the repeated titles are numbered)
+        let mut names = Vec::<String>::with_capacity(calculations.len());
+        for frame in &calculations {
+            let title = frame.meta.title();
+            let mut name = title.clone();
+            let mut number = 1;
+            while name == "mean" || names.contains(&name) {
+                number += 1;
+                name = format!("{title} ({number})");
+            }
+            names.push(name);
+        }
+        // Composition
+        for index in 0..calculations.len() {
+            let mut settings = CompositionSettings::new(Some(index));
+            settings.confirmed = self.config.composition.clone();
+            let data = triacylglycerol::Computer.try_compute(triacylglycerol::Key {
+                frames: &calculations,
+                settings: &settings,
+            })?;
+            let name = &names[index];
+            self.write(
+                &format!("{name}.calculation"),
+                &mut calculations[index].clone(),
+            )?;
+            let meta = calculations[index].meta.clone();
+            self.write(
+                &format!("{name}.composition"),
+                &mut MetaDataFrame::new(meta, data),
+            )?;
+        }
+        // Mean
+        if self.mean {
+            let settings = CalculationSettings {
+                index: None,
+                ..self.config.calculation.clone()
+            };
+            let mut data = calculation::Computer.try_compute(calculation::Key {
+                frames: &configurations,
+                settings: &settings,
+            })?;
+            let meta = configurations[0].meta.clone();
+            self.write(
+                "mean.calculation",
+                &mut MetaDataFrame::new(meta, data.clone()),
+            )?;
+            let mut settings = CompositionSettings::new(None);
+            settings.confirmed = self.config.composition.clone();
+            data = triacylglycerol::Computer.try_compute(triacylglycerol::Key {
+                frames: &calculations,
+                settings: &settings,
+            })?;
+            let meta = calculations[0].meta.clone();
+            self.write("mean.composition", &mut MetaDataFrame::new(meta, data))?;
+        }
+        Ok(())
+    }
+
+    fn write(&self, name: &str, frame: &mut MetaDataFrame) -> Result<()> {
+        let path = self.output.join(format!("{name}.utca.ipc"));
+        info!("write {}", path.display());
+        save(&path.to_string_lossy(), frame)
+    }
+}
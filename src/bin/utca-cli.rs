@@ -0,0 +1,45 @@
+//! Headless UTCA
+//!
+//! `utca-cli --config settings.toml --output results --mean *.utca.ipc`
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> anyhow::Result<()> {
+    use anyhow::{Context as _, bail};
+    use std::{env::args, fs::create_dir_all, path::PathBuf};
+    use utca::batch::{Batch, Config};
+
+    const USAGE: &str = "Usage: utca-cli [--config <FILE>] [--output <DIR>] [--mean] <FILES>...";
+
+    unsafe { std::env::set_var("POLARS_FMT_MAX_COLS", "256") };
+    tracing_subscriber::fmt::init();
+
+    let mut batch = Batch {
+        output: PathBuf::from("."),
+        ..Default::default()
+    };
+    let mut args = args().skip(1);
+    while let Some(arg) = args.next() {
+        match &*arg {
+            "-c" | "--config" => {
+                let path = PathBuf::from(args.next().context(USAGE)?);
+                batch.config = Config::load(&path)?;
+            }
+            "-o" | "--output" => batch.output = PathBuf::from(args.next().context(USAGE)?),
+            "-m" | "--mean" => batch.mean = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ if arg.starts_with('-') => bail!("unknown option: {arg}\n{USAGE}"),
+            _ => batch.inputs.push(PathBuf::from(arg)),
+        }
+    }
+    if batch.inputs.is_empty() {
+        bail!(USAGE);
+    }
+    create_dir_all(&batch.output)?;
+    batch.run()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
@@ -5,8 +5,11 @@ pub use app::App;
 mod app;
 mod r#const;
 mod localization;
+mod parsers;
 mod properties;
 mod special;
 mod widgets;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod batch;
 pub mod utils;
@@ -0,0 +1,192 @@
+//! Agilent GLC `TOTAL_REPORT`
+//!
+//! ```text
+//! Sample_TAG_of_seed_Pinus_oil_Year_2023_Stage_III
+//! Date:_23-05-2023_Mole_Part_TOTAL_REPORT_AGILENT
+//! _N___GLC_Peak_Area__Total_TAGs
+//!  1          42194.  .00054
+//! _N___GLC_Peak_Area__Free_1,2-DAGs
+//!  1         208042.  .00067
+//! _N_______Selectivity___________2-TAGs
+//!  1                53.      .02893
+//! CALCULATED_TAG_COMPOSITION
+//! "Pi", "Pa", "Pn", "Gd", "St", "Ol", "Ar", "Li", "Ln"
+//! ```
+
+use anyhow::{Result, bail};
+use chrono::NaiveDate;
+use lipid::prelude::*;
+use metadata::{MetaDataFrame, Metadata};
+use polars::prelude::*;
+use std::{collections::BTreeMap, str::FromStr};
+
+const TOTAL_REPORT: &str = "TOTAL_REPORT";
+
+/// Report
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Report {
+    pub(crate) name: String,
+    pub(crate) date: Option<NaiveDate>,
+    pub(crate) labels: Vec<String>,
+    pub(crate) peaks: Vec<Peak>,
+}
+
+impl Report {
+    /// Checks whether the text looks like a report
+    pub(crate) fn is(text: &str) -> bool {
+        text.contains(TOTAL_REPORT) || text.contains("_GLC_Peak_Area_")
+    }
+
+    /// Metadata frame with the configuration schema
+    pub(crate) fn frame(
+        &self,
+        name: &str,
+        labels: &[String],
+        fatty_acids: &[Option<FattyAcid>],
+    ) -> PolarsResult<MetaDataFrame> {
+        let values = |f: fn(&Peak) -> Option<f64>| self.peaks.iter().map(f).collect::<Vec<_>>();
+        let data = super::configuration(
+            labels,
+            fatty_acids,
+            &values(|peak| peak.triacylglycerol),
+            &values(|peak| peak.diacylglycerol1223),
+            &values(|peak| peak.monoacylglycerol2),
+        )?;
+        Ok(MetaDataFrame {
+            meta: Metadata {
+                version: None,
+                name: name.to_owned(),
+                description: "".to_owned(),
+                authors: Vec::new(),
+                date: self.date,
+            },
+            data,
+        })
+    }
+}
+
+impl FromStr for Report {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let mut report = Report::default();
+        let mut peaks = BTreeMap::<u32, Peak>::new();
+        let mut block = None;
+        let mut lines = text.lines().map(str::trim);
+        while let Some(line) = lines.next() {
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix("Sample_") {
+                report.name = name.replace('_', " ");
+            } else if let Some(date) = line.strip_prefix("Date:_") {
+                let date = date.split('_').next().unwrap_or_default();
+                report.date = NaiveDate::parse_from_str(date, "%d-%m-%Y").ok();
+            } else if line.starts_with("_N_") {
+                block = Some(Block::new(line));
+            } else if line.starts_with("CALCULATED_TAG_COMPOSITION") {
+                if let Some(line) = lines.next() {
+                    report.labels = line
+                        .split(',')
+                        .map(|label| label.trim().trim_matches('"').to_owned())
+                        .collect();
+                }
+                block = None;
+            } else if let Some(block) = block {
+                let mut fields = line.split_whitespace();
+                // Area (selectivity in the 2-TAGs block) and mole part, the mole
+                // part is taken from every block
+                let (Some(index), Some(_), Some(part)) =
+                    (fields.next(), fields.next(), fields.next())
+                else {
+                    bail!("invalid {TOTAL_REPORT} line: {line:?}");
+                };
+                let index = index.parse()?;
+                let peak = peaks.entry(index).or_insert_with(|| Peak::new(index));
+                match block {
+                    Block::Triacylglycerol => peak.triacylglycerol = Some(part.parse()?),
+                    Block::Diacylglycerol1223 => peak.diacylglycerol1223 = Some(part.parse()?),
+                    Block::Monoacylglycerol2 => peak.monoacylglycerol2 = Some(part.parse()?),
+                    Block::Other => {}
+                }
+            }
+        }
+        if peaks.is_empty() {
+            bail!("{TOTAL_REPORT} peaks not found");
+        }
+        report.peaks = peaks.into_values().collect();
+        Ok(report)
+    }
+}
+
+/// Peak
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Peak {
+    pub(crate) index: u32,
+    pub(crate) triacylglycerol: Option<f64>,
+    pub(crate) diacylglycerol1223: Option<f64>,
+    pub(crate) monoacylglycerol2: Option<f64>,
+}
+
+impl Peak {
+    fn new(index: u32) -> Self {
+        Self {
+            index,
+            ..Default::default()
+        }
+    }
+}
+
+/// Block
+#[derive(Clone, Copy, Debug)]
+enum Block {
+    Triacylglycerol,
+    Diacylglycerol1223,
+    Monoacylglycerol2,
+    Other,
+}
+
+impl Block {
+    fn new(header: &str) -> Self {
+        if header.ends_with("Total_TAGs") {
+            Self::Triacylglycerol
+        } else if header.ends_with("1,2-DAGs") {
+            Self::Diacylglycerol1223
+        } else if header.ends_with("_2-TAGs") {
+            Self::Monoacylglycerol2
+        } else {
+            Self::Other
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<()> {
+        let text = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/doc/test/PIN341.TXT"));
+        assert!(Report::is(text));
+        let report = text.parse::<Report>()?;
+        assert_eq!(report.name, "TAG of seed Pinus oil Year 2023 Stage III");
+        assert_eq!(report.date, NaiveDate::from_ymd_opt(2023, 5, 23));
+        assert_eq!(
+            report.labels,
+            ["Pi", "Pa", "Pn", "Gd", "St", "Ol", "Ar", "Li", "Ln"],
+        );
+        assert_eq!(report.peaks.len(), 9);
+        // Mole parts of each block
+        let peak = report.peaks[0];
+        assert_eq!(peak.index, 1);
+        assert_eq!(peak.triacylglycerol, Some(0.00054));
+        assert_eq!(peak.diacylglycerol1223, Some(0.00067));
+        assert_eq!(peak.monoacylglycerol2, Some(0.02893));
+        let peak = report.peaks[8];
+        assert_eq!(peak.index, 9);
+        assert_eq!(peak.triacylglycerol, Some(0.00007));
+        assert_eq!(peak.diacylglycerol1223, Some(0.00004));
+        assert_eq!(peak.monoacylglycerol2, Some(0.0));
+        Ok(())
+    }
+}
@@ -0,0 +1,323 @@
+//! Clipboard (spreadsheet) rows
+//!
+//! ```text
+//! Label	Fatty acid	TAG	DAG1223	MAG2
+//! P	16:0	10,5	12,1	2,3
+//! L	18:2Δ9c,12c	30,2	29,8	45,1
+//! ```
+
+use anyhow::{Context as _, Result, bail, ensure};
+use lipid::prelude::*;
+use metadata::{MetaDataFrame, Metadata};
+
+/// Clipboard row
+#[derive(Clone, Debug)]
+pub(crate) struct Row {
+    pub(crate) label: String,
+    pub(crate) fatty_acid: Option<FattyAcid>,
+    pub(crate) triacylglycerol: Option<f64>,
+    pub(crate) diacylglycerol1223: Option<f64>,
+    pub(crate) monoacylglycerol2: Option<f64>,
+}
+
+/// Parse tab, semicolon or comma separated rows
+///
+/// Each item is the line number and the parsed row. A leading header line is
+/// skipped.
+pub(crate) fn rows(text: &str) -> Vec<(usize, Result<Row>)> {
+    let separator = separator(text);
+    let mut rows = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row = row(line, separator);
+        if index == 0 && row.is_err() && is_header(line, separator) {
+            continue;
+        }
+        rows.push((index + 1, row));
+    }
+    rows
+}
+
+/// Parse CSV/TSV table to the configuration frame
+pub(crate) fn frame(name: &str, text: &str) -> Result<MetaDataFrame> {
+    let rows = rows(text);
+    ensure!(!rows.is_empty(), "no rows");
+    let mut labels = Vec::with_capacity(rows.len());
+    let mut fatty_acids = Vec::with_capacity(rows.len());
+    let mut triacylglycerol = Vec::with_capacity(rows.len());
+    let mut diacylglycerol1223 = Vec::with_capacity(rows.len());
+    let mut monoacylglycerol2 = Vec::with_capacity(rows.len());
+    for (line, row) in rows {
+        let row = row.with_context(|| format!("line {line}"))?;
+        labels.push(row.label);
+        fatty_acids.push(row.fatty_acid);
+        triacylglycerol.push(row.triacylglycerol);
+        diacylglycerol1223.push(row.diacylglycerol1223);
+        monoacylglycerol2.push(row.monoacylglycerol2);
+    }
+    let data = super::configuration(
+        &labels,
+        &fatty_acids,
+        &triacylglycerol,
+        &diacylglycerol1223,
+        &monoacylglycerol2,
+    )?;
+    let name = name.split_once('.').map_or(name, |(stem, _)| stem);
+    Ok(MetaDataFrame {
+        meta: Metadata {
+            version: None,
+            name: name.to_owned(),
+            description: "".to_owned(),
+            authors: Vec::new(),
+            date: None,
+        },
+        data,
+    })
+}
+
+/// Parse CSV/TSV table to the correction factors frame
+///
+/// ```text
+/// Fatty acid	Factor
+/// 16:0	1,02
+/// 18:2Δ9c,12c	0,97
+/// ```
+pub(crate) fn factors(name: &str, text: &str) -> Result<MetaDataFrame> {
+    let separator = separator(text);
+    let mut fatty_acids = Vec::new();
+    let mut factors = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some((fatty_acid, factor)) = line.rsplit_once(separator) else {
+            bail!("line {}: expected 2 fields", index + 1);
+        };
+        let factor = match float(factor.trim(), separator) {
+            Ok(factor) => factor,
+            Err(_) if index == 0 => continue,
+            Err(error) => {
+                return Err(
+                    error.context(format!("line {}: invalid factor: {factor:?}", index + 1))
+                );
+            }
+        };
+        fatty_acids.push(Some(self::fatty_acid(fatty_acid).with_context(|| {
+            format!("line {}: invalid fatty acid: {fatty_acid:?}", index + 1)
+        })?));
+        factors.push(factor);
+    }
+    ensure!(!factors.is_empty(), "no rows");
+    let name = name.split_once('.').map_or(name, |(stem, _)| stem);
+    Ok(MetaDataFrame {
+        meta: Metadata {
+            version: None,
+            name: name.to_owned(),
+            description: "".to_owned(),
+            authors: Vec::new(),
+            date: None,
+        },
+        data: super::factors(&fatty_acids, &factors)?,
+    })
+}
+
+/// Parse CSV/TSV key and value rows (a measured profile)
+///
+/// ```text
+/// Species	Value
+/// {P,O,P}	12,5
+/// {P,O,O}	30,1
+/// ```
+pub(crate) fn profile(text: &str) -> Result<Vec<(String, f64)>> {
+    let separator = separator(text);
+    let mut profile = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some((key, value)) = line.rsplit_once(separator) else {
+            bail!("line {}: expected 2 fields", index + 1);
+        };
+        let value = match float(value.trim(), separator) {
+            Ok(value) => value,
+            Err(_) if index == 0 => continue,
+            Err(error) => {
+                return Err(error.context(format!("line {}: invalid value: {value:?}", index + 1)));
+            }
+        };
+        profile.push((key.trim().to_owned(), value));
+    }
+    Ok(profile)
+}
+
+fn row(line: &str, separator: char) -> Result<Row> {
+    let fields = line.split(separator).map(str::trim).collect::<Vec<_>>();
+    ensure!(
+        (2..=5).contains(&fields.len()),
+        "expected 2..=5 fields, found {}",
+        fields.len(),
+    );
+    let value = |index: usize| -> Result<Option<f64>> {
+        match fields.get(index) {
+            Some(&field) if !field.is_empty() => Ok(Some(
+                float(field, separator).with_context(|| format!("invalid value: {field:?}"))?,
+            )),
+            _ => Ok(None),
+        }
+    };
+    let fatty_acid = match fields[1] {
+        "" => None,
+        field => Some(fatty_acid(field).with_context(|| format!("invalid fatty acid: {field:?}"))?),
+    };
+    Ok(Row {
+        label: fields[0].to_owned(),
+        fatty_acid,
+        triacylglycerol: value(2)?,
+        diacylglycerol1223: value(3)?,
+        monoacylglycerol2: value(4)?,
+    })
+}
+
+/// Field separator: tab, else semicolon, else comma
+fn separator(text: &str) -> char {
+    if text.contains('\t') {
+        '\t'
+    } else if text.contains(';') {
+        ';'
+    } else {
+        ','
+    }
+}
+
+/// Header line has no numbers in the value fields
+fn is_header(line: &str, separator: char) -> bool {
+    line.split(separator)
+        .skip(2)
+        .all(|field| float(field.trim(), separator).is_err())
+}
+
+/// Parse float, comma decimal separator is allowed unless it separates fields
+fn float(text: &str, separator: char) -> Result<f64> {
+    Ok(if separator == ',' {
+        text.parse()?
+    } else {
+        text.replace(',', ".").parse()?
+    })
+}
+
+/// Parse fatty acid shorthand (`18:2`, `C18:2Δ9c,12c`, `18:2(9Z,12Z)`)
+///
+/// Bound positions are unknown if not specified (or specified in the `n-x`
+/// notation).
+pub(crate) fn fatty_acid(text: &str) -> Result<FattyAcid> {
+    let text = text.trim();
+    let text = text.strip_prefix(['C', 'c']).unwrap_or(text);
+    let Some((carbons, text)) = text.split_once(':') else {
+        bail!("expected `carbons:unsaturation`");
+    };
+    let carbons = carbons.trim().parse()?;
+    let digits = text
+        .find(|char: char| !char.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (unsaturation, text) = text.split_at(digits);
+    let unsaturation = unsaturation.parse::<usize>()?;
+    let text = text.trim();
+    let mut unsaturated = Vec::with_capacity(unsaturation);
+    if !text.starts_with(['n', 'ω']) {
+        let bounds = text
+            .trim_start_matches(['Δ', '-', '('])
+            .trim_end_matches(')');
+        for bound in bounds.split([',', ' ']).filter(|bound| !bound.is_empty()) {
+            let digits = bound
+                .find(|char: char| !char.is_ascii_digit())
+                .unwrap_or(bound.len());
+            let (index, isomerism) = bound.split_at(digits);
+            unsaturated.push(Unsaturated {
+                index: Some(index.parse()?),
+                isomerism: match isomerism {
+                    "" => None,
+                    "c" | "Z" => Some(Isomerism::Cis),
+                    "t" | "E" => Some(Isomerism::Trans),
+                    _ => bail!("unexpected isomerism: {isomerism:?}"),
+                },
+                unsaturation: Some(Unsaturation::One),
+            });
+        }
+    }
+    if unsaturated.is_empty() {
+        unsaturated = vec![
+            Unsaturated {
+                index: None,
+                isomerism: None,
+                unsaturation: Some(Unsaturation::One),
+            };
+            unsaturation
+        ];
+    }
+    ensure!(
+        unsaturated.len() == unsaturation,
+        "expected {unsaturation} bounds, found {}",
+        unsaturated.len(),
+    );
+    Ok(FattyAcid {
+        carbons,
+        unsaturated,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<()> {
+        // Tab separated with a header and comma decimal separators
+        let rows = rows(
+            "Label\tFatty acid\tTAG\tDAG1223\tMAG2\n\
+             P\t16:0\t10,5\t12,1\t2,3\n\
+             L\t18:2Δ9c,12c\t30,2\t29,8\t45,1\n",
+        );
+        assert_eq!(rows.len(), 2);
+        let (line, row) = &rows[0];
+        assert_eq!(*line, 2);
+        let row = row.as_ref().unwrap();
+        assert_eq!(row.label, "P");
+        assert_eq!(row.triacylglycerol, Some(10.5));
+        assert_eq!(row.diacylglycerol1223, Some(12.1));
+        assert_eq!(row.monoacylglycerol2, Some(2.3));
+        let (line, row) = &rows[1];
+        assert_eq!(*line, 3);
+        let fatty_acid = row.as_ref().unwrap().fatty_acid.as_ref().unwrap();
+        assert_eq!(fatty_acid.carbons, 18);
+        assert_eq!(fatty_acid.unsaturated.len(), 2);
+        assert_eq!(fatty_acid.unsaturated[1].index, Some(12));
+        assert!(matches!(
+            fatty_acid.unsaturated[1].isomerism,
+            Some(Isomerism::Cis),
+        ));
+        // Semicolon separated without a header, missing values and an invalid
+        // row
+        let rows = self::rows("P;16:0;10,5\n\nX;abc;1\nO;;1;2\n");
+        assert_eq!(rows.len(), 3);
+        let row = rows[0].1.as_ref().unwrap();
+        assert_eq!(row.triacylglycerol, Some(10.5));
+        assert_eq!(row.diacylglycerol1223, None);
+        assert_eq!(row.monoacylglycerol2, None);
+        assert_eq!(rows[1].0, 3);
+        assert!(rows[1].1.is_err());
+        let row = rows[2].1.as_ref().unwrap();
+        assert!(row.fatty_acid.is_none());
+        assert_eq!(row.diacylglycerol1223, Some(2.0));
+        // Comma separated with dot decimal separators
+        let rows = self::rows("P,16:0,10.5,12.1,2.3\n");
+        assert_eq!(rows.len(), 1);
+        let row = rows[0].1.as_ref().unwrap();
+        assert_eq!(row.fatty_acid.as_ref().unwrap().carbons, 16);
+        assert_eq!(row.monoacylglycerol2, Some(2.3));
+        // Too many fields
+        assert!(self::rows("P\t16:0\t1\t2\t3\t4\n")[0].1.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,349 @@
+//! Legacy `utca.toml` and `utca.ron` configs
+
+use anyhow::{Context as _, Result, bail};
+use lipid::prelude::*;
+use metadata::{MetaDataFrame, Metadata};
+use serde::Deserialize;
+use std::{collections::HashMap, sync::LazyLock};
+
+const UNTITLED: &str = "Untitled";
+
+/// Fatty acid name table (abbreviation to identifier)
+static NAMES: LazyLock<HashMap<&str, &str>> = LazyLock::new(|| {
+    let source = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/ftl/en/fatty_acids/byrdwell.com.ftl"
+    ));
+    let mut names = HashMap::new();
+    let mut id = None;
+    for line in source.lines() {
+        if let Some(abbreviation) = line.trim().strip_prefix(".abbreviation = ") {
+            if let Some(id) = id {
+                names.entry(abbreviation.trim()).or_insert(id);
+            }
+        } else if let Some(identifier) = line.strip_suffix(" =") {
+            id = Some(identifier.trim());
+        }
+    }
+    names
+});
+
+/// Checks whether the text looks like a legacy `toml` config
+pub(crate) fn is_toml(text: &str) -> bool {
+    text.contains("[[fatty_acids]]")
+}
+
+/// Checks whether the text looks like a legacy `ron` config
+pub(crate) fn is_ron(text: &str) -> bool {
+    text.trim_start().starts_with('(') && text.contains("columns")
+}
+
+/// Parse legacy `toml` config
+pub(crate) fn toml(text: &str) -> Result<MetaDataFrame> {
+    let config: TomlConfig = toml::from_str(text)?;
+    let mut labels = Vec::with_capacity(config.fatty_acids.len());
+    let mut fatty_acids = Vec::with_capacity(config.fatty_acids.len());
+    let mut triacylglycerol = Vec::with_capacity(config.fatty_acids.len());
+    let mut diacylglycerol1223 = Vec::with_capacity(config.fatty_acids.len());
+    let mut monoacylglycerol2 = Vec::with_capacity(config.fatty_acids.len());
+    for fatty_acid in config.fatty_acids {
+        let (carbons, unsaturation) = formula(&fatty_acid.formula)
+            .with_context(|| format!("invalid formula: {}", fatty_acid.formula))?;
+        fatty_acids.push(Some(self::fatty_acid(
+            &fatty_acid.label,
+            carbons,
+            unsaturation,
+        )));
+        labels.push(fatty_acid.label);
+        triacylglycerol.push(fatty_acid.data.tag123);
+        diacylglycerol1223.push(fatty_acid.data.dag1223);
+        monoacylglycerol2.push(fatty_acid.data.mag2);
+    }
+    let data = super::configuration(
+        &labels,
+        &fatty_acids,
+        &triacylglycerol,
+        &diacylglycerol1223,
+        &monoacylglycerol2,
+    )?;
+    Ok(frame(config.name.as_deref().unwrap_or(UNTITLED), data))
+}
+
+/// Parse legacy `ron` config (polars data frame)
+pub(crate) fn ron(text: &str) -> Result<MetaDataFrame> {
+    let config: RonConfig = ron::from_str(text)?;
+    let column = |name| {
+        config
+            .columns
+            .iter()
+            .find(|column| column.name() == Some(name))
+            .with_context(|| format!("column not found: {name}"))
+    };
+    let labels = column("Label")?
+        .values::<String>()?
+        .into_iter()
+        .map(Option::unwrap_or_default)
+        .collect::<Vec<_>>();
+    let carbons = column("Carbons")?.values::<u8>()?;
+    let doubles = column("Doubles")?.values::<RonList>()?;
+    let triples = match column("Triples") {
+        Ok(column) => column.values::<RonList>()?,
+        Err(_) => vec![None; labels.len()],
+    };
+    let mut fatty_acids = Vec::with_capacity(labels.len());
+    for index in 0..labels.len() {
+        let Some(carbons) = carbons[index] else {
+            fatty_acids.push(None);
+            continue;
+        };
+        let mut unsaturated = Vec::new();
+        for (list, unsaturation) in [
+            (&doubles[index], Unsaturation::One),
+            (&triples[index], Unsaturation::Two),
+        ] {
+            for &value in list.iter().flat_map(|list| &list.values).flatten() {
+                unsaturated.push(Unsaturated {
+                    index: (value != 0).then_some(value.unsigned_abs()),
+                    isomerism: match value.signum() {
+                        1 => Some(Isomerism::Cis),
+                        -1 => Some(Isomerism::Trans),
+                        _ => None,
+                    },
+                    unsaturation: Some(unsaturation),
+                });
+            }
+        }
+        let mut fatty_acid = FattyAcid {
+            carbons,
+            unsaturated,
+        };
+        if fatty_acid
+            .unsaturated
+            .iter()
+            .all(|unsaturated| unsaturated.index.is_none())
+        {
+            fatty_acid =
+                self::fatty_acid(&labels[index], carbons, fatty_acid.unsaturated.len() as _);
+        }
+        fatty_acids.push(Some(fatty_acid));
+    }
+    let data = super::configuration(
+        &labels,
+        &fatty_acids,
+        &column("TAG")?.values::<f64>()?,
+        &column("DAG1223")?.values::<f64>()?,
+        &column("MAG2")?.values::<f64>()?,
+    )?;
+    Ok(frame(UNTITLED, data))
+}
+
+fn frame(name: &str, data: polars::prelude::DataFrame) -> MetaDataFrame {
+    MetaDataFrame {
+        meta: Metadata {
+            version: None,
+            name: name.to_owned(),
+            description: "".to_owned(),
+            authors: Vec::new(),
+            date: None,
+        },
+        data,
+    }
+}
+
+/// Fatty acid with unknown bound positions, filled from the name table if the
+/// label matches
+fn fatty_acid(label: &str, carbons: u8, unsaturation: u8) -> FattyAcid {
+    if let Some(fatty_acid) = NAMES.get(label).and_then(|id| identifier(id)) {
+        if fatty_acid.carbons == carbons && fatty_acid.unsaturated.len() == unsaturation as usize {
+            return fatty_acid;
+        }
+    }
+    FattyAcid {
+        carbons,
+        unsaturated: vec![
+            Unsaturated {
+                index: None,
+                isomerism: None,
+                unsaturation: Some(Unsaturation::One),
+            };
+            unsaturation as _
+        ],
+    }
+}
+
+/// Parse fatty acid identifier (`c18u2c9c12`)
+fn identifier(id: &str) -> Option<FattyAcid> {
+    let id = id.strip_prefix('c')?;
+    let (carbons, id) = id.split_once('u')?;
+    let carbons = carbons.parse().ok()?;
+    let digits = id.find(|c: char| !c.is_ascii_digit()).unwrap_or(id.len());
+    let (unsaturation, mut id) = id.split_at(digits);
+    let unsaturation = unsaturation.parse::<usize>().ok()?;
+    let mut unsaturated = Vec::with_capacity(unsaturation);
+    while let Some(isomerism) = id.chars().next() {
+        let isomerism = match isomerism {
+            'c' => Isomerism::Cis,
+            't' => Isomerism::Trans,
+            _ => return None,
+        };
+        id = &id[1..];
+        let digits = id.find(|c: char| !c.is_ascii_digit()).unwrap_or(id.len());
+        let (index, rest) = id.split_at(digits);
+        unsaturated.push(Unsaturated {
+            index: Some(index.parse().ok()?),
+            isomerism: Some(isomerism),
+            unsaturation: Some(Unsaturation::One),
+        });
+        id = rest;
+    }
+    if unsaturated.len() != unsaturation {
+        return None;
+    }
+    Some(FattyAcid {
+        carbons,
+        unsaturated,
+    })
+}
+
+/// Parse formula (`H32C18O2`) into carbons and unsaturation
+fn formula(formula: &str) -> Result<(u8, u8)> {
+    let mut carbons = 0u32;
+    let mut hydrogens = 0u32;
+    let mut chars = formula.chars().peekable();
+    while let Some(element) = chars.next() {
+        let mut count = String::new();
+        while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+            count.push(digit);
+        }
+        let count = if count.is_empty() { 1 } else { count.parse()? };
+        match element {
+            'C' => carbons += count,
+            'H' => hydrogens += count,
+            'O' => {}
+            _ => bail!("unexpected element: {element}"),
+        }
+    }
+    let Some(unsaturation) = (2 * carbons).checked_sub(hydrogens) else {
+        bail!("too many hydrogens");
+    };
+    if unsaturation % 2 != 0 {
+        bail!("odd number of hydrogens");
+    }
+    Ok((carbons.try_into()?, (unsaturation / 2).try_into()?))
+}
+
+/// Legacy `toml` config
+#[derive(Deserialize)]
+struct TomlConfig {
+    name: Option<String>,
+    fatty_acids: Vec<TomlFattyAcid>,
+}
+
+/// Legacy `toml` fatty acid
+#[derive(Deserialize)]
+struct TomlFattyAcid {
+    label: String,
+    formula: String,
+    data: TomlData,
+}
+
+/// Legacy `toml` data
+#[derive(Deserialize)]
+struct TomlData {
+    tag123: Option<f64>,
+    dag1223: Option<f64>,
+    mag2: Option<f64>,
+}
+
+/// Legacy `ron` config
+#[derive(Deserialize)]
+struct RonConfig {
+    columns: Vec<RonColumn>,
+}
+
+/// Legacy `ron` column (`{ "name": …, "datatype": …, "values": […] }`)
+#[derive(Deserialize)]
+#[serde(transparent)]
+struct RonColumn(HashMap<String, ron::Value>);
+
+impl RonColumn {
+    fn name(&self) -> Option<&str> {
+        match self.0.get("name")? {
+            ron::Value::String(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn values<T: for<'de> Deserialize<'de>>(&self) -> Result<Vec<Option<T>>> {
+        let name = self.name().unwrap_or_default();
+        let values = self
+            .0
+            .get("values")
+            .with_context(|| format!("column {name}: values not found"))?;
+        values
+            .clone()
+            .into_rust()
+            .with_context(|| format!("column {name}"))
+    }
+}
+
+/// Legacy `ron` list
+#[derive(Clone, Deserialize)]
+struct RonList {
+    values: Vec<Option<i8>>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<()> {
+        assert_eq!(formula("H32C18O2")?, (18, 2));
+        assert_eq!(formula("H36C18O2")?, (18, 0));
+        let linoleic = fatty_acid("L", 18, 2);
+        assert_eq!(linoleic.unsaturated[0].index, Some(9));
+        assert_eq!(linoleic.unsaturated[1].index, Some(12));
+        // Stearidonic acid in the name table, stearic acid in the config
+        let stearic = fatty_acid("St", 18, 0);
+        assert!(stearic.unsaturated.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn configs() -> Result<()> {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/doc/test/PinaceaeCedrus.utca.toml"
+        ));
+        assert!(is_toml(text));
+        let toml = toml(text)?;
+        assert_eq!(toml.meta.name, "Pinaceae Cedrus");
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/doc/test/PinaceaeCedrus.utca.ron"
+        ));
+        assert!(is_ron(text));
+        let ron = ron(text)?;
+        assert_eq!(ron.meta.name, UNTITLED);
+        // The same fatty acids in a different order
+        assert_eq!(toml.data.height(), 9);
+        assert_eq!(ron.data.height(), 9);
+        let values = |frame: &MetaDataFrame, label: &str| -> Result<[Option<f64>; 3]> {
+            let index = frame.data["Label"]
+                .str()?
+                .into_iter()
+                .position(|other| other == Some(label))
+                .with_context(|| format!("label not found: {label}"))?;
+            Ok(
+                ["Triacylglycerol", "Diacylglycerol1223", "Monoacylglycerol2"]
+                    .map(|name| frame.data[name].f64().ok()?.get(index)),
+            )
+        };
+        for label in ["Pi", "Pa", "Pn", "Gd", "St", "Ol", "Ar", "Li", "Ln"] {
+            assert_eq!(values(&toml, label)?, values(&ron, label)?, "{label}");
+        }
+        assert_eq!(values(&ron, "Pi")?[0], Some(42194071.111));
+        Ok(())
+    }
+}
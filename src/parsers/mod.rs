@@ -0,0 +1,36 @@
+use crate::utils::polars::fatty_acids;
+use lipid::prelude::*;
+use polars::prelude::*;
+
+/// Configuration data frame
+///
+/// Builds a data frame with the configuration schema.
+pub(crate) fn configuration(
+    labels: &[String],
+    fatty_acid: &[Option<FattyAcid>],
+    triacylglycerol: &[Option<f64>],
+    diacylglycerol1223: &[Option<f64>],
+    monoacylglycerol2: &[Option<f64>],
+) -> PolarsResult<DataFrame> {
+    let length = labels.len() as u32;
+    DataFrame::new(vec![
+        Series::new("Index".into(), (0..length).collect::<Vec<_>>()).into_column(),
+        Series::new("Label".into(), labels).into_column(),
+        fatty_acids("FattyAcid".into(), fatty_acid)?.into_column(),
+        Series::new("Triacylglycerol".into(), triacylglycerol).into_column(),
+        Series::new("Diacylglycerol1223".into(), diacylglycerol1223).into_column(),
+        Series::new("Monoacylglycerol2".into(), monoacylglycerol2).into_column(),
+    ])
+}
+
+/// Correction factors data frame
+pub(crate) fn factors(fatty_acid: &[Option<FattyAcid>], factor: &[f64]) -> PolarsResult<DataFrame> {
+    DataFrame::new(vec![
+        fatty_acids("FattyAcid".into(), fatty_acid)?.into_column(),
+        Series::new("Factor".into(), factor).into_column(),
+    ])
+}
+
+pub(crate) mod agilent;
+pub(crate) mod clipboard;
+pub(crate) mod legacy;
@@ -0,0 +1,209 @@
+//! Inverse problem of the 1,3-random-2-random distribution
+//!
+//! Estimates the sn-1,3 and sn-2 fatty acid distributions from the measured
+//! species by the least squares on the simplex (projected gradient descent).
+
+use super::composition::{Composition, SNC, SPC, SSC};
+use std::collections::HashMap;
+
+const ITERATIONS: usize = 5000;
+const TOLERANCE: f64 = 1e-15;
+
+/// Species key kind
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Kind {
+    /// `{a,b,c}` = `{c,b,a}`
+    Positional,
+    /// `{a,b,c}` = any permutation
+    NonStereospecific,
+    Stereospecific,
+}
+
+impl Kind {
+    pub fn new(composition: Composition) -> Option<Self> {
+        match composition {
+            SPC => Some(Self::Positional),
+            SNC => Some(Self::NonStereospecific),
+            SSC => Some(Self::Stereospecific),
+            _ => None,
+        }
+    }
+
+    /// Canonical key of the species
+    pub fn key(self, [a, b, c]: [usize; 3]) -> [usize; 3] {
+        match self {
+            Self::Positional => [a.min(c), b, a.max(c)],
+            Self::NonStereospecific => {
+                let mut key = [a, b, c];
+                key.sort();
+                key
+            }
+            Self::Stereospecific => [a, b, c],
+        }
+    }
+}
+
+/// Estimated distributions
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Solution {
+    pub stereospecific_numbers13: Vec<f64>,
+    pub stereospecific_numbers2: Vec<f64>,
+    /// Canonical key, measured and fitted fractions
+    pub species: Vec<([usize; 3], f64, f64)>,
+    pub root_mean_square: f64,
+}
+
+/// Solves the inverse problem for `count` fatty acids
+///
+/// Measured values are normalized. The species missing in the measurement
+/// are zeros.
+pub fn solve(count: usize, measured: &[([usize; 3], f64)], kind: Kind) -> Solution {
+    if count == 0 {
+        return Solution::default();
+    }
+    // Species index of each fatty acid triple
+    let mut keys = Vec::new();
+    let mut indices = HashMap::new();
+    let mut triples = Vec::with_capacity(count.pow(3));
+    for i in 0..count {
+        for j in 0..count {
+            for k in 0..count {
+                let key = kind.key([i, j, k]);
+                let index = *indices.entry(key).or_insert_with(|| {
+                    keys.push(key);
+                    keys.len() - 1
+                });
+                triples.push(([i, j, k], index));
+            }
+        }
+    }
+    let mut target = vec![0.0; keys.len()];
+    for (key, value) in measured {
+        if let Some(&index) = indices.get(&kind.key(*key)) {
+            target[index] += value;
+        }
+    }
+    let total = target.iter().sum::<f64>();
+    if total > 0.0 {
+        target.iter_mut().for_each(|value| *value /= total);
+    }
+    let fitted = |x13: &[f64], x2: &[f64]| {
+        let mut fitted = vec![0.0; keys.len()];
+        for &([i, j, k], index) in &triples {
+            fitted[index] += x13[i] * x2[j] * x13[k];
+        }
+        fitted
+    };
+    let loss = |x13: &[f64], x2: &[f64]| {
+        fitted(x13, x2)
+            .iter()
+            .zip(&target)
+            .map(|(fitted, target)| (fitted - target).powi(2))
+            .sum::<f64>()
+    };
+    let mut x13 = vec![1.0 / count as f64; count];
+    let mut x2 = vec![1.0 / count as f64; count];
+    let mut value = loss(&x13, &x2);
+    let mut step = 1.0;
+    for _ in 0..ITERATIONS {
+        // Gradient
+        let residuals = fitted(&x13, &x2)
+            .iter()
+            .zip(&target)
+            .map(|(fitted, target)| 2.0 * (fitted - target))
+            .collect::<Vec<_>>();
+        let mut g13 = vec![0.0; count];
+        let mut g2 = vec![0.0; count];
+        for &([i, j, k], index) in &triples {
+            let residual = residuals[index];
+            g13[i] += residual * x2[j] * x13[k];
+            g13[k] += residual * x13[i] * x2[j];
+            g2[j] += residual * x13[i] * x13[k];
+        }
+        // Backtracking line search
+        let (y13, y2, next) = loop {
+            let y13 = project(x13.iter().zip(&g13).map(|(x, g)| x - step * g).collect());
+            let y2 = project(x2.iter().zip(&g2).map(|(x, g)| x - step * g).collect());
+            let next = loss(&y13, &y2);
+            let distance = x13
+                .iter()
+                .zip(&y13)
+                .chain(x2.iter().zip(&y2))
+                .map(|(x, y)| (x - y).powi(2))
+                .sum::<f64>();
+            if next <= value - distance / (2.0 * step) || step < 1e-12 {
+                break (y13, y2, next);
+            }
+            step /= 2.0;
+        };
+        let improvement = value - next;
+        x13 = y13;
+        x2 = y2;
+        value = next;
+        step = (step * 2.0).min(1e3);
+        if improvement.abs() < TOLERANCE {
+            break;
+        }
+    }
+    let fitted = fitted(&x13, &x2);
+    let species = keys
+        .into_iter()
+        .zip(target)
+        .zip(fitted)
+        .filter(|((_, target), fitted)| *target > 0.0 || *fitted > 0.0)
+        .map(|((key, target), fitted)| (key, target, fitted))
+        .collect::<Vec<_>>();
+    let root_mean_square = (value / species.len().max(1) as f64).sqrt();
+    Solution {
+        stereospecific_numbers13: x13,
+        stereospecific_numbers2: x2,
+        species,
+        root_mean_square,
+    }
+}
+
+/// Euclidean projection onto the probability simplex
+fn project(mut values: Vec<f64>) -> Vec<f64> {
+    let mut sorted = values.clone();
+    sorted.sort_by(|a, b| b.total_cmp(a));
+    let mut sum = 0.0;
+    let mut theta = 0.0;
+    for (index, value) in sorted.iter().enumerate() {
+        sum += value;
+        let candidate = (sum - 1.0) / (index + 1) as f64;
+        if value - candidate > 0.0 {
+            theta = candidate;
+        }
+    }
+    values
+        .iter_mut()
+        .for_each(|value| *value = (*value - theta).max(0.0));
+    values
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tests() {
+        let x13 = [0.6, 0.3, 0.1];
+        let x2 = [0.1, 0.7, 0.2];
+        let mut measured = Vec::new();
+        for i in 0..3 {
+            for j in 0..3 {
+                for k in 0..3 {
+                    measured.push(([i, j, k], x13[i] * x2[j] * x13[k]));
+                }
+            }
+        }
+        let solution = solve(3, &measured, Kind::Positional);
+        for (estimated, expected) in solution.stereospecific_numbers13.iter().zip(x13) {
+            assert!((estimated - expected).abs() < 1e-3);
+        }
+        for (estimated, expected) in solution.stereospecific_numbers2.iter().zip(x2) {
+            assert!((estimated - expected).abs() < 1e-3);
+        }
+        assert!(solution.root_mean_square < 1e-4);
+    }
+}
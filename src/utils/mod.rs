@@ -1,8 +1,13 @@
-pub use self::{save::save, spawn::spawn};
+pub use self::{
+    save::{save, save_bytes, to_bytes},
+    spawn::spawn,
+};
 
 pub mod egui;
 pub mod polars;
+pub mod statistics;
 pub mod ui;
+pub mod xlsx;
 
 mod save;
 mod spawn;
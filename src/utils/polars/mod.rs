@@ -1,4 +1,8 @@
-use polars::prelude::*;
+use lipid::{
+    fatty_acid::display::{COMMON, DisplayWithOptions as _},
+    prelude::*,
+};
+use polars::{chunked_array::builder::AnonymousOwnedListBuilder, prelude::*};
 
 /// Convert type `Boolean` to `String`
 pub fn r#type(series: &Series) -> PolarsResult<Series> {
@@ -32,6 +36,144 @@ pub fn tag_map(
     }
 }
 
+/// Flatten struct columns into `Parent.Child` columns
+///
+/// `FattyAcid` columns are converted to the common notation, arrays are split
+/// into `Parent.Index` columns, lists are joined into strings.
+pub fn flatten(data_frame: &DataFrame) -> PolarsResult<DataFrame> {
+    fn flatten(prefix: &str, series: &Series, columns: &mut Vec<Column>) -> PolarsResult<()> {
+        let name = if prefix.is_empty() {
+            series.name().to_string()
+        } else {
+            format!("{prefix}.{}", series.name())
+        };
+        match series.dtype() {
+            DataType::Struct(_) if series.name() == "FattyAcid" => {
+                let fatty_acids = series.fa();
+                let mut strings = StringChunkedBuilder::new(name.into(), series.len());
+                for index in 0..series.len() {
+                    let fatty_acid = fatty_acids.get(index)?;
+                    strings.append_option(
+                        fatty_acid.map(|fatty_acid| format!("{:#}", fatty_acid.display(COMMON))),
+                    );
+                }
+                columns.push(strings.finish().into_column());
+            }
+            DataType::Struct(_) => {
+                for field in series.struct_()?.fields_as_series() {
+                    flatten(&name, &field, columns)?;
+                }
+            }
+            DataType::Array(_, width) => {
+                let elements = DataFrame::new(vec![series.clone().into_column()])?
+                    .lazy()
+                    .select(
+                        (0..*width)
+                            .map(|index| {
+                                col(series.name().clone())
+                                    .arr()
+                                    .get(lit(index as u32), false)
+                                    .alias(index.to_string())
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                    .collect()?;
+                for element in elements.get_columns() {
+                    flatten(&name, element.as_materialized_series(), columns)?;
+                }
+            }
+            DataType::List(_) => {
+                let mut strings = StringChunkedBuilder::new(name.into(), series.len());
+                for list in series.list()? {
+                    strings.append_option(list.map(|list| {
+                        list.iter()
+                            .map(|value| value.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    }));
+                }
+                columns.push(strings.finish().into_column());
+            }
+            _ => columns.push(series.clone().with_name(name.into()).into_column()),
+        }
+        Ok(())
+    }
+
+    let mut columns = Vec::new();
+    for column in data_frame.get_columns() {
+        flatten("", column.as_materialized_series(), &mut columns)?;
+    }
+    DataFrame::new(columns)
+}
+
+/// Build `FattyAcid` series
+pub fn fatty_acids(name: PlSmallStr, fatty_acids: &[Option<FattyAcid>]) -> PolarsResult<Series> {
+    let mut carbons =
+        PrimitiveChunkedBuilder::<UInt8Type>::new("Carbons".into(), fatty_acids.len());
+    let mut unsaturated = AnonymousOwnedListBuilder::new(
+        "Unsaturated".into(),
+        fatty_acids.len(),
+        Some(DataType::Struct(vec![
+            Field::new("Index".into(), DataType::UInt8),
+            Field::new("Isomerism".into(), DataType::Int8),
+            Field::new("Unsaturation".into(), DataType::UInt8),
+        ])),
+    );
+    for fatty_acid in fatty_acids {
+        // Carbons
+        carbons.append_option(fatty_acid.as_ref().map(|fatty_acid| fatty_acid.carbons));
+        // Unsaturated
+        if let Some(fatty_acid) = fatty_acid {
+            let mut index = PrimitiveChunkedBuilder::<UInt8Type>::new(
+                "Index".into(),
+                fatty_acid.unsaturated.len(),
+            );
+            let mut isomerism = PrimitiveChunkedBuilder::<Int8Type>::new(
+                "Isomerism".into(),
+                fatty_acid.unsaturated.len(),
+            );
+            let mut unsaturation = PrimitiveChunkedBuilder::<UInt8Type>::new(
+                "Unsaturation".into(),
+                fatty_acid.unsaturated.len(),
+            );
+            for unsaturated in &fatty_acid.unsaturated {
+                index.append_option(unsaturated.index);
+                isomerism.append_option(unsaturated.isomerism.map(|isomerism| isomerism as _));
+                unsaturation.append_option(
+                    unsaturated
+                        .unsaturation
+                        .map(|unsaturation| unsaturation as _),
+                );
+            }
+            unsaturated.append_series(
+                &StructChunked::from_series(
+                    PlSmallStr::EMPTY,
+                    fatty_acid.unsaturated.len(),
+                    [
+                        index.finish().into_series(),
+                        isomerism.finish().into_series(),
+                        unsaturation.finish().into_series(),
+                    ]
+                    .iter(),
+                )?
+                .into_series(),
+            )?;
+        } else {
+            unsaturated.append_opt_series(None)?;
+        }
+    }
+    Ok(StructChunked::from_series(
+        name,
+        fatty_acids.len(),
+        [
+            carbons.finish().into_series(),
+            unsaturated.finish().into_series(),
+        ]
+        .iter(),
+    )?
+    .into_series())
+}
+
 pub mod expr;
 pub mod schema;
 
@@ -71,3 +213,91 @@ pub mod schema;
 // pub fn round(decimals: u32) -> impl Fn(&Series) -> PolarsResult<Series> {
 //     move |series| series.round(decimals)
 // }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() -> PolarsResult<()> {
+        let stearic = FattyAcid {
+            carbons: 18,
+            unsaturated: Vec::new(),
+        };
+        let values = StructChunked::from_series(
+            "Values".into(),
+            2,
+            [
+                Series::new("Mean".into(), [0.25, 0.75]),
+                Series::new("StandardDeviation".into(), [0.01, 0.02]),
+            ]
+            .iter(),
+        )?
+        .into_series();
+        let data_frame = DataFrame::new(vec![
+            Series::new("Index".into(), [0u32, 1]).into_column(),
+            fatty_acids("FattyAcid".into(), &[Some(stearic.clone()), None])?.into_column(),
+            values.into_column(),
+            Series::new(
+                "Repetitions".into(),
+                [
+                    Series::new(PlSmallStr::EMPTY, [1i32, 2]),
+                    Series::new(PlSmallStr::EMPTY, [3i32]),
+                ],
+            )
+            .into_column(),
+        ])?;
+        let data_frame = flatten(&data_frame)?;
+        assert_eq!(
+            data_frame.get_column_names_str(),
+            [
+                "Index",
+                "FattyAcid",
+                "Values.Mean",
+                "Values.StandardDeviation",
+                "Repetitions",
+            ],
+        );
+        // Fatty acids in the common notation
+        let fatty_acid = data_frame["FattyAcid"].str()?;
+        assert_eq!(
+            fatty_acid.get(0),
+            Some(&*format!("{:#}", stearic.display(COMMON))),
+        );
+        assert_eq!(fatty_acid.get(1), None);
+        assert_eq!(data_frame["Values.Mean"].f64()?.get(1), Some(0.75));
+        // Lists joined into strings
+        let repetitions = data_frame["Repetitions"].str()?;
+        assert_eq!(repetitions.get(0), Some("1, 2"));
+        assert_eq!(repetitions.get(1), Some("3"));
+        // Arrays (of the composition values) split by the index
+        let value = |index: usize| {
+            as_struct(vec![
+                col(format!("Mean{index}")).alias("Mean"),
+                col(format!("StandardDeviation{index}")).alias("StandardDeviation"),
+            ])
+        };
+        let data_frame = df! {
+            "Mean0" => [0.1, 0.3],
+            "StandardDeviation0" => [0.01, 0.03],
+            "Mean1" => [0.2, 0.4],
+            "StandardDeviation1" => [0.02, 0.04],
+        }?
+        .lazy()
+        .select([concat_arr(vec![value(0), value(1)])?.alias("Values")])
+        .collect()?;
+        let data_frame = flatten(&data_frame)?;
+        assert_eq!(
+            data_frame.get_column_names_str(),
+            [
+                "Values.0.Mean",
+                "Values.0.StandardDeviation",
+                "Values.1.Mean",
+                "Values.1.StandardDeviation",
+            ],
+        );
+        assert_eq!(data_frame["Values.1.Mean"].f64()?.get(0), Some(0.2));
+        assert_eq!(data_frame["Values.0.Mean"].f64()?.get(1), Some(0.3));
+        Ok(())
+    }
+}
@@ -11,10 +11,30 @@ pub fn save(name: &str, frame: &mut MetaDataFrame) -> Result<()> {
 
 #[cfg(target_arch = "wasm32")]
 pub fn save(name: &str, frame: &mut MetaDataFrame) -> Result<()> {
-    use anyhow::anyhow;
-    use egui_ext::download;
+    save_bytes(name, &to_bytes(frame)?)
+}
 
+/// Meta data frame IPC bytes
+pub fn to_bytes(frame: &mut MetaDataFrame) -> Result<Vec<u8>> {
     let mut bytes = Vec::new();
     MetaDataFrame::new(frame.meta.clone(), &mut frame.data).write(&mut bytes)?;
-    download(name, &bytes).map_err(|error| anyhow!(error))
+    Ok(bytes)
+}
+
+/// Save bytes to a file
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_bytes(name: &str, bytes: &[u8]) -> Result<()> {
+    use std::io::Write as _;
+
+    File::create(name)?.write_all(bytes)?;
+    Ok(())
+}
+
+/// Download bytes as a file
+#[cfg(target_arch = "wasm32")]
+pub fn save_bytes(name: &str, bytes: &[u8]) -> Result<()> {
+    use anyhow::anyhow;
+    use egui_ext::download;
+
+    download(name, bytes).map_err(|error| anyhow!(error))
 }
@@ -0,0 +1,457 @@
+//! Statistics of the samples
+//!
+//! Tests between the groups of samples (Welch, ANOVA, Tukey HSD), quantiles of
+//! the Student's t distribution, robust z-scores of the replicates and the
+//! goodness of fit of the distribution models.
+
+use std::f64::consts::PI;
+
+/// One-way analysis of variance
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Anova {
+    pub f: f64,
+    pub p_value: f64,
+}
+
+/// One-way ANOVA
+///
+/// Empty groups are ignored. Returns `None` if there are less than two groups
+/// or no within group variance.
+pub fn anova(groups: &[&[f64]]) -> Option<Anova> {
+    let groups = groups
+        .iter()
+        .filter(|group| !group.is_empty())
+        .collect::<Vec<_>>();
+    let k = groups.len();
+    let n = groups.iter().map(|group| group.len()).sum::<usize>();
+    if k < 2 || n <= k {
+        return None;
+    }
+    let grand_mean = groups.iter().flat_map(|group| group.iter()).sum::<f64>() / n as f64;
+    let mut between = 0.0;
+    let mut within = 0.0;
+    for group in groups {
+        let mean = mean(group);
+        between += group.len() as f64 * (mean - grand_mean).powi(2);
+        within += group.iter().map(|x| (x - mean).powi(2)).sum::<f64>();
+    }
+    let df_between = (k - 1) as f64;
+    let df_within = (n - k) as f64;
+    if within == 0.0 {
+        return None;
+    }
+    let f = (between / df_between) / (within / df_within);
+    Some(Anova {
+        f,
+        p_value: fisher_sf(f, df_between, df_within),
+    })
+}
+
+/// Tukey HSD (Tukey-Kramer for the unequal group sizes)
+///
+/// Returns the p-values of the pairwise comparisons in the order `(0, 1),
+/// (0, 2), …, (1, 2), …`. A pair with an empty group is `None`.
+pub fn tukey(groups: &[&[f64]]) -> Option<Vec<Option<f64>>> {
+    let k = groups.iter().filter(|group| !group.is_empty()).count();
+    let n = groups.iter().map(|group| group.len()).sum::<usize>();
+    if k < 2 || n <= k {
+        return None;
+    }
+    let df = (n - k) as f64;
+    let within = groups
+        .iter()
+        .filter(|group| !group.is_empty())
+        .map(|group| {
+            let mean = mean(group);
+            group.iter().map(|x| (x - mean).powi(2)).sum::<f64>()
+        })
+        .sum::<f64>()
+        / df;
+    if within == 0.0 {
+        return None;
+    }
+    let mut p_values = Vec::new();
+    for i in 0..groups.len() {
+        for j in i + 1..groups.len() {
+            let (a, b) = (groups[i], groups[j]);
+            if a.is_empty() || b.is_empty() {
+                p_values.push(None);
+                continue;
+            }
+            let standard_error =
+                (within / 2.0 * (1.0 / a.len() as f64 + 1.0 / b.len() as f64)).sqrt();
+            let q = (mean(a) - mean(b)).abs() / standard_error;
+            p_values.push(Some((1.0 - range_cdf(q, k as f64, df)).clamp(0.0, 1.0)));
+        }
+    }
+    Some(p_values)
+}
+
+/// Two-sided p-value of the Welch t-test
+///
+/// Returns `None` if a group has less than two values or both groups have no
+/// variance.
+pub fn welch(a: &[f64], b: &[f64]) -> Option<f64> {
+    if a.len() < 2 || b.len() < 2 {
+        return None;
+    }
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let v1 = variance(a) / n1;
+    let v2 = variance(b) / n2;
+    let v = v1 + v2;
+    if v == 0.0 {
+        return None;
+    }
+    let t = (mean(a) - mean(b)) / v.sqrt();
+    let df = v.powi(2) / (v1.powi(2) / (n1 - 1.0) + v2.powi(2) / (n2 - 1.0));
+    Some(beta_regularized(df / (df + t.powi(2)), df / 2.0, 0.5))
+}
+
+/// Quantile of the Student's t distribution
+pub fn t_quantile(probability: f64, df: f64) -> f64 {
+    if probability < 0.5 {
+        return -t_quantile(1.0 - probability, df);
+    }
+    let cdf = |t: f64| 1.0 - 0.5 * beta_regularized(df / (df + t * t), df / 2.0, 0.5);
+    let mut to = 1.0;
+    while cdf(to) < probability && to < 1e6 {
+        to *= 2.0;
+    }
+    let mut from = 0.0;
+    for _ in 0..100 {
+        let middle = (from + to) / 2.0;
+        if cdf(middle) < probability {
+            from = middle;
+        } else {
+            to = middle;
+        }
+    }
+    (from + to) / 2.0
+}
+
+/// Robust (modified) z-scores on the median and the median absolute deviation
+///
+/// `M = 0.6745 * (x - median) / MAD` (Iglewicz and Hoaglin). Missing values
+/// keep their places. Returns `None` for all values if there are less than
+/// three values or no deviation.
+pub fn robust_z_scores(values: &[Option<f64>]) -> Vec<Option<f64>> {
+    let present = values.iter().flatten().copied().collect::<Vec<_>>();
+    if present.len() < 3 {
+        return vec![None; values.len()];
+    }
+    let center = median(present.clone());
+    let deviation = median(present.iter().map(|x| (x - center).abs()).collect());
+    if deviation == 0.0 {
+        return vec![None; values.len()];
+    }
+    values
+        .iter()
+        .map(|value| value.map(|x| 0.6745 * (x - center) / deviation))
+        .collect()
+}
+
+/// Goodness of fit of the model to the measurement
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fit {
+    /// Infinite if a key without the expected value is observed
+    pub chi_square: f64,
+    pub root_mean_square: f64,
+    /// `1 - ∑|o - e| / 2`
+    pub similarity: f64,
+    /// Observed fraction of the keys without the expected value
+    pub unexpected: f64,
+}
+
+/// Goodness of fit of the expected (model) values to the observed ones
+///
+/// Both are fractions of the same keys. The keys with zero expected and
+/// observed values are skipped by the chi-square. The root mean square of no
+/// keys is zero.
+pub fn fit(observed: &[f64], expected: &[f64]) -> Fit {
+    let mut chi_square = 0.0;
+    let mut square = 0.0;
+    let mut absolute = 0.0;
+    let mut unexpected = 0.0;
+    for (o, e) in observed.iter().zip(expected) {
+        if *e > 0.0 {
+            chi_square += (o - e).powi(2) / e;
+        } else if *o > 0.0 {
+            chi_square = f64::INFINITY;
+            unexpected += o;
+        }
+        square += (o - e).powi(2);
+        absolute += (o - e).abs();
+    }
+    Fit {
+        chi_square,
+        root_mean_square: if observed.is_empty() {
+            0.0
+        } else {
+            (square / observed.len() as f64).sqrt()
+        },
+        similarity: 1.0 - absolute / 2.0,
+        unexpected,
+    }
+}
+
+/// Significance marker (`***` p < 0.001, `**` p < 0.01, `*` p < 0.05)
+pub fn significance(p_value: f64) -> &'static str {
+    if p_value < 0.001 {
+        "***"
+    } else if p_value < 0.01 {
+        "**"
+    } else if p_value < 0.05 {
+        "*"
+    } else {
+        ""
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
+
+/// Sample variance
+fn variance(values: &[f64]) -> f64 {
+    let mean = mean(values);
+    values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64
+}
+
+/// Survival function of the F distribution
+fn fisher_sf(f: f64, df1: f64, df2: f64) -> f64 {
+    beta_regularized(df2 / (df2 + df1 * f), df2 / 2.0, df1 / 2.0)
+}
+
+/// Cumulative distribution function of the studentized range
+///
+/// `P(Q < q) = ∫ W(q * s) f(s) ds`, where `W` - distribution of the range of
+/// `k` standard normal values, `f` - density of `s = √(χ²(ν) / ν)`.
+fn range_cdf(q: f64, k: f64, df: f64) -> f64 {
+    if q <= 0.0 {
+        return 0.0;
+    }
+    if df > 25000.0 {
+        return normal_range_cdf(q, k);
+    }
+    let sigma = (2.0 * df).sqrt().recip();
+    let from = (1.0 - 8.0 * sigma).max(0.0);
+    let to = 1.0 + 8.0 * sigma;
+    let ln_constant = df / 2.0 * df.ln() - ln_gamma(df / 2.0) - (df / 2.0 - 1.0) * 2f64.ln();
+    simpson(from, to, 100, |s| {
+        if s <= 0.0 {
+            return 0.0;
+        }
+        let density = (ln_constant + (df - 1.0) * s.ln() - df * s * s / 2.0).exp();
+        normal_range_cdf(q * s, k) * density
+    })
+    .min(1.0)
+}
+
+/// Cumulative distribution function of the range of `k` standard normal
+/// values
+///
+/// `W(w) = k ∫ φ(z) (Φ(z) - Φ(z - w))^(k - 1) dz`
+fn normal_range_cdf(w: f64, k: f64) -> f64 {
+    k * simpson(-8.0, 8.0, 200, |z| {
+        let difference = normal_cdf(z) - normal_cdf(z - w);
+        normal_pdf(z) * difference.max(0.0).powf(k - 1.0)
+    })
+}
+
+fn simpson(from: f64, to: f64, intervals: usize, f: impl Fn(f64) -> f64) -> f64 {
+    let step = (to - from) / intervals as f64;
+    let mut sum = f(from) + f(to);
+    for index in 1..intervals {
+        let weight = if index % 2 == 1 { 4.0 } else { 2.0 };
+        sum += weight * f(from + index as f64 * step);
+    }
+    sum * step / 3.0
+}
+
+fn normal_pdf(x: f64) -> f64 {
+    (-x * x / 2.0).exp() / (2.0 * PI).sqrt()
+}
+
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / 2f64.sqrt())
+}
+
+/// Complementary error function (fractional error < 1.2e-7)
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let r = t
+        * (-z * z - 1.26551223
+            + t * (1.00002368
+                + t * (0.37409196
+                    + t * (0.09678418
+                        + t * (-0.18628806
+                            + t * (0.27886807
+                                + t * (-1.13520398
+                                    + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277)))))))))
+            .exp();
+    if x >= 0.0 { r } else { 2.0 - r }
+}
+
+/// Natural logarithm of the gamma function (Lanczos approximation)
+fn ln_gamma(x: f64) -> f64 {
+    const G: f64 = 7.0;
+    const C: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + G + 0.5;
+    let mut a = C[0];
+    for (index, c) in C.iter().enumerate().skip(1) {
+        a += c / (x + index as f64);
+    }
+    0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + a.ln()
+}
+
+/// Regularized incomplete beta function `I_x(a, b)`
+fn beta_regularized(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - front * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+/// Continued fraction of the incomplete beta function (modified Lentz's
+/// method)
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const EPSILON: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+    let tiny = |value: f64| if value.abs() < TINY { TINY } else { value };
+    let mut c = 1.0;
+    let mut d = 1.0 / tiny(1.0 - (a + b) * x / (a + 1.0));
+    let mut h = d;
+    for m in 1..=300 {
+        let m = m as f64;
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 / tiny(1.0 + even * d);
+        c = tiny(1.0 + even / c);
+        h *= d * c;
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 / tiny(1.0 + odd * d);
+        c = tiny(1.0 + odd / c);
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    h
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn close(left: f64, right: f64, epsilon: f64) -> bool {
+        (left - right).abs() < epsilon
+    }
+
+    const A: [f64; 5] = [1.0, 2.0, 3.0, 4.0, 5.0];
+    const B: [f64; 5] = [2.0, 4.0, 6.0, 8.0, 10.0];
+    const C: [f64; 5] = [7.0, 8.0, 9.0, 10.0, 11.0];
+
+    #[test]
+    fn welch() {
+        // t.test(1:5, c(2, 4, 6, 8, 10))
+        assert!(close(super::welch(&A, &B).unwrap(), 0.1075, 1e-3));
+        assert_eq!(super::welch(&A, &[1.0]), None);
+        assert_eq!(super::welch(&[1.0, 1.0], &[2.0, 2.0]), None);
+    }
+
+    #[test]
+    fn anova() {
+        // qf(0.95, 2, 10) = 4.102821
+        assert!(close(fisher_sf(4.102821, 2.0, 10.0), 0.05, 1e-5));
+        let anova = super::anova(&[&A, &B, &C]).unwrap();
+        assert!(close(anova.f, 9.0, 1e-9));
+        assert_eq!(super::anova(&[&A, &[]]), None);
+    }
+
+    #[test]
+    fn tukey() {
+        // qtukey(0.95, 3, 10) = 3.877676
+        assert!(close(range_cdf(3.877676, 3.0, 10.0), 0.95, 1e-4));
+        // qtukey(0.95, 2, Inf) = 2.771808
+        assert!(close(range_cdf(2.771808, 2.0, f64::INFINITY), 0.95, 1e-4));
+        let p_values = super::tukey(&[&A, &B, &C]).unwrap();
+        assert_eq!(p_values.len(), 3);
+        assert!(p_values[0].unwrap() > p_values[1].unwrap());
+        let p_values = super::tukey(&[&A, &[], &C]).unwrap();
+        assert_eq!(p_values[0], None);
+        assert!(p_values[1].is_some());
+    }
+
+    #[test]
+    fn t_quantile() {
+        // qt(0.975, 4) = 2.776445
+        assert!(close(super::t_quantile(0.975, 4.0), 2.776445, 1e-5));
+        assert!(close(super::t_quantile(0.025, 4.0), -2.776445, 1e-5));
+    }
+
+    #[test]
+    fn robust_z_scores() {
+        let z_scores = super::robust_z_scores(&[Some(1.0), Some(2.0), None, Some(3.0), Some(10.0)]);
+        assert_eq!(z_scores[2], None);
+        // median = 2.5, MAD = 1
+        assert!(close(z_scores[4].unwrap(), 0.6745 * 7.5, 1e-9));
+        assert_eq!(
+            super::robust_z_scores(&[Some(1.0), Some(2.0)]),
+            [None, None]
+        );
+    }
+
+    #[test]
+    fn fit() {
+        let fit = super::fit(&[0.5, 0.5], &[0.25, 0.75]);
+        assert!(close(fit.similarity, 0.75, 1e-12));
+        assert!(close(fit.root_mean_square, 0.25, 1e-12));
+        // (0.25² / 0.25) + (0.25² / 0.75)
+        assert!(close(fit.chi_square, 1.0 / 3.0, 1e-12));
+        assert_eq!(fit.unexpected, 0.0);
+        // The observed key without the expected value
+        let fit = super::fit(&[0.5, 0.3, 0.2], &[0.5, 0.5, 0.0]);
+        assert_eq!(fit.chi_square, f64::INFINITY);
+        assert!(close(fit.unexpected, 0.2, 1e-12));
+        let fit = super::fit(&[], &[]);
+        assert_eq!(fit.root_mean_square, 0.0);
+        assert_eq!(fit.similarity, 1.0);
+        assert_eq!(significance(0.0005), "***");
+        assert_eq!(significance(0.03), "*");
+        assert_eq!(significance(0.5), "");
+    }
+}
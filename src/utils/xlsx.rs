@@ -0,0 +1,208 @@
+//! Spreadsheet (`xlsx`) workbook
+
+use anyhow::Result;
+use metadata::Metadata;
+use polars::prelude::*;
+use rust_xlsxwriter::{Format, Workbook, Worksheet};
+use std::collections::HashSet;
+
+const MAX_SHEET_NAME: usize = 31;
+
+/// Workbook sheet
+#[derive(Clone, Debug)]
+pub struct Sheet {
+    pub name: String,
+    /// Lines written above the table
+    pub header: Vec<String>,
+    /// Flat data frame (no nested columns)
+    pub data: DataFrame,
+    pub precision: usize,
+    /// Float columns shown as percent
+    pub percent: Option<fn(&str) -> bool>,
+}
+
+/// Workbook with the metadata front sheet and the given sheets
+pub fn workbook(metadata: &[Metadata], sheets: &[Sheet]) -> Result<Vec<u8>> {
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+    let mut names = HashSet::new();
+    // Metadata
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(name("Metadata", &mut names))?;
+    for (column, header) in ["Name", "Description", "Authors", "Date", "Version"]
+        .into_iter()
+        .enumerate()
+    {
+        worksheet.write_string_with_format(0, column as _, header, &bold)?;
+    }
+    for (row, meta) in metadata.iter().enumerate() {
+        let row = row as u32 + 1;
+        worksheet.write_string(row, 0, &meta.name)?;
+        worksheet.write_string(row, 1, &meta.description)?;
+        worksheet.write_string(row, 2, meta.authors.join(", "))?;
+        if let Some(date) = meta.date {
+            worksheet.write_string(row, 3, date.to_string())?;
+        }
+        if let Some(version) = &meta.version {
+            worksheet.write_string(row, 4, version.to_string())?;
+        }
+    }
+    worksheet.autofit();
+    // Sheets
+    for sheet in sheets {
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(name(&sheet.name, &mut names))?;
+        write(worksheet, sheet, &bold)?;
+    }
+    Ok(workbook.save_to_buffer()?)
+}
+
+fn write(worksheet: &mut Worksheet, sheet: &Sheet, bold: &Format) -> Result<()> {
+    let mut row = 0;
+    for line in &sheet.header {
+        worksheet.write_string(row, 0, line)?;
+        row += 1;
+    }
+    if row != 0 {
+        row += 1;
+    }
+    let number_format = Format::new().set_num_format(format(sheet.precision, false));
+    let percent_format = Format::new().set_num_format(format(sheet.precision, true));
+    for (column, series) in sheet
+        .data
+        .get_columns()
+        .iter()
+        .map(Column::as_materialized_series)
+        .enumerate()
+    {
+        let column = column as u16;
+        worksheet.write_string_with_format(row, column, series.name().as_str(), bold)?;
+        match series.dtype() {
+            data_type if data_type.is_float() => {
+                let format = match sheet.percent {
+                    Some(percent) if percent(series.name()) => &percent_format,
+                    _ => &number_format,
+                };
+                for (index, value) in series.cast(&DataType::Float64)?.f64()?.iter().enumerate() {
+                    if let Some(value) = value {
+                        worksheet.write_number_with_format(
+                            row + 1 + index as u32,
+                            column,
+                            value,
+                            format,
+                        )?;
+                    }
+                }
+            }
+            data_type if data_type.is_primitive_numeric() => {
+                for (index, value) in series.cast(&DataType::Float64)?.f64()?.iter().enumerate() {
+                    if let Some(value) = value {
+                        worksheet.write_number(row + 1 + index as u32, column, value)?;
+                    }
+                }
+            }
+            DataType::Boolean => {
+                for (index, value) in series.bool()?.iter().enumerate() {
+                    if let Some(value) = value {
+                        worksheet.write_boolean(row + 1 + index as u32, column, value)?;
+                    }
+                }
+            }
+            _ => {
+                for index in 0..series.len() {
+                    let value = series.get(index)?;
+                    if !value.is_null() {
+                        worksheet.write_string(
+                            row + 1 + index as u32,
+                            column,
+                            value.str_value(),
+                        )?;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Number format (`0.00`, `0.00%`)
+fn format(precision: usize, percent: bool) -> String {
+    let mut format = "0".to_owned();
+    if precision > 0 {
+        format.push('.');
+        format.push_str(&"0".repeat(precision));
+    }
+    if percent {
+        format.push('%');
+    }
+    format
+}
+
+/// Unique valid sheet name
+fn name(name: &str, names: &mut HashSet<String>) -> String {
+    let name = name
+        .chars()
+        .filter(|char| !matches!(char, '[' | ']' | ':' | '*' | '?' | '/' | '\\'))
+        .collect::<String>();
+    let name = name.trim_matches(|char: char| char == '\'' || char.is_whitespace());
+    let name = if name.is_empty() { "Sheet" } else { name };
+    let mut unique = name.chars().take(MAX_SHEET_NAME).collect::<String>();
+    let mut index = 1;
+    while !names.insert(unique.to_lowercase()) {
+        index += 1;
+        let suffix = format!(" ({index})");
+        unique = name
+            .chars()
+            .take(MAX_SHEET_NAME - suffix.len())
+            .chain(suffix.chars())
+            .collect();
+    }
+    unique
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() -> Result<()> {
+        // Formats
+        assert_eq!(format(0, false), "0");
+        assert_eq!(format(3, false), "0.000");
+        assert_eq!(format(2, true), "0.00%");
+        // Sheet names
+        let mut names = HashSet::new();
+        assert_eq!(name("Metadata", &mut names), "Metadata");
+        assert_eq!(name("metadata", &mut names), "metadata (2)");
+        assert_eq!(name("'A/B: [C]?'", &mut names), "AB C");
+        assert_eq!(name(" ", &mut names), "Sheet");
+        let long = "A".repeat(40);
+        assert_eq!(name(&long, &mut names), "A".repeat(MAX_SHEET_NAME));
+        let unique = name(&long, &mut names);
+        assert_eq!(unique.chars().count(), MAX_SHEET_NAME);
+        assert!(unique.ends_with(" (2)"));
+        // Workbook (zip archive)
+        let sheet = Sheet {
+            name: "Composition".to_owned(),
+            header: vec!["Composition: SSC".to_owned()],
+            data: df! {
+                "Key" => ["PPO", "POO"],
+                "Value" => [0.25, 0.75],
+                "Count" => [1u32, 2],
+                "Major" => [true, false],
+            }?,
+            precision: 2,
+            percent: Some(|name| name == "Value"),
+        };
+        let metadata = Metadata {
+            version: None,
+            name: "Sample".to_owned(),
+            description: "".to_owned(),
+            authors: vec!["Author".to_owned()],
+            date: None,
+        };
+        let bytes = workbook(&[metadata], &[sheet.clone(), sheet])?;
+        assert!(bytes.starts_with(b"PK"));
+        Ok(())
+    }
+}
@@ -1,77 +1,67 @@
 #[cfg(not(target_arch = "wasm32"))]
-pub use self::native::FileDialog;
+pub use self::native::{FileDialog, save_as};
 #[cfg(target_arch = "wasm32")]
-pub use self::web::FileDialog;
+pub use self::web::{FileDialog, save_as};
 
 #[cfg(not(target_arch = "wasm32"))]
 mod native {
     use super::some;
-    use std::{
-        fs::{read, write},
-        io::Result,
-    };
+    use anyhow::Result;
+    use std::fs::{read, write};
 
     #[derive(Debug, Default)]
     pub struct FileDialog {
-        file: Option<Vec<u8>>,
+        file: Option<(String, Vec<u8>)>,
     }
 
     impl FileDialog {
         pub fn load(&mut self) -> Result<()> {
             if let Some(path) = rfd::FileDialog::new().pick_file() {
-                self.file = Some(read(path)?);
+                let name = path
+                    .file_name()
+                    .map_or_else(Default::default, |name| name.to_string_lossy().into_owned());
+                self.file = Some((name, read(path)?));
             }
             Ok(())
         }
 
         pub fn save<T: AsRef<[u8]>>(&self, file_name: &str, bytes: T) -> Result<()> {
-            if let Some(path) = rfd::FileDialog::new().set_file_name(file_name).save_file() {
-                write(path, bytes)?;
-            }
-            Ok(())
+            save_as(file_name, bytes.as_ref())
         }
 
-        pub fn take(&mut self) -> Option<Vec<u8>> {
+        /// File name and bytes
+        pub fn take(&mut self) -> Option<(String, Vec<u8>)> {
             self.file.take_if(some)
         }
     }
+
+    /// Save bytes to the file chosen in the system dialog
+    pub fn save_as(file_name: &str, bytes: &[u8]) -> Result<()> {
+        if let Some(path) = rfd::FileDialog::new().set_file_name(file_name).save_file() {
+            write(path, bytes)?;
+        }
+        Ok(())
+    }
 }
 
 /// https://stackoverflow.com/questions/3665115/how-to-create-a-file-in-memory-for-user-to-download-but-not-through-server
 #[cfg(target_arch = "wasm32")]
 mod web {
     use super::some;
+    use anyhow::anyhow;
     use js_sys::{Array, ArrayBuffer, Uint8Array};
     use std::{
         rc::Rc,
-        sync::mpsc::{channel, Receiver, Sender},
+        sync::mpsc::{Receiver, Sender, channel},
     };
-    use wasm_bindgen::{prelude::*, JsCast, JsError, JsValue};
+    use wasm_bindgen::{JsCast, JsError, JsValue, prelude::*};
     use web_sys::{
-        window, Blob, BlobPropertyBag, File, FilePropertyBag, FileReader, HtmlAnchorElement,
-        HtmlInputElement, Url,
+        File, FilePropertyBag, FileReader, HtmlAnchorElement, HtmlInputElement, Url, window,
     };
 
-    enum Io {
-        Input(HtmlInputElement),
-        Output(HtmlAnchorElement),
-    }
-
-    // pub enum FileDialog {
-    //     Input {
-    //         tx: Sender<Vec<u8>>,
-    //         rx: Receiver<Vec<u8>>,
-    //         input: HtmlInputElement,
-    //         callback: Option<Closure<dyn FnMut() -> Result<(), JsValue>>>,
-    //     }
-    //     Output {
-    //         output: HtmlAnchorElement,
-    //     }
-    // }
-
     pub struct FileDialog {
-        tx: Sender<Vec<u8>>,
-        rx: Receiver<Vec<u8>>,
+        tx: Sender<(String, Vec<u8>)>,
+        rx: Receiver<(String, Vec<u8>)>,
         input: HtmlInputElement,
         output: HtmlAnchorElement,
         callback: Option<Closure<dyn FnMut() -> Result<(), JsValue>>>,
@@ -125,7 +115,7 @@ mod web {
     }
 
     impl FileDialog {
-        pub fn load(&mut self) -> Result<(), JsValue> {
+        pub fn load(&mut self) -> anyhow::Result<()> {
             if let Some(closure) = self.callback.take_if(some) {
                 self.input
                     .remove_event_listener_with_callback("change", closure.as_ref().unchecked_ref())
@@ -134,16 +124,16 @@ mod web {
             }
             let tx = self.tx.clone();
             let input = self.input.clone();
-            let multiple = self.input.multiple();
             let callback = Closure::once(move || {
                 if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                    let name = file.name();
                     let reader = Rc::new(FileReader::new()?);
                     let onload: Closure<dyn FnMut() -> Result<(), JsValue>> = Closure::once({
                         let reader = reader.clone();
                         move || {
                             let array_buffer = reader.result()?.dyn_into::<ArrayBuffer>()?;
                             let bytes = Uint8Array::new(&array_buffer).to_vec();
-                            tx.send(bytes).map_err(JsError::from)?;
+                            tx.send((name, bytes)).map_err(JsError::from)?;
                             Ok(())
                         }
                     });
@@ -162,8 +152,13 @@ mod web {
         }
 
         // save<T: AsRef<[u8]>>(&self, file_name: &str, bytes: T)
-        pub fn save<T: AsRef<[u8]>>(&self, file_name: &str, content: T) -> Result<(), JsValue> {
-            if let Some(window) = window() {
+        pub fn save<T: AsRef<[u8]>>(&self, file_name: &str, content: T) -> anyhow::Result<()> {
+            self.download(file_name, content.as_ref())
+                .map_err(|error| anyhow!("{error:?}"))
+        }
+
+        fn download(&self, file_name: &str, content: &[u8]) -> Result<(), JsValue> {
+            if window().is_some() {
                 // let array = Uint8Array::from(&*content);
                 // let file_bits = Array::new();
                 // file_bits.push(&array.buffer());
@@ -173,7 +168,7 @@ mod web {
                 // ));
 
                 // [see](https://stackoverflow.com/questions/69556755/web-sysurlcreate-object-url-with-blobblob-not-formatting-binary-data-co)
-                let bytes = Uint8Array::from(content.as_ref());
+                let bytes = Uint8Array::from(content);
                 let array = Array::new();
                 array.push(&bytes.buffer());
 
@@ -188,16 +183,22 @@ mod web {
                 // )?;
                 let url = Url::create_object_url_with_blob(&file)?;
                 self.output.set_href(&url);
-                self.output.set_download(&file_name);
+                self.output.set_download(file_name);
                 self.output.click();
             }
             Ok(())
         }
 
-        pub fn take(&self) -> Option<Vec<u8>> {
+        /// File name and bytes
+        pub fn take(&self) -> Option<(String, Vec<u8>)> {
             self.rx.try_recv().ok()
         }
     }
+
+    /// Download bytes as a file
+    pub fn save_as(file_name: &str, bytes: &[u8]) -> anyhow::Result<()> {
+        crate::utils::save_bytes(file_name, bytes)
+    }
 }
 
 fn some<T>(_: &mut T) -> bool {
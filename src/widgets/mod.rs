@@ -1,4 +1,4 @@
-// pub(super) use self::file_dialog::FileDialog;
+pub(crate) use self::file_dialog::{FileDialog, save_as};
 
-// mod file_dialog;
+mod file_dialog;
 // mod variable_list;